@@ -1,19 +1,101 @@
-use crate::fractal_params::FractalParams;
-use crate::fractal_types::{MandelbrotSet, JuliaSet, BurningShip, Tricorn, NewtonSet};
+use crate::fractal_params::{FractalParams, ParameterFile};
+use crate::formula::{CompiledFormula, FormulaError};
+use crate::hybrid::HybridSet;
+use crate::buddhabrot::{BuddhabrotRenderer, BuddhabrotSettings};
+use crate::ifs::{Ifs, IfsPreset, IfsSettings, IfsView};
+use crate::flame::Flame;
+use crate::attractor::AttractorRenderer;
+use crate::kleinian::KleinianRenderer;
+use crate::raymarch::{RayMarcher, RayMarchSettings, Shape};
+use crate::mesh::{heightmap_mesh, MeshSettings};
+use crate::lsystem::{LSystem, LSystemError};
+use crate::flame_io::{load_flames, FlameError, ImportedFlame};
+use crate::fractal_types::{MandelbrotSet, JuliaSet, Multibrot, BurningShip, Tricorn, NewtonSet, NovaSet, PhoenixSet, MagnetSet, AbsFormula, TranscendentalSet, McMullenSet, LyapunovSet};
 use crate::color_schemes::{ColorScheme, ClassicScheme, FireScheme, OceanScheme, RainbowScheme, GrayscaleScheme};
-use crate::fractal_calculator::FractalCalculator;
+use crate::fractal_calculator::{FractalCalculator, IterationData, QualityLevel};
+use crate::color_cycle::{export_cycle_gif, ColorCycle};
+use crate::gradient::GradientScheme;
+use crate::palette_editor::PaletteEditor;
 use crate::ui::UserInterface;
 use eframe::egui;
 use num::Complex;
 use image::{ImageBuffer, Rgb};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub enum ActiveFractal {
     Mandelbrot(FractalCalculator<MandelbrotSet>),
     Julia(FractalCalculator<JuliaSet>),
+    Multibrot(FractalCalculator<Multibrot>),
     BurningShip(FractalCalculator<BurningShip>),
     Tricorn(FractalCalculator<Tricorn>),
     Newton(FractalCalculator<NewtonSet>),
+    Nova(FractalCalculator<NovaSet>),
+    Phoenix(FractalCalculator<PhoenixSet>),
+    Magnet(FractalCalculator<MagnetSet>),
+    AbsVariant(FractalCalculator<AbsFormula>),
+    Transcendental(FractalCalculator<TranscendentalSet>),
+    McMullen(FractalCalculator<McMullenSet>),
+    Lyapunov(FractalCalculator<LyapunovSet>),
+    Formula(FractalCalculator<CompiledFormula>),
+    Hybrid(FractalCalculator<HybridSet>),
+    Attractor(AttractorRenderer),
+    Kleinian(KleinianRenderer),
+}
+
+impl ActiveFractal {
+    /// Nom stable du type, enregistré dans les fichiers de paramètres.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ActiveFractal::Mandelbrot(_) => "mandelbrot",
+            ActiveFractal::Julia(_) => "julia",
+            ActiveFractal::Multibrot(_) => "multibrot",
+            ActiveFractal::BurningShip(_) => "burning_ship",
+            ActiveFractal::Tricorn(_) => "tricorn",
+            ActiveFractal::Newton(_) => "newton",
+            ActiveFractal::Nova(_) => "nova",
+            ActiveFractal::Phoenix(_) => "phoenix",
+            ActiveFractal::Magnet(_) => "magnet",
+            ActiveFractal::AbsVariant(_) => "abs_variant",
+            ActiveFractal::Transcendental(_) => "transcendental",
+            ActiveFractal::McMullen(_) => "mcmullen",
+            ActiveFractal::Lyapunov(_) => "lyapunov",
+            ActiveFractal::Formula(_) => "formula",
+            ActiveFractal::Hybrid(_) => "hybrid",
+            ActiveFractal::Attractor(_) => "attractor",
+            ActiveFractal::Kleinian(_) => "kleinian",
+        }
+    }
+
+    /// Fractales tracées dans leur propre plan (exposants de Lyapunov,
+    /// groupes de Klein, cadre d'un attracteur) plutôt que dans le plan des z ou des c.
+    pub fn has_own_plane(&self) -> bool {
+        matches!(self, ActiveFractal::Lyapunov(_) | ActiveFractal::Kleinian(_) | ActiveFractal::Attractor(_))
+    }
+
+    /// Calculateur du type `kind` ; `None` si le type est inconnu ou si la
+    /// formule ne compile pas.
+    pub fn build(kind: &str, params: FractalParams) -> Option<ActiveFractal> {
+        Some(match kind {
+            "mandelbrot" => ActiveFractal::Mandelbrot(FractalCalculator::<MandelbrotSet>::new_mandelbrot(params)),
+            "julia" => ActiveFractal::Julia(FractalCalculator::<JuliaSet>::new_julia(params.clone(), params.julia_c)),
+            "multibrot" => ActiveFractal::Multibrot(FractalCalculator::<Multibrot>::new_multibrot(params)),
+            "burning_ship" => ActiveFractal::BurningShip(FractalCalculator::<BurningShip>::new_burning_ship(params)),
+            "tricorn" => ActiveFractal::Tricorn(FractalCalculator::<Tricorn>::new_tricorn(params)),
+            "newton" => ActiveFractal::Newton(FractalCalculator::<NewtonSet>::new_newton(params)),
+            "nova" => ActiveFractal::Nova(FractalCalculator::<NovaSet>::new_nova(params)),
+            "phoenix" => ActiveFractal::Phoenix(FractalCalculator::<PhoenixSet>::new_phoenix(params)),
+            "magnet" => ActiveFractal::Magnet(FractalCalculator::<MagnetSet>::new_magnet(params)),
+            "abs_variant" => ActiveFractal::AbsVariant(FractalCalculator::<AbsFormula>::new_abs_variant(params)),
+            "transcendental" => ActiveFractal::Transcendental(FractalCalculator::<TranscendentalSet>::new_transcendental(params)),
+            "mcmullen" => ActiveFractal::McMullen(FractalCalculator::<McMullenSet>::new_mcmullen(params)),
+            "lyapunov" => ActiveFractal::Lyapunov(FractalCalculator::<LyapunovSet>::new_lyapunov(params)),
+            "formula" => ActiveFractal::Formula(FractalCalculator::<CompiledFormula>::new_formula(params).ok()?),
+            "hybrid" => ActiveFractal::Hybrid(FractalCalculator::<HybridSet>::new_hybrid(params).ok()?),
+            "attractor" => ActiveFractal::Attractor(AttractorRenderer::new(params)),
+            "kleinian" => ActiveFractal::Kleinian(KleinianRenderer::new(params)),
+            _ => return None,
+        })
+    }
 }
 
 pub enum ActiveColorScheme {
@@ -22,6 +104,7 @@ pub enum ActiveColorScheme {
     Ocean(OceanScheme),
     Rainbow(RainbowScheme),
     Grayscale(GrayscaleScheme),
+    Gradient(GradientScheme),
 }
 
 pub enum ActiveColorSchemeType {
@@ -32,27 +115,140 @@ pub enum ActiveColorSchemeType {
     Grayscale,
 }
 
+/// Contenu de la zone d'image : la fractale active, ou une vue à part qui la
+/// remplace, chacune avec sa fenêtre de réglages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum View {
+    Fractal,
+    /// La fractale reste affichée tant que le rendu n'est pas démarré.
+    Buddhabrot,
+    Ifs,
+    Flame,
+    LSystem,
+    RayMarch,
+}
+
 pub struct FractalApp {
     pub params: FractalParams,
     pub active_fractal: ActiveFractal,
     pub active_color_scheme: ActiveColorScheme,
     pub image_data: Vec<u8>,
+    pub iteration_data: Option<IterationData>,
     pub need_update: bool,
+    pub need_recolor: bool,
+    pub color_cycle: ColorCycle,
+    pub color_cycle_error: Option<String>,
+    /// Échec du dernier enregistrement ou chargement de paramètres.
+    pub params_error: Option<String>,
+    /// Centre et zoom du plan complexe pendant l'affichage d'une fractale à
+    /// plan propre.
+    pub saved_view: Option<(Complex<f64>, f64)>,
     pub save_dialog: Option<rfd::FileDialog>,
+    pub palette_editor: PaletteEditor,
+    pub newton_coefficients: String,
+    pub newton_error: Option<String>,
+    /// Texte en cours d'édition de la formule, appliqué seulement s'il compile.
+    pub formula_source: String,
+    pub formula_error: Option<FormulaError>,
+    pub view: View,
+    /// Rendu Buddhabrot en cours ; tant qu'il existe, il remplace l'image.
+    pub buddhabrot: Option<BuddhabrotRenderer>,
+    pub buddhabrot_settings: BuddhabrotSettings,
+    pub buddhabrot_running: bool,
+    /// Système de fonctions itérées de la vue `View::Ifs` ; la fenêtre reste
+    /// fixe pendant l'édition.
+    pub ifs: Ifs,
+    pub ifs_settings: IfsSettings,
+    pub ifs_view: Option<IfsView>,
+    /// Flamme de la vue `View::Flame`, choisie parmi celles du dernier
+    /// fichier importé.
+    pub flame: Flame,
+    pub flame_library: Vec<ImportedFlame>,
+    pub flame_message: Option<String>,
+    pub lsystem: LSystem,
+    pub lsystem_error: Option<LSystemError>,
+    /// Fractale 3D en lancer de rayons de la vue `View::RayMarch` ; le rendu
+    /// s'affine d'un lot à chaque image.
+    pub raymarch_settings: RayMarchSettings,
+    pub raymarcher: Option<RayMarcher>,
+    pub raymarch_error: Option<String>,
+    /// Rotation animée des coupes 4D, en degrés par seconde (0 : arrêtée),
+    /// dans le plan `raymarch_spin_plane` (0 : xw, 1 : yw, 2 : zw).
+    pub raymarch_spin: f64,
+    pub raymarch_spin_plane: usize,
+    pub mesh_settings: MeshSettings,
+    pub show_mesh_export: bool,
+    /// Résultat du dernier export en relief.
+    pub mesh_status: Option<String>,
+}
+
+impl ActiveColorScheme {
+    pub fn as_scheme(&self) -> &(dyn ColorScheme + Sync) {
+        match self {
+            ActiveColorScheme::Classic(scheme) => scheme,
+            ActiveColorScheme::Fire(scheme) => scheme,
+            ActiveColorScheme::Ocean(scheme) => scheme,
+            ActiveColorScheme::Rainbow(scheme) => scheme,
+            ActiveColorScheme::Grayscale(scheme) => scheme,
+            ActiveColorScheme::Gradient(scheme) => scheme,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            ActiveColorScheme::Classic(_) => "Classic",
+            ActiveColorScheme::Fire(_) => "Fire",
+            ActiveColorScheme::Ocean(_) => "Ocean",
+            ActiveColorScheme::Rainbow(_) => "Rainbow",
+            ActiveColorScheme::Grayscale(_) => "Grayscale",
+            ActiveColorScheme::Gradient(gradient) => &gradient.name,
+        }
+    }
 }
 
 impl Default for FractalApp {
     fn default() -> Self {
         let params = FractalParams::default();
         Self {
+            formula_source: params.formula.clone(),
             active_fractal: ActiveFractal::Mandelbrot(
                 FractalCalculator::<MandelbrotSet>::new_mandelbrot(params.clone())
             ),
             active_color_scheme: ActiveColorScheme::Classic(ClassicScheme),
             params,
             image_data: Vec::new(),
+            iteration_data: None,
             need_update: true,
+            need_recolor: false,
+            color_cycle: ColorCycle::default(),
+            color_cycle_error: None,
+            params_error: None,
+            saved_view: None,
             save_dialog: None,
+            palette_editor: PaletteEditor::default(),
+            newton_coefficients: String::from("-1, 0, 0, 1"),
+            newton_error: None,
+            formula_error: None,
+            view: View::Fractal,
+            buddhabrot: None,
+            buddhabrot_settings: BuddhabrotSettings::default(),
+            buddhabrot_running: false,
+            ifs: IfsPreset::BarnsleyFern.ifs(),
+            ifs_settings: IfsSettings::default(),
+            ifs_view: None,
+            flame: Flame::default(),
+            flame_library: Vec::new(),
+            flame_message: None,
+            lsystem: LSystem::default(),
+            lsystem_error: None,
+            raymarch_settings: RayMarchSettings::default(),
+            raymarcher: None,
+            raymarch_error: None,
+            raymarch_spin: 0.0,
+            raymarch_spin_plane: 0,
+            mesh_settings: MeshSettings::default(),
+            show_mesh_export: false,
+            mesh_status: None,
         }
     }
 }
@@ -70,24 +266,90 @@ impl FractalApp {
             self.image_data = vec![0; required_size];
         }
 
-        let color_scheme: &(dyn ColorScheme + Sync) = match &self.active_color_scheme {
-            ActiveColorScheme::Classic(scheme) => scheme,
-            ActiveColorScheme::Fire(scheme) => scheme,
-            ActiveColorScheme::Ocean(scheme) => scheme,
-            ActiveColorScheme::Rainbow(scheme) => scheme,
-            ActiveColorScheme::Grayscale(scheme) => scheme,
-        };
+        match self.view {
+            View::Flame => {
+                self.image_data = self.flame.render(self.params.size);
+                return;
+            }
+            View::LSystem => {
+                self.render_lsystem();
+                return;
+            }
+            View::Ifs => {
+                self.render_ifs(false);
+                return;
+            }
+            View::RayMarch => {
+                self.start_raymarch();
+                return;
+            }
+            // La vue a changé : l'histogramme accumulé n'est plus valable.
+            View::Buddhabrot if self.buddhabrot.is_some() => {
+                self.reset_buddhabrot();
+                return;
+            }
+            View::Buddhabrot | View::Fractal => {}
+        }
 
-        let new_data = match &self.active_fractal {
-            ActiveFractal::Mandelbrot(calc) => calc.generate(color_scheme),
-            ActiveFractal::Julia(calc) => calc.generate(color_scheme),
-            ActiveFractal::BurningShip(calc) => calc.generate(color_scheme),
-            ActiveFractal::Tricorn(calc) => calc.generate(color_scheme),
-            ActiveFractal::Newton(calc) => calc.generate(color_scheme),
-        };
+        self.iteration_data = Some(match &self.active_fractal {
+            ActiveFractal::Mandelbrot(calc) => calc.compute(),
+            ActiveFractal::Julia(calc) => calc.compute(),
+            ActiveFractal::Multibrot(calc) => calc.compute(),
+            ActiveFractal::BurningShip(calc) => calc.compute(),
+            ActiveFractal::Tricorn(calc) => calc.compute(),
+            ActiveFractal::Newton(calc) => calc.compute(),
+            ActiveFractal::Nova(calc) => calc.compute(),
+            ActiveFractal::Phoenix(calc) => calc.compute(),
+            ActiveFractal::Magnet(calc) => calc.compute(),
+            ActiveFractal::AbsVariant(calc) => calc.compute(),
+            ActiveFractal::Transcendental(calc) => calc.compute(),
+            ActiveFractal::McMullen(calc) => calc.compute(),
+            ActiveFractal::Lyapunov(calc) => calc.compute(),
+            ActiveFractal::Formula(calc) => calc.compute(),
+            ActiveFractal::Hybrid(calc) => calc.compute(),
+            ActiveFractal::Attractor(renderer) => renderer.compute(),
+            ActiveFractal::Kleinian(renderer) => renderer.compute(),
+        });
+        self.recolor();
+    }
+
+    /// Recolorise les itérations déjà calculées (changement de palette, cycle).
+    pub fn recolor(&mut self) {
+        match self.view {
+            // Une flamme a sa propre palette.
+            View::Flame => {}
+            View::Ifs => self.render_ifs(false),
+            View::LSystem => self.render_lsystem(),
+            // Seule la palette change : les rayons déjà lancés sont gardés.
+            View::RayMarch => self.show_raymarch_image(),
+            View::Buddhabrot if self.buddhabrot.is_some() => {}
+            View::Buddhabrot | View::Fractal => self.recolor_fractal(),
+        }
+        self.need_recolor = false;
+    }
 
-        if new_data.len() == required_size {
-            self.image_data = new_data;
+    fn recolor_fractal(&mut self) {
+        let required_size = self.params.size.0 * self.params.size.1 * 4;
+        if let Some(data) = &self.iteration_data {
+            let color_scheme = self.active_color_scheme.as_scheme();
+            let new_data = data.colorize(color_scheme, self.color_cycle.offset);
+            if new_data.len() == required_size {
+                self.image_data = new_data;
+            }
+        }
+    }
+
+    pub fn export_color_cycle(&self, path: &Path, frame_count: usize, frame_delay_ms: u32) -> image::ImageResult<()> {
+        match &self.iteration_data {
+            Some(data) => export_cycle_gif(
+                path,
+                data,
+                self.active_color_scheme.as_scheme(),
+                &self.color_cycle,
+                frame_count,
+                frame_delay_ms,
+            ),
+            None => Ok(()),
         }
     }
 
@@ -107,11 +369,33 @@ impl FractalApp {
         img.save(path).expect("Échec de la sauvegarde de l'image");
     }
 
+    /// Choisir une fractale quitte la vue à part éventuellement affichée.
     pub fn set_fractal_type(&mut self, fractal_type: ActiveFractal) {
+        if self.view != View::Fractal {
+            self.close_view();
+        }
+        let leaving_own_plane = self.active_fractal.has_own_plane() && !fractal_type.has_own_plane();
         self.active_fractal = fractal_type;
+        if leaving_own_plane {
+            if let Some((center, zoom)) = self.saved_view.take() {
+                self.params.center = center;
+                self.params.zoom = zoom;
+                self.rebuild_fractal();
+            }
+        }
         self.need_update = true;
     }
 
+    /// Cadre une fractale à plan propre ; la vue courante du plan complexe
+    /// est mise de côté et rétablie en revenant à une fractale ordinaire.
+    pub fn set_own_plane_view(&mut self, center: Complex<f64>, zoom: f64) {
+        if !self.active_fractal.has_own_plane() {
+            self.saved_view = Some((self.params.center, self.params.zoom));
+        }
+        self.params.center = center;
+        self.params.zoom = zoom;
+    }
+
     pub fn set_color_scheme(&mut self, scheme_type: ActiveColorSchemeType) {
         self.active_color_scheme = match scheme_type {
             ActiveColorSchemeType::Classic => ActiveColorScheme::Classic(ClassicScheme),
@@ -120,9 +404,79 @@ impl FractalApp {
             ActiveColorSchemeType::Rainbow => ActiveColorScheme::Rainbow(RainbowScheme),
             ActiveColorSchemeType::Grayscale => ActiveColorScheme::Grayscale(GrayscaleScheme),
         };
+        self.need_recolor = true;
+    }
+
+    pub fn set_gradient(&mut self, gradient: GradientScheme) {
+        self.active_color_scheme = ActiveColorScheme::Gradient(gradient);
+        self.need_recolor = true;
+    }
+
+    /// Ouvre l'éditeur sur la palette active, convertie en dégradé si besoin.
+    pub fn open_palette_editor(&mut self) {
+        let gradient = match &self.active_color_scheme {
+            ActiveColorScheme::Gradient(gradient) => gradient.clone(),
+            other => GradientScheme::from_scheme(other.name(), other.as_scheme(), 16),
+        };
+        self.palette_editor.open_with(gradient);
+    }
+
+    /// Reconstruit le calculateur de Newton, ou de Nova qui partage ses
+    /// racines, après modification du polynôme.
+    pub fn update_newton(&mut self, quality: QualityLevel) {
+        self.active_fractal = if matches!(self.active_fractal, ActiveFractal::Nova(_)) {
+            let mut calc = FractalCalculator::<NovaSet>::new_nova(self.params.clone());
+            calc.set_quality_level(quality);
+            ActiveFractal::Nova(calc)
+        } else {
+            let mut calc = FractalCalculator::<NewtonSet>::new_newton(self.params.clone());
+            calc.set_quality_level(quality);
+            ActiveFractal::Newton(calc)
+        };
         self.need_update = true;
     }
 
+    /// Compile le texte de l'éditeur de formule et l'active s'il est valide.
+    pub fn apply_formula(&mut self) {
+        match CompiledFormula::compile(&self.formula_source) {
+            Ok(_) => {
+                self.params.formula = self.formula_source.clone();
+                self.formula_error = None;
+                if let Some(fractal) = ActiveFractal::build("formula", self.params.clone()) {
+                    self.set_fractal_type(fractal);
+                }
+            }
+            Err(error) => self.formula_error = Some(error),
+        }
+    }
+
+    /// Enregistre le type de fractale et ses paramètres, formule comprise.
+    pub fn save_params(&self, path: &Path) -> std::io::Result<()> {
+        ParameterFile {
+            fractal: self.active_fractal.kind().to_string(),
+            params: self.params.clone(),
+        }
+        .save(path)
+    }
+
+    pub fn load_params(&mut self, path: &Path) -> std::io::Result<()> {
+        let file = ParameterFile::load(path)?;
+        let mut params = file.params;
+        params.size = self.params.size;
+        let fractal = ActiveFractal::build(&file.fractal, params.clone()).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("type de fractale « {} » invalide", file.fractal),
+            )
+        })?;
+        self.formula_source = params.formula.clone();
+        self.formula_error = None;
+        self.params = params;
+        self.saved_view = None;
+        self.set_fractal_type(fractal);
+        Ok(())
+    }
+
     pub fn handle_zoom(&mut self, mouse_x: f32, mouse_y: f32, zoom_factor: f64) {
         let fx = (mouse_x as f64 / self.params.size.0 as f64 - 0.5) / self.params.zoom + self.params.center.re;
         let fy = (mouse_y as f64 / self.params.size.1 as f64 - 0.5) / self.params.zoom + self.params.center.im;
@@ -133,25 +487,205 @@ impl FractalApp {
             fy - (mouse_y as f64 / self.params.size.1 as f64 - 0.5) / self.params.zoom
         );
 
-        let params = self.params.clone();
-        self.active_fractal = match &self.active_fractal {
-            ActiveFractal::Mandelbrot(_) => {
-                ActiveFractal::Mandelbrot(FractalCalculator::<MandelbrotSet>::new_mandelbrot(params))
-            },
-            ActiveFractal::Julia(_) => {
-                ActiveFractal::Julia(FractalCalculator::<JuliaSet>::new_julia(params, self.params.julia_c))
-            },
-            ActiveFractal::BurningShip(_) => {
-                ActiveFractal::BurningShip(FractalCalculator::<BurningShip>::new_burning_ship(params))
-            },
-            ActiveFractal::Tricorn(_) => {
-                ActiveFractal::Tricorn(FractalCalculator::<Tricorn>::new_tricorn(params))
-            },
-            ActiveFractal::Newton(_) => {
-                ActiveFractal::Newton(FractalCalculator::<NewtonSet>::new_newton(params))
-            },
-        };
+        self.rebuild_fractal();
+    }
+
+    /// Reconstruit le calculateur actif à partir des paramètres courants.
+    pub fn rebuild_fractal(&mut self) {
+        if let Some(fractal) = ActiveFractal::build(self.active_fractal.kind(), self.params.clone()) {
+            self.active_fractal = fractal;
+        }
         
         self.need_update = true;
     }
+
+    /// Démarre (ou redémarre de zéro) un rendu Buddhabrot sur la vue courante.
+    pub fn start_buddhabrot(&mut self) {
+        self.buddhabrot = Some(BuddhabrotRenderer::new(self.buddhabrot_settings.clone(), &self.params));
+        self.buddhabrot_running = true;
+    }
+
+    pub fn reset_buddhabrot(&mut self) {
+        let running = self.buddhabrot_running;
+        self.start_buddhabrot();
+        self.buddhabrot_running = running;
+        if let Some(renderer) = &self.buddhabrot {
+            self.image_data = renderer.image();
+        }
+    }
+
+    /// Remplace la vue courante ; les rendus progressifs (Buddhabrot, 3D) de
+    /// l'ancienne vue sont abandonnés.
+    pub fn open_view(&mut self, view: View) {
+        self.buddhabrot = None;
+        self.buddhabrot_running = false;
+        self.raymarcher = None;
+        self.view = view;
+        self.need_update = true;
+    }
+
+    /// Revient à la fractale active.
+    pub fn close_view(&mut self) {
+        self.open_view(View::Fractal);
+    }
+
+    /// Trace un lot si le rendu n'est pas en pause, puis affiche l'état courant.
+    pub fn step_buddhabrot(&mut self) {
+        if let Some(renderer) = &mut self.buddhabrot {
+            if self.buddhabrot_running {
+                renderer.run_batch();
+            }
+            if renderer.size() == self.params.size {
+                self.image_data = renderer.image();
+            }
+        }
+    }
+
+    /// Remplace le système et recadre la vue sur son attracteur.
+    pub fn set_ifs(&mut self, ifs: Ifs) {
+        self.ifs = ifs;
+        self.ifs_view = None;
+        self.need_update = true;
+    }
+
+    /// Fenêtre courante, ajustée à la taille de l'image.
+    pub fn ifs_view(&mut self) -> IfsView {
+        let bounds = *self.ifs_view.get_or_insert_with(|| self.ifs.bounds());
+        bounds.fit(self.params.size.0, self.params.size.1)
+    }
+
+    /// `preview` réduit le nombre de points, pour le glissement des triangles.
+    pub fn render_ifs(&mut self, preview: bool) {
+        let view = self.ifs_view();
+        let mut settings = self.ifs_settings.clone();
+        if preview {
+            settings.samples = (settings.samples / 10).max(10_000);
+        }
+        self.image_data = crate::ifs::render(
+            &self.ifs,
+            &view,
+            self.active_color_scheme.as_scheme(),
+            &settings,
+            self.params.size,
+        );
+    }
+
+    /// Importe un fichier `.flame` et affiche sa première flamme.
+    pub fn import_flames(&mut self, path: &Path) -> Result<(), FlameError> {
+        self.flame_library = load_flames(path)?;
+        self.select_flame(0);
+        Ok(())
+    }
+
+    pub fn select_flame(&mut self, index: usize) {
+        if let Some(imported) = self.flame_library.get(index) {
+            self.flame = imported.flame.clone();
+            self.flame_message = (!imported.unsupported.is_empty())
+                .then(|| format!("Variations ignorées : {}", imported.unsupported.join(", ")));
+            self.need_update = true;
+        }
+    }
+
+    /// Dérive et trace le L-système ; en cas d'erreur l'image précédente reste affichée.
+    pub fn render_lsystem(&mut self) {
+        match self.lsystem.segments() {
+            Ok(segments) => {
+                self.lsystem_error = None;
+                self.image_data = crate::lsystem::rasterize(&segments, self.active_color_scheme.as_scheme(), self.params.size);
+            }
+            Err(error) => self.lsystem_error = Some(error),
+        }
+    }
+
+    /// Export vectoriel, cadré comme l'image affichée.
+    pub fn save_lsystem_svg(&self, path: &Path) -> Result<(), LSystemError> {
+        let segments = self.lsystem.segments()?;
+        std::fs::write(path, crate::lsystem::to_svg(&segments, self.active_color_scheme.as_scheme(), self.params.size))
+            .map_err(|e| LSystemError::Export(e.to_string()))
+    }
+
+    /// Rendu à la taille de référence de la flamme, enregistré en PNG.
+    pub fn save_flame_render(&self, path: &Path) -> image::ImageResult<()> {
+        let (width, height) = self.flame.size;
+        let pixels = self.flame.render((width, height));
+        let img = ImageBuffer::<image::Rgba<u8>, _>::from_raw(width as u32, height as u32, pixels)
+            .expect("taille de l'image incohérente");
+        img.save(path)
+    }
+
+    /// Recommence le rendu 3D de zéro, avec les réglages courants.
+    pub fn start_raymarch(&mut self) {
+        self.raymarcher = Some(RayMarcher::new(self.raymarch_settings.clone(), self.params.size));
+        self.show_raymarch_image();
+    }
+
+    /// Lance un lot de rayons si l'image n'est pas terminée, puis l'affiche.
+    pub fn step_raymarch(&mut self) {
+        if let Some(renderer) = &mut self.raymarcher {
+            if !renderer.is_done() {
+                renderer.run_batch();
+            }
+        }
+        self.show_raymarch_image();
+    }
+
+    /// Fait avancer la rotation 4D de `dt` secondes ; renvoie vrai si la
+    /// coupe a changé.
+    pub fn advance_raymarch_spin(&mut self, dt: f64) -> bool {
+        let plane = self.raymarch_spin_plane.min(2);
+        match &mut self.raymarch_settings.shape {
+            Shape::Julia4D { slice, .. } if self.view == View::RayMarch && self.raymarch_spin != 0.0 => {
+                slice.rotation[plane] = (slice.rotation[plane] + self.raymarch_spin * dt).rem_euclid(360.0);
+                self.need_update = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Tour complet de la coupe 4D dans le plan de rotation, enregistré en
+    /// `frame_count` images PNG numérotées dans `directory`.
+    pub fn export_raymarch_rotation(&self, directory: &Path, frame_count: usize) -> image::ImageResult<()> {
+        let plane = self.raymarch_spin_plane.min(2);
+        for frame in 0..frame_count {
+            let mut settings = self.raymarch_settings.clone();
+            if let Shape::Julia4D { slice, .. } = &mut settings.shape {
+                slice.rotation[plane] += 360.0 * frame as f64 / frame_count as f64;
+            }
+            let mut renderer = RayMarcher::new(settings, self.params.size);
+            while !renderer.is_done() {
+                renderer.run_batch();
+            }
+            let (width, height) = renderer.size();
+            let pixels = renderer.image(self.active_color_scheme.as_scheme());
+            let img = ImageBuffer::<image::Rgba<u8>, _>::from_raw(width as u32, height as u32, pixels)
+                .expect("taille de l'image incohérente");
+            img.save(directory.join(format!("rotation_{:04}.png", frame)))?;
+        }
+        Ok(())
+    }
+
+    /// Les itérations affichées existent (l'image n'est pas remplacée par
+    /// un rendu à part, IFS, flamme…) et peuvent être exportées en relief.
+    pub fn can_export_mesh(&self) -> bool {
+        self.iteration_data.is_some() && self.buddhabrot.is_none() && matches!(self.view, View::Fractal | View::Buddhabrot)
+    }
+
+    /// Relief des itérations affichées, coloré par la palette active.
+    pub fn export_mesh(&self, path: &Path) -> std::io::Result<()> {
+        let data = self
+            .iteration_data
+            .as_ref()
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "aucune itération calculée"))?;
+        let mesh = heightmap_mesh(data, self.active_color_scheme.as_scheme(), self.color_cycle.offset, &self.mesh_settings);
+        mesh.save(path, self.mesh_settings.format)
+    }
+
+    fn show_raymarch_image(&mut self) {
+        if let Some(renderer) = &self.raymarcher {
+            if renderer.size() == self.params.size {
+                self.image_data = renderer.image(self.active_color_scheme.as_scheme());
+            }
+        }
+    }
 } 
\ No newline at end of file
@@ -0,0 +1,385 @@
+use crate::density::DensityBuffer;
+use crate::fractal_calculator::IterationData;
+use crate::fractal_params::FractalParams;
+use crate::rng::Rng;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AttractorKind {
+    Clifford,
+    DeJong,
+    Henon,
+    Ikeda,
+    GumowskiMira,
+    Lorenz,
+    Rossler,
+    Aizawa,
+}
+
+impl AttractorKind {
+    pub const ALL: [AttractorKind; 8] = [
+        AttractorKind::Clifford,
+        AttractorKind::DeJong,
+        AttractorKind::Henon,
+        AttractorKind::Ikeda,
+        AttractorKind::GumowskiMira,
+        AttractorKind::Lorenz,
+        AttractorKind::Rossler,
+        AttractorKind::Aizawa,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AttractorKind::Clifford => "Clifford",
+            AttractorKind::DeJong => "Peter de Jong",
+            AttractorKind::Henon => "Hénon",
+            AttractorKind::Ikeda => "Ikeda",
+            AttractorKind::GumowskiMira => "Gumowski–Mira",
+            AttractorKind::Lorenz => "Lorenz",
+            AttractorKind::Rossler => "Rössler",
+            AttractorKind::Aizawa => "Aizawa",
+        }
+    }
+
+    /// Flot continu en 3D, intégré par Runge–Kutta et projeté.
+    pub fn is_flow(&self) -> bool {
+        matches!(self, AttractorKind::Lorenz | AttractorKind::Rossler | AttractorKind::Aizawa)
+    }
+
+    pub fn parameter_names(&self) -> &'static [&'static str] {
+        match self {
+            AttractorKind::Clifford | AttractorKind::DeJong => &["a", "b", "c", "d"],
+            AttractorKind::Henon => &["a", "b"],
+            AttractorKind::Ikeda => &["u"],
+            AttractorKind::GumowskiMira => &["a", "σ", "μ"],
+            AttractorKind::Lorenz => &["σ", "ρ", "β"],
+            AttractorKind::Rossler => &["a", "b", "c"],
+            AttractorKind::Aizawa => &["a", "b", "c", "d", "e", "f"],
+        }
+    }
+
+    /// Paramètres classiques, qui donnent un attracteur étrange.
+    pub fn default_parameters(&self) -> Vec<f64> {
+        match self {
+            AttractorKind::Clifford => vec![-1.4, 1.6, 1.0, 0.7],
+            AttractorKind::DeJong => vec![1.4, -2.3, 2.4, -2.1],
+            AttractorKind::Henon => vec![1.4, 0.3],
+            AttractorKind::Ikeda => vec![0.9],
+            AttractorKind::GumowskiMira => vec![0.008, 0.05, -0.496],
+            AttractorKind::Lorenz => vec![10.0, 28.0, 8.0 / 3.0],
+            AttractorKind::Rossler => vec![0.2, 0.2, 5.7],
+            AttractorKind::Aizawa => vec![0.95, 0.7, 0.6, 3.5, 0.25, 0.1],
+        }
+    }
+
+    /// Intervalle de tirage des paramètres pour la recherche aléatoire.
+    fn parameter_range(&self, index: usize) -> (f64, f64) {
+        match self {
+            AttractorKind::Clifford | AttractorKind::DeJong => (-3.0, 3.0),
+            AttractorKind::Henon => [(0.8, 1.5), (-0.4, 0.4)][index],
+            AttractorKind::Ikeda => (0.6, 1.0),
+            AttractorKind::GumowskiMira => [(-0.05, 0.05), (0.0, 0.2), (-1.0, 1.0)][index],
+            AttractorKind::Lorenz => [(5.0, 20.0), (20.0, 60.0), (1.0, 4.0)][index],
+            AttractorKind::Rossler => [(0.05, 0.4), (0.05, 2.0), (3.0, 12.0)][index],
+            AttractorKind::Aizawa => [(0.7, 1.1), (0.5, 0.9), (0.4, 0.8), (2.5, 4.5), (0.1, 0.4), (0.0, 0.3)][index],
+        }
+    }
+
+    fn default_step(&self) -> f64 {
+        match self {
+            AttractorKind::Lorenz => 0.005,
+            _ => 0.01,
+        }
+    }
+
+    fn initial_point(&self) -> [f64; 3] {
+        match self {
+            AttractorKind::Lorenz => [1.0, 1.0, 1.0],
+            AttractorKind::Aizawa => [0.1, 0.0, 0.0],
+            _ => [0.1, 0.1, 0.0],
+        }
+    }
+}
+
+/// Attracteur et sa caméra. Les applications 2D n'utilisent pas `step`, `yaw`
+/// ni `pitch`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Attractor {
+    pub kind: AttractorKind,
+    pub parameters: Vec<f64>,
+    /// Pas d'intégration des flots.
+    pub step: f64,
+    /// Rotation de la projection des flots autour de l'axe z puis de l'axe x, en degrés.
+    pub yaw: f64,
+    pub pitch: f64,
+}
+
+impl Default for Attractor {
+    fn default() -> Self {
+        Attractor::new(AttractorKind::Clifford)
+    }
+}
+
+// Itérations ignorées avant que l'orbite n'atteigne l'attracteur.
+const WARMUP: usize = 1000;
+// Au-delà, l'orbite est considérée comme partie à l'infini.
+const DIVERGED: f64 = 1e6;
+
+impl Attractor {
+    pub fn new(kind: AttractorKind) -> Self {
+        let (yaw, pitch) = if kind == AttractorKind::Lorenz { (0.0, 90.0) } else { (0.0, 0.0) };
+        Self { kind, parameters: kind.default_parameters(), step: kind.default_step(), yaw, pitch }
+    }
+
+    #[inline]
+    fn parameter(&self, index: usize) -> f64 {
+        self.parameters.get(index).copied().unwrap_or(0.0)
+    }
+
+    /// Champ de vitesses des flots.
+    #[inline]
+    fn velocity(&self, [x, y, z]: [f64; 3]) -> [f64; 3] {
+        let p = |i| self.parameter(i);
+        match self.kind {
+            AttractorKind::Lorenz => [p(0) * (y - x), x * (p(1) - z) - y, x * y - p(2) * z],
+            AttractorKind::Rossler => [-y - z, x + p(0) * y, p(1) + z * (x - p(2))],
+            AttractorKind::Aizawa => {
+                let (a, b, c, d, e, f) = (p(0), p(1), p(2), p(3), p(4), p(5));
+                [
+                    (z - b) * x - d * y,
+                    d * x + (z - b) * y,
+                    c + a * z - z * z * z / 3.0 - (x * x + y * y) * (1.0 + e * z) + f * z * x * x * x,
+                ]
+            }
+            _ => [0.0; 3],
+        }
+    }
+
+    /// Un pas : itération de l'application, ou pas de Runge–Kutta d'ordre 4.
+    #[inline]
+    pub fn advance(&self, point: [f64; 3]) -> [f64; 3] {
+        let [x, y, _] = point;
+        let p = |i| self.parameter(i);
+        match self.kind {
+            AttractorKind::Clifford => [
+                (p(0) * y).sin() + p(2) * (p(0) * x).cos(),
+                (p(1) * x).sin() + p(3) * (p(1) * y).cos(),
+                0.0,
+            ],
+            AttractorKind::DeJong => [(p(0) * y).sin() - (p(1) * x).cos(), (p(2) * x).sin() - (p(3) * y).cos(), 0.0],
+            AttractorKind::Henon => [1.0 - p(0) * x * x + y, p(1) * x, 0.0],
+            AttractorKind::Ikeda => {
+                let t = 0.4 - 6.0 / (1.0 + x * x + y * y);
+                let (s, c) = t.sin_cos();
+                [1.0 + p(0) * (x * c - y * s), p(0) * (x * s + y * c), 0.0]
+            }
+            AttractorKind::GumowskiMira => {
+                let mu = p(2);
+                let f = |x: f64| mu * x + 2.0 * (1.0 - mu) * x * x / (1.0 + x * x);
+                let next_x = y + p(0) * (1.0 - p(1) * y * y) * y + f(x);
+                [next_x, -x + f(next_x), 0.0]
+            }
+            _ => {
+                let h = self.step;
+                let offset = |p: [f64; 3], k: [f64; 3], s: f64| [p[0] + k[0] * s, p[1] + k[1] * s, p[2] + k[2] * s];
+                let k1 = self.velocity(point);
+                let k2 = self.velocity(offset(point, k1, h / 2.0));
+                let k3 = self.velocity(offset(point, k2, h / 2.0));
+                let k4 = self.velocity(offset(point, k3, h));
+                [0, 1, 2].map(|i| point[i] + h / 6.0 * (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i]))
+            }
+        }
+    }
+
+    /// Projection dans le plan de l'image.
+    #[inline]
+    fn project(&self, [x, y, z]: [f64; 3], (sin_yaw, cos_yaw, sin_pitch, cos_pitch): (f64, f64, f64, f64)) -> [f64; 2] {
+        if !self.kind.is_flow() {
+            return [x, y];
+        }
+        let (rx, ry) = (x * cos_yaw - y * sin_yaw, x * sin_yaw + y * cos_yaw);
+        // La composante verticale monte à l'écran, d'où le signe.
+        [rx, -(ry * cos_pitch + z * sin_pitch)]
+    }
+
+    fn camera(&self) -> (f64, f64, f64, f64) {
+        let (sin_yaw, cos_yaw) = self.yaw.to_radians().sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.to_radians().sin_cos();
+        (sin_yaw, cos_yaw, sin_pitch, cos_pitch)
+    }
+
+    /// Plus grand exposant de Lyapunov, par pas, estimé en suivant une orbite
+    /// voisine renormalisée à chaque pas ; `None` si l'orbite diverge ou
+    /// tombe sur un point fixe.
+    pub fn lyapunov(&self, steps: usize) -> Option<f64> {
+        const SEPARATION: f64 = 1e-8;
+        let mut p = self.kind.initial_point();
+        for _ in 0..WARMUP {
+            p = self.advance(p);
+        }
+        let mut q = [p[0] + SEPARATION, p[1], p[2]];
+        let mut sum = 0.0;
+        let mut travel = 0.0;
+        for _ in 0..steps {
+            let next = self.advance(p);
+            q = self.advance(q);
+            if !next.iter().all(|v| v.is_finite() && v.abs() < DIVERGED) {
+                return None;
+            }
+            travel += distance(next, p);
+            p = next;
+            let d = distance(p, q);
+            if d == 0.0 {
+                return Some(f64::NEG_INFINITY);
+            }
+            sum += (d / SEPARATION).ln();
+            q = [0, 1, 2].map(|i| p[i] + (q[i] - p[i]) * SEPARATION / d);
+        }
+        (travel / steps as f64 > 1e-6).then_some(sum / steps as f64)
+    }
+
+    /// Tire des paramètres au hasard jusqu'à obtenir une orbite bornée et
+    /// chaotique (exposant de Lyapunov positif), selon la méthode de Sprott.
+    pub fn random_chaotic(kind: AttractorKind, rng: &mut Rng) -> Option<Attractor> {
+        let mut attractor = Attractor::new(kind);
+        for _ in 0..2000 {
+            for (i, parameter) in attractor.parameters.iter_mut().enumerate() {
+                let (min, max) = kind.parameter_range(i);
+                *parameter = rng.range(min, max);
+            }
+            let threshold = if kind.is_flow() { 0.0005 } else { 0.01 };
+            if matches!(attractor.lyapunov(5000), Some(lambda) if lambda > threshold) && attractor.spread() {
+                return Some(attractor);
+            }
+        }
+        None
+    }
+
+    // Écarte les attracteurs réduits à quelques points ou à une courbe fine.
+    fn spread(&self) -> bool {
+        let (min, max) = self.bounds(20_000);
+        let size = (max[0] - min[0]).min(max[1] - min[1]);
+        let mut cells = std::collections::HashSet::new();
+        let camera = self.camera();
+        let mut p = self.kind.initial_point();
+        for i in 0..WARMUP + 20_000 {
+            p = self.advance(p);
+            if i >= WARMUP {
+                let [x, y] = self.project(p, camera);
+                cells.insert((((x - min[0]) / (max[0] - min[0]) * 64.0) as i32, ((y - min[1]) / (max[1] - min[1]) * 64.0) as i32));
+            }
+        }
+        size > 1e-6 && cells.len() > 200
+    }
+
+    /// Rectangle englobant la projection de l'orbite.
+    pub fn bounds(&self, points: usize) -> ([f64; 2], [f64; 2]) {
+        let camera = self.camera();
+        let (mut min, mut max) = ([f64::INFINITY; 2], [f64::NEG_INFINITY; 2]);
+        let mut p = self.kind.initial_point();
+        for i in 0..WARMUP + points {
+            p = self.advance(p);
+            if i >= WARMUP {
+                let q = self.project(p, camera);
+                if q[0].is_finite() && q[1].is_finite() && q[0].abs() < DIVERGED && q[1].abs() < DIVERGED {
+                    min = [min[0].min(q[0]), min[1].min(q[1])];
+                    max = [max[0].max(q[0]), max[1].max(q[1])];
+                }
+            }
+        }
+        if !(min[0] < max[0] && min[1] < max[1]) {
+            return ([-1.0; 2], [1.0; 2]);
+        }
+        (min, max)
+    }
+}
+
+#[inline]
+fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
+
+/// Rendu d'un attracteur en densité de points, cadré automatiquement ; le
+/// zoom et le centre des paramètres s'appliquent à ce cadre, dont la largeur
+/// vaut 1 au zoom 1 et dont le milieu est l'origine. Le résultat est un `IterationData` : palettes, cycle des couleurs et exports
+/// fonctionnent comme pour les fractales à temps d'échappement.
+pub struct AttractorRenderer {
+    pub params: FractalParams,
+}
+
+impl AttractorRenderer {
+    pub fn new(params: FractalParams) -> Self {
+        Self { params }
+    }
+
+    pub fn compute(&self) -> IterationData {
+        let attractor = &self.params.attractor;
+        let (width, height) = self.params.size;
+        let (min, max) = attractor.bounds(50_000);
+        // Pixels carrés et marge de 5 %.
+        let fitted = ((max[0] - min[0]) / width.max(1) as f64).max((max[1] - min[1]) / height.max(1) as f64) * 1.1;
+        let offset = self.params.center;
+        let center = [
+            (min[0] + max[0]) / 2.0 + offset.re * fitted * width as f64,
+            (min[1] + max[1]) / 2.0 + offset.im * fitted * height as f64,
+        ];
+        let scale = fitted / self.params.zoom;
+        let camera = attractor.camera();
+
+        // Une orbite par thread, chacune légèrement décalée au départ.
+        let threads = rayon::current_num_threads().max(1);
+        let per_thread = self.params.attractor_points / threads + 1;
+        let buffers: Vec<DensityBuffer> = (0..threads)
+            .into_par_iter()
+            .map(|thread| {
+                let mut local = DensityBuffer::new(width, height);
+                let mut rng = Rng::stream(0, thread as u64);
+                let start = attractor.kind.initial_point();
+                let mut p = [0, 1, 2].map(|i| start[i] + rng.range(-1e-3, 1e-3));
+                for i in 0..WARMUP + per_thread {
+                    p = attractor.advance(p);
+                    if i < WARMUP {
+                        continue;
+                    }
+                    let [x, y] = attractor.project(p, camera);
+                    let px = (x - center[0]) / scale + width as f64 / 2.0;
+                    let py = (y - center[1]) / scale + height as f64 / 2.0;
+                    if let Some(index) = local.index(px, py) {
+                        local.add(index, [false; 3], 1.0);
+                    }
+                }
+                local
+            })
+            .collect();
+        let mut density = DensityBuffer::new(width, height);
+        for buffer in &buffers {
+            density.merge(buffer);
+        }
+
+        // Densité logarithmique ramenée à [0, max_iterations] ; les pixels
+        // jamais atteints restent noirs.
+        let max_iterations = self.params.max_iterations.max(1);
+        let max = density.data.iter().map(|cell| cell[3]).fold(0.0f32, f32::max) as f64;
+        let log_max = (1.0 + max).ln().max(f64::EPSILON);
+        let smooth = density
+            .data
+            .iter()
+            .map(|cell| {
+                if cell[3] > 0.0 {
+                    (1.0 + cell[3] as f64).ln() / log_max * max_iterations as f64
+                } else {
+                    f64::NAN
+                }
+            })
+            .collect::<Vec<_>>();
+        IterationData {
+            size: (width, height),
+            max_iterations,
+            converged: vec![false; smooth.len()],
+            smooth,
+            root_count: 0,
+            basins: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use crate::density::DensityBuffer;
+use crate::fractal_params::FractalParams;
+use crate::rng::Rng;
+use num::Complex;
+use rayon::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BuddhaMode {
+    /// Orbites des points qui s'échappent.
+    Buddhabrot,
+    /// Orbites des points restés bornés.
+    AntiBuddhabrot,
+}
+
+/// Plage d'itérations [min, max] des orbites retenues pour un canal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IterationRange {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl IterationRange {
+    #[inline]
+    fn contains(&self, n: u32) -> bool {
+        self.min <= n && n <= self.max
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BuddhabrotSettings {
+    pub seed: u64,
+    pub mode: BuddhaMode,
+    /// Plages rouge, verte et bleue ; identiques pour un Buddhabrot simple,
+    /// différentes pour un Nebulabrot.
+    pub channels: [IterationRange; 3],
+    /// Échantillonnage préférentiel de Metropolis–Hastings, utile en zoom.
+    pub metropolis: bool,
+    pub samples_per_batch: usize,
+    pub gamma: f64,
+}
+
+impl Default for BuddhabrotSettings {
+    fn default() -> Self {
+        Self {
+            seed: 1,
+            mode: BuddhaMode::Buddhabrot,
+            channels: [
+                IterationRange { min: 20, max: 1000 },
+                IterationRange { min: 20, max: 200 },
+                IterationRange { min: 20, max: 50 },
+            ],
+            metropolis: false,
+            samples_per_batch: 200_000,
+            gamma: 2.0,
+        }
+    }
+}
+
+// Les orbites qui s'échappent partent toutes du disque |c| ≤ 2.
+const SAMPLE_RADIUS: f64 = 2.0;
+// Probabilité qu'une mutation de Metropolis soit un tirage uniforme.
+const UNIFORM_MUTATION: f64 = 0.2;
+
+#[derive(Clone)]
+struct Worker {
+    rng: Rng,
+    /// Échantillon courant de la chaîne de Metropolis : c, sa contribution
+    /// et ses canaux ; son orbite est gardée dans `chain_orbit`.
+    chain: Option<(Complex<f64>, f64, [bool; 3])>,
+    orbit: Vec<Complex<f64>>,
+    chain_orbit: Vec<Complex<f64>>,
+    density: DensityBuffer,
+}
+
+/// Rendu par accumulation de densité : chaque échantillon c trace son orbite
+/// entière dans l'histogramme. Le calcul avance par lots ; entre deux lots
+/// l'état (générateurs, chaînes, histogramme) est conservé, ce qui permet de
+/// suspendre et reprendre un long rendu.
+pub struct BuddhabrotRenderer {
+    pub settings: BuddhabrotSettings,
+    center: Complex<f64>,
+    zoom: f64,
+    density: DensityBuffer,
+    workers: Vec<Worker>,
+    samples: u64,
+}
+
+impl BuddhabrotRenderer {
+    /// La vue (centre, zoom, taille) est celle des paramètres de fractale.
+    pub fn new(settings: BuddhabrotSettings, params: &FractalParams) -> Self {
+        let (width, height) = (params.size.0.max(1), params.size.1.max(1));
+        let workers = (0..rayon::current_num_threads().max(1))
+            .map(|i| Worker {
+                rng: Rng::stream(settings.seed, i as u64),
+                chain: None,
+                orbit: Vec::new(),
+                chain_orbit: Vec::new(),
+                density: DensityBuffer::new(width, height),
+            })
+            .collect();
+        Self {
+            settings,
+            center: params.center,
+            zoom: params.zoom,
+            density: DensityBuffer::new(width, height),
+            workers,
+            samples: 0,
+        }
+    }
+
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        (self.density.width, self.density.height)
+    }
+
+    /// Trace un lot d'échantillons, réparti entre les threads.
+    pub fn run_batch(&mut self) {
+        let per_worker = (self.settings.samples_per_batch / self.workers.len()).max(1);
+        let view = View { center: self.center, zoom: self.zoom };
+        let settings = &self.settings;
+        self.workers.par_iter_mut().for_each(|worker| {
+            for _ in 0..per_worker {
+                worker.sample(settings, &view);
+            }
+        });
+        for worker in &mut self.workers {
+            self.density.merge(&worker.density);
+            worker.density.clear();
+        }
+        self.samples += (per_worker * self.workers.len()) as u64;
+    }
+
+    /// Image RGBA de l'histogramme accumulé jusqu'ici.
+    pub fn image(&self) -> Vec<u8> {
+        self.density.to_rgba_per_channel(self.settings.gamma)
+    }
+}
+
+struct View {
+    center: Complex<f64>,
+    zoom: f64,
+}
+
+impl View {
+    // Inverse de la correspondance pixel → c de `FractalCalculator`.
+    #[inline]
+    fn index(&self, density: &DensityBuffer, z: Complex<f64>) -> Option<usize> {
+        let x = ((z.re - self.center.re) * self.zoom + 0.5) * density.width as f64;
+        let y = ((z.im - self.center.im) * self.zoom + 0.5) * density.height as f64;
+        density.index(x, y)
+    }
+}
+
+// Cardioïde principale et disque de période 2 : jamais d'échappement.
+#[inline]
+fn in_main_bulbs(c: Complex<f64>) -> bool {
+    let q = (c.re - 0.25).powi(2) + c.im * c.im;
+    q * (q + (c.re - 0.25)) <= 0.25 * c.im * c.im || (c.re + 1.0).powi(2) + c.im * c.im <= 0.0625
+}
+
+impl Worker {
+    fn uniform_sample(&mut self) -> Complex<f64> {
+        loop {
+            let c = Complex::new(
+                self.rng.range(-SAMPLE_RADIUS, SAMPLE_RADIUS),
+                self.rng.range(-SAMPLE_RADIUS, SAMPLE_RADIUS),
+            );
+            if c.norm_sqr() <= SAMPLE_RADIUS * SAMPLE_RADIUS {
+                return c;
+            }
+        }
+    }
+
+    /// Calcule l'orbite de c ; renvoie les canaux concernés, ou `None` si
+    /// l'orbite n'est pas retenue.
+    fn trace(&mut self, settings: &BuddhabrotSettings, c: Complex<f64>) -> Option<[bool; 3]> {
+        let escaping = settings.mode == BuddhaMode::Buddhabrot;
+        if escaping && in_main_bulbs(c) {
+            return None;
+        }
+        let max_iter = settings.channels.iter().map(|r| r.max).max().unwrap_or(0);
+        self.orbit.clear();
+        let mut z = Complex::new(0.0, 0.0);
+        let mut n = 0;
+        while n < max_iter && z.norm_sqr() <= 4.0 {
+            z = z * z + c;
+            self.orbit.push(z);
+            n += 1;
+        }
+        let escaped = n < max_iter;
+        if escaped != escaping {
+            return None;
+        }
+        let channels = if escaping {
+            [0, 1, 2].map(|i| settings.channels[i].contains(n))
+        } else {
+            [true; 3]
+        };
+        channels.iter().any(|&b| b).then_some(channels)
+    }
+
+    // Nombre de points de l'orbite tombant dans la vue.
+    fn contribution(&self, view: &View) -> f64 {
+        self.orbit.iter().filter(|z| view.index(&self.density, **z).is_some()).count() as f64
+    }
+
+    fn plot(&mut self, view: &View, channels: [bool; 3], weight: f32) {
+        plot_orbit(&mut self.density, view, &self.orbit, channels, weight);
+    }
+
+    fn sample(&mut self, settings: &BuddhabrotSettings, view: &View) {
+        if !settings.metropolis {
+            let c = self.uniform_sample();
+            if let Some(channels) = self.trace(settings, c) {
+                self.plot(view, channels, 1.0);
+            }
+            return;
+        }
+
+        // Chaîne de Metropolis : c est tiré proportionnellement à sa
+        // contribution f(c), chaque orbite est donc tracée avec un poids 1/f(c).
+        let (current, current_value, current_channels) = match self.chain {
+            Some(chain) => chain,
+            None => {
+                let c = self.uniform_sample();
+                if let Some(channels) = self.trace(settings, c) {
+                    let value = self.contribution(view);
+                    if value > 0.0 {
+                        self.chain = Some((c, value, channels));
+                        std::mem::swap(&mut self.orbit, &mut self.chain_orbit);
+                    }
+                }
+                return;
+            }
+        };
+        let proposal = if self.rng.next_f64() < UNIFORM_MUTATION {
+            self.uniform_sample()
+        } else {
+            let scale = 0.25 / self.density.width.max(self.density.height) as f64 / view.zoom;
+            current + Complex::new(self.rng.gaussian(), self.rng.gaussian()) * scale
+        };
+        if let Some(channels) = self.trace(settings, proposal) {
+            let value = self.contribution(view);
+            if value > 0.0 && self.rng.next_f64() < value / current_value {
+                self.chain = Some((proposal, value, channels));
+                std::mem::swap(&mut self.orbit, &mut self.chain_orbit);
+                plot_orbit(&mut self.density, view, &self.chain_orbit, channels, (1.0 / value) as f32);
+                return;
+            }
+        }
+        // Refus : l'échantillon courant est compté de nouveau.
+        let weight = (1.0 / current_value) as f32;
+        plot_orbit(&mut self.density, view, &self.chain_orbit, current_channels, weight);
+    }
+}
+
+fn plot_orbit(density: &mut DensityBuffer, view: &View, orbit: &[Complex<f64>], channels: [bool; 3], weight: f32) {
+    for &z in orbit {
+        if let Some(index) = view.index(density, z) {
+            density.add(index, channels, weight);
+        }
+    }
+}
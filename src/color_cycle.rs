@@ -0,0 +1,86 @@
+use crate::color_schemes::ColorScheme;
+use crate::fractal_calculator::IterationData;
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame, RgbaImage};
+use std::fs::File;
+use std::path::Path;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CycleDirection {
+    Forward,
+    Backward,
+}
+
+/// Cycle de palette : seul le décalage des couleurs évolue, les itérations
+/// déjà calculées ne sont jamais recalculées.
+#[derive(Clone, Debug)]
+pub struct ColorCycle {
+    pub enabled: bool,
+    pub paused: bool,
+    /// Tours de palette par seconde.
+    pub speed: f64,
+    pub direction: CycleDirection,
+    pub offset: f64,
+}
+
+impl Default for ColorCycle {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            paused: false,
+            speed: 0.1,
+            direction: CycleDirection::Forward,
+            offset: 0.0,
+        }
+    }
+}
+
+impl ColorCycle {
+    /// Avance le décalage de `dt` secondes ; renvoie `true` s'il a changé.
+    pub fn advance(&mut self, dt: f64) -> bool {
+        if !self.enabled || self.paused || self.speed == 0.0 {
+            return false;
+        }
+        let step = match self.direction {
+            CycleDirection::Forward => self.speed * dt,
+            CycleDirection::Backward => -self.speed * dt,
+        };
+        self.offset = (self.offset + step).rem_euclid(1.0);
+        true
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+}
+
+/// Exporte un tour complet de palette en GIF bouclant sans raccord visible :
+/// la dernière image précède exactement la première.
+pub fn export_cycle_gif(
+    path: &Path,
+    data: &IterationData,
+    color_scheme: &(dyn ColorScheme + Sync),
+    cycle: &ColorCycle,
+    frame_count: usize,
+    frame_delay_ms: u32,
+) -> image::ImageResult<()> {
+    let (width, height) = data.size;
+    let frame_count = frame_count.max(1);
+    let mut encoder = GifEncoder::new(File::create(path)?);
+    encoder.set_repeat(Repeat::Infinite)?;
+
+    for i in 0..frame_count {
+        let step = i as f64 / frame_count as f64;
+        // Décalé d'un tour complet pour ne jamais valoir zéro : la palette est
+        // ainsi enroulée de la même façon sur toutes les images.
+        let offset = match cycle.direction {
+            CycleDirection::Forward => 1.0 + cycle.offset + step,
+            CycleDirection::Backward => 1.0 + cycle.offset - step,
+        };
+        let pixels = data.colorize(color_scheme, offset);
+        let image = RgbaImage::from_raw(width as u32, height as u32, pixels)
+            .expect("taille du tampon d'image");
+        encoder.encode_frame(Frame::from_parts(image, 0, 0, Delay::from_numer_denom_ms(frame_delay_ms, 1)))?;
+    }
+    Ok(())
+}
@@ -1,19 +1,54 @@
 pub trait ColorScheme: Send + Sync {
     fn get_color(&self, t: f64) -> (u8, u8, u8);
-    fn smooth_color(&self, iterations: u32, max_iterations: u32, z_norm: f64) -> (u8, u8, u8) {
-        if iterations == max_iterations {
-            return (0, 0, 0);
+    fn smooth_color(&self, iterations: u32, max_iterations: u32, z_norm: f64, degree: f64) -> (u8, u8, u8) {
+        match smooth_iteration(iterations, max_iterations, z_norm, degree) {
+            Some(smooth_iter) => self.get_color(smooth_iter / max_iterations as f64),
+            None => (0, 0, 0),
         }
-        
-        let log_zn = z_norm.ln() / 2.0;
-        let nu = (log_zn / f64::ln(2.0)).ln() / f64::ln(2.0);
-        let smooth_iter = iterations as f64 + 1.0 - nu;
-        
-        let t = smooth_iter / max_iterations as f64;
-        self.get_color(t)
     }
 }
 
+/// Nombre d'itérations lissé pour z ↦ z^degree + c, `None` pour les points
+/// restés dans l'ensemble. `z_norm` est |z|².
+pub fn smooth_iteration(iterations: u32, max_iterations: u32, z_norm: f64, degree: f64) -> Option<f64> {
+    if iterations == max_iterations {
+        return None;
+    }
+    // Sans croissance exponentielle de |z|, pas de lissage possible.
+    if degree <= 1.0 {
+        return Some(iterations as f64);
+    }
+
+    let log_zn = z_norm.ln() / 2.0;
+    let nu = (log_zn / f64::ln(2.0)).ln() / degree.ln();
+    Some(iterations as f64 + 1.0 - nu)
+}
+
+/// Équivalent lissé pour une orbite qui converge vers une racine : la
+/// convergence quadratique place la distance finale entre tolérance² et tolérance.
+pub fn smooth_convergence(iterations: u32, distance: f64, tolerance: f64) -> f64 {
+    let ratio = (distance.ln() / tolerance.ln()).clamp(1.0, 2.0);
+    iterations as f64 - ratio.log2()
+}
+
+/// Valeur lissée d'une estimation de distance, exprimée en pixels : le bord
+/// de l'ensemble part du début de la palette, qui est parcourue jusqu'à
+/// 1024 pixels. `None` à l'intérieur de l'ensemble.
+pub fn smooth_distance(distance: f64, max_iterations: u32) -> Option<f64> {
+    if distance <= 0.0 || distance.is_nan() {
+        return None;
+    }
+    Some(((1.0 + distance).log2() / 10.0).min(1.0) * max_iterations as f64)
+}
+
+/// Couleur d'un bassin d'attraction : la teinte identifie la racine atteinte,
+/// la luminosité baisse avec le temps de convergence.
+pub fn basin_color(root: usize, root_count: usize, smooth_iter: f64, max_iterations: u32, offset: f64) -> (u8, u8, u8) {
+    let hue = (root as f64 / root_count.max(1) as f64 + offset).rem_euclid(1.0);
+    let brightness = 1.0 - (smooth_iter.max(0.0) / max_iterations.max(1) as f64).sqrt().min(1.0);
+    hsv_to_rgb(hue as f32, 0.8, brightness as f32)
+}
+
 pub struct ClassicScheme;
 pub struct FireScheme;
 pub struct OceanScheme;
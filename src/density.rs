@@ -0,0 +1,103 @@
+/// Histogramme de densité à plusieurs canaux, accumulé point par point puis
+/// converti en image par une échelle logarithmique.
+#[derive(Clone, Debug)]
+pub struct DensityBuffer {
+    pub width: usize,
+    pub height: usize,
+    /// Rouge, vert, bleu et nombre total de points par pixel.
+    pub data: Vec<[f32; 4]>,
+}
+
+impl DensityBuffer {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self { width, height, data: vec![[0.0; 4]; width * height] }
+    }
+
+    pub fn clear(&mut self) {
+        self.data.iter_mut().for_each(|cell| *cell = [0.0; 4]);
+    }
+
+    #[inline]
+    pub fn index(&self, x: f64, y: f64) -> Option<usize> {
+        if x >= 0.0 && y >= 0.0 && x < self.width as f64 && y < self.height as f64 {
+            Some(y as usize * self.width + x as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Ajoute `weight` aux canaux dont le masque est vrai, et au total.
+    #[inline]
+    pub fn add(&mut self, index: usize, channels: [bool; 3], weight: f32) {
+        let cell = &mut self.data[index];
+        for (value, &enabled) in cell.iter_mut().zip(&channels) {
+            if enabled {
+                *value += weight;
+            }
+        }
+        cell[3] += weight;
+    }
+
+    /// Ajoute une couleur (composantes 0–255) pondérée, et compte le point.
+    #[inline]
+    pub fn add_color(&mut self, index: usize, color: [f32; 3], weight: f32) {
+        let cell = &mut self.data[index];
+        for channel in 0..3 {
+            cell[channel] += color[channel] * weight;
+        }
+        cell[3] += weight;
+    }
+
+    pub fn merge(&mut self, other: &DensityBuffer) {
+        for (cell, other) in self.data.iter_mut().zip(&other.data) {
+            for channel in 0..4 {
+                cell[channel] += other[channel];
+            }
+        }
+    }
+
+    fn channel_max(&self) -> [f32; 4] {
+        self.data.iter().fold([0.0f32; 4], |mut max, cell| {
+            for channel in 0..4 {
+                max[channel] = max[channel].max(cell[channel]);
+            }
+            max
+        })
+    }
+
+    /// Chaque canal est normalisé séparément : t = ln(1 + v) / ln(1 + max),
+    /// puis corrigé par `gamma`. Adapté aux Buddhabrot et Nebulabrot.
+    pub fn to_rgba_per_channel(&self, gamma: f64) -> Vec<u8> {
+        let max = self.channel_max();
+        let scale: Vec<f32> = (0..3).map(|c| 1.0 / (1.0 + max[c]).ln().max(f32::EPSILON)).collect();
+        let inv_gamma = (1.0 / gamma.max(0.01)) as f32;
+        let mut pixels = Vec::with_capacity(self.data.len() * 4);
+        for cell in &self.data {
+            for channel in 0..3 {
+                let t = ((1.0 + cell[channel]).ln() * scale[channel]).powf(inv_gamma);
+                pixels.push((t.clamp(0.0, 1.0) * 255.0) as u8);
+            }
+            pixels.push(255);
+        }
+        pixels
+    }
+
+    /// La densité totale donne la luminosité, t = ln(1 + n) / ln(1 + max)
+    /// corrigé par `gamma`, et la teinte est la moyenne des couleurs reçues.
+    /// Adapté aux IFS, dont les canaux proviennent de `add_color`.
+    pub fn to_rgba_log_density(&self, gamma: f64) -> Vec<u8> {
+        let max = self.channel_max()[3];
+        let scale = 1.0 / (1.0 + max).ln().max(f32::EPSILON);
+        let inv_gamma = (1.0 / gamma.max(0.01)) as f32;
+        let mut pixels = Vec::with_capacity(self.data.len() * 4);
+        for cell in &self.data {
+            let count = cell[3];
+            let brightness = if count > 0.0 { ((1.0 + count).ln() * scale).powf(inv_gamma) / count } else { 0.0 };
+            for value in &cell[..3] {
+                pixels.push((value * brightness).clamp(0.0, 255.0) as u8);
+            }
+            pixels.push(255);
+        }
+        pixels
+    }
+}
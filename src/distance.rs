@@ -0,0 +1,108 @@
+use num::Complex;
+use std::f64::consts::SQRT_2;
+
+// La formule n'est juste qu'asymptotiquement : on laisse l'orbite s'éloigner
+// bien au-delà du rayon d'échappement habituel.
+const ESCAPE_RADIUS_SQR: f64 = 1e4;
+
+/// Estimation de la distance d'un point à un ensemble rempli, et piège
+/// d'orbite : plus petit |z| de l'orbite, ramené à [0, 1].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Estimate {
+    pub distance: f64,
+    pub trap: f64,
+}
+
+/// ½·|z|·ln|z| / |z'| pour une orbite échappée de z ↦ z² + c, où z' est la
+/// dérivée par rapport à la variable du plan (Milnor).
+#[inline]
+pub fn escape_distance(z_norm: f64, dz_norm: f64) -> f64 {
+    0.5 * z_norm * z_norm.ln() / dz_norm.max(1e-300)
+}
+
+/// Ensemble de Julia rempli de z ↦ z² + c ; distance nulle si l'orbite de
+/// `z` reste bornée pendant `max_iter` itérations.
+pub fn julia(mut z: Complex<f64>, c: Complex<f64>, max_iter: u32) -> Estimate {
+    let mut dz = Complex::new(1.0, 0.0);
+    let mut trap = z.norm();
+    for _ in 0..max_iter {
+        if z.norm_sqr() > ESCAPE_RADIUS_SQR {
+            return Estimate { distance: escape_distance(z.norm(), dz.norm()), trap: trap.min(1.0) };
+        }
+        dz = 2.0 * z * dz;
+        z = z * z + c;
+        trap = trap.min(z.norm());
+    }
+    Estimate { distance: 0.0, trap: trap.min(1.0) }
+}
+
+/// Ensemble de Mandelbrot : la dérivée est prise par rapport à c.
+pub fn mandelbrot(c: Complex<f64>, max_iter: u32) -> Estimate {
+    let mut z = Complex::new(0.0, 0.0);
+    let mut dz = Complex::new(0.0, 0.0);
+    let mut trap = f64::INFINITY;
+    for _ in 0..max_iter {
+        if z.norm_sqr() > ESCAPE_RADIUS_SQR {
+            return Estimate { distance: escape_distance(z.norm(), dz.norm()), trap: trap.min(1.0) };
+        }
+        dz = 2.0 * z * dz + 1.0;
+        z = z * z + c;
+        trap = trap.min(z.norm());
+    }
+    Estimate { distance: 0.0, trap: trap.min(1.0) }
+}
+
+/// Algèbre de dimension 4 dans laquelle itérer q ↦ q² + c.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Algebra {
+    Quaternion,
+    /// Nombres bicomplexes z₁ + z₂·j, avec i² = j² = −1 et ij = ji.
+    Bicomplex,
+}
+
+impl Algebra {
+    /// Ensemble de Julia rempli de q ↦ q² + c ; q = (a, b, c, d) se lit
+    /// a + bi + cj + dk.
+    pub fn julia(&self, q: [f64; 4], c: [f64; 4], max_iter: u32) -> Estimate {
+        match self {
+            Algebra::Quaternion => quaternion_julia(q, c, max_iter),
+            Algebra::Bicomplex => {
+                // Dans la base idempotente, q devient (z₁ − i·z₂, z₁ + i·z₂) et
+                // le carré agit composante par composante : l'ensemble est le
+                // produit de deux ensembles de Julia du plan.
+                let [w1, w2] = idempotent(q);
+                let [k1, k2] = idempotent(c);
+                let (e1, e2) = (julia(w1, k1, max_iter), julia(w2, k2, max_iter));
+                // Le changement de base multiplie les longueurs par √2.
+                Estimate {
+                    distance: e1.distance.hypot(e2.distance) / SQRT_2,
+                    trap: (e1.trap.hypot(e2.trap) / SQRT_2).min(1.0),
+                }
+            }
+        }
+    }
+}
+
+fn idempotent([a, b, c, d]: [f64; 4]) -> [Complex<f64>; 2] {
+    let (z1, z2) = (Complex::new(a, b), Complex::new(c, d));
+    let i = Complex::new(0.0, 1.0);
+    [z1 - i * z2, z1 + i * z2]
+}
+
+fn quaternion_julia(mut q: [f64; 4], c: [f64; 4], max_iter: u32) -> Estimate {
+    let norm_sqr = |q: [f64; 4]| q.iter().map(|v| v * v).sum::<f64>();
+    let mut dr = 1.0;
+    let mut trap = norm_sqr(q).sqrt();
+    for _ in 0..max_iter {
+        let r2 = norm_sqr(q);
+        if r2 > ESCAPE_RADIUS_SQR {
+            return Estimate { distance: escape_distance(r2.sqrt(), dr), trap: trap.min(1.0) };
+        }
+        // |q·dq| = |q|·|dq| : une seule norme suffit pour la dérivée.
+        dr *= 2.0 * r2.sqrt();
+        let [a, b, cc, d] = q;
+        q = [a * a - b * b - cc * cc - d * d + c[0], 2.0 * a * b + c[1], 2.0 * a * cc + c[2], 2.0 * a * d + c[3]];
+        trap = trap.min(norm_sqr(q).sqrt());
+    }
+    Estimate { distance: 0.0, trap: trap.min(1.0) }
+}
@@ -0,0 +1,591 @@
+use crate::color_schemes::ColorScheme;
+use crate::density::DensityBuffer;
+use crate::ifs::AffineMap;
+use crate::rng::Rng;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+const EPS: f64 = 1e-10;
+
+/// Variations non linéaires de flam3, nommées comme dans les fichiers `.flame`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variation {
+    Linear,
+    Sinusoidal,
+    Spherical,
+    Swirl,
+    Horseshoe,
+    Polar,
+    Handkerchief,
+    Heart,
+    Disc,
+    Spiral,
+    Hyperbolic,
+    Diamond,
+    Ex,
+    Julia,
+    Bent,
+    Waves,
+    Fisheye,
+    Popcorn,
+    Exponential,
+    Power,
+    Cosine,
+    Rings,
+    Fan,
+    Blur,
+    Eyefish,
+    Bubble,
+    Cylinder,
+    Noise,
+    GaussianBlur,
+    Tangent,
+    Cross,
+}
+
+impl Variation {
+    pub const ALL: [Variation; 31] = [
+        Variation::Linear,
+        Variation::Sinusoidal,
+        Variation::Spherical,
+        Variation::Swirl,
+        Variation::Horseshoe,
+        Variation::Polar,
+        Variation::Handkerchief,
+        Variation::Heart,
+        Variation::Disc,
+        Variation::Spiral,
+        Variation::Hyperbolic,
+        Variation::Diamond,
+        Variation::Ex,
+        Variation::Julia,
+        Variation::Bent,
+        Variation::Waves,
+        Variation::Fisheye,
+        Variation::Popcorn,
+        Variation::Exponential,
+        Variation::Power,
+        Variation::Cosine,
+        Variation::Rings,
+        Variation::Fan,
+        Variation::Blur,
+        Variation::Eyefish,
+        Variation::Bubble,
+        Variation::Cylinder,
+        Variation::Noise,
+        Variation::GaussianBlur,
+        Variation::Tangent,
+        Variation::Cross,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Variation::Linear => "linear",
+            Variation::Sinusoidal => "sinusoidal",
+            Variation::Spherical => "spherical",
+            Variation::Swirl => "swirl",
+            Variation::Horseshoe => "horseshoe",
+            Variation::Polar => "polar",
+            Variation::Handkerchief => "handkerchief",
+            Variation::Heart => "heart",
+            Variation::Disc => "disc",
+            Variation::Spiral => "spiral",
+            Variation::Hyperbolic => "hyperbolic",
+            Variation::Diamond => "diamond",
+            Variation::Ex => "ex",
+            Variation::Julia => "julia",
+            Variation::Bent => "bent",
+            Variation::Waves => "waves",
+            Variation::Fisheye => "fisheye",
+            Variation::Popcorn => "popcorn",
+            Variation::Exponential => "exponential",
+            Variation::Power => "power",
+            Variation::Cosine => "cosine",
+            Variation::Rings => "rings",
+            Variation::Fan => "fan",
+            Variation::Blur => "blur",
+            Variation::Eyefish => "eyefish",
+            Variation::Bubble => "bubble",
+            Variation::Cylinder => "cylinder",
+            Variation::Noise => "noise",
+            Variation::GaussianBlur => "gaussian_blur",
+            Variation::Tangent => "tangent",
+            Variation::Cross => "cross",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Variation> {
+        Variation::ALL.iter().copied().find(|v| v.name() == name)
+    }
+
+    /// Image de `p` (déjà passé par la partie affine). Certaines variations
+    /// lisent les coefficients affines, comme dans flam3.
+    // Intégrée dans la boucle du jeu du chaos, cette grande correspondance la
+    // ralentit de près d'un facteur 7 : on garde un vrai appel.
+    #[inline(never)]
+    fn apply(&self, [x, y]: [f64; 2], affine: &AffineMap, rng: &mut Rng) -> [f64; 2] {
+        let r2 = x * x + y * y;
+        let r = r2.sqrt();
+        // Conventions de flam3 : θ = atan2(x, y), d'où sin θ = x/r et cos θ = y/r.
+        let theta = || x.atan2(y);
+        match self {
+            Variation::Linear => [x, y],
+            Variation::Sinusoidal => [x.sin(), y.sin()],
+            Variation::Spherical => {
+                let k = 1.0 / (r2 + EPS);
+                [x * k, y * k]
+            }
+            Variation::Swirl => {
+                let (s, c) = r2.sin_cos();
+                [x * s - y * c, x * c + y * s]
+            }
+            Variation::Horseshoe => {
+                let k = 1.0 / (r + EPS);
+                [(x - y) * (x + y) * k, 2.0 * x * y * k]
+            }
+            Variation::Polar => [theta() / PI, r - 1.0],
+            Variation::Handkerchief => {
+                let t = theta();
+                [r * (t + r).sin(), r * (t - r).cos()]
+            }
+            Variation::Heart => {
+                let (s, c) = (theta() * r).sin_cos();
+                [r * s, -r * c]
+            }
+            Variation::Disc => {
+                let (s, c) = (PI * r).sin_cos();
+                let t = theta() / PI;
+                [t * s, t * c]
+            }
+            Variation::Spiral => {
+                let k = 1.0 / (r + EPS);
+                let (sin_t, cos_t) = (x * k, y * k);
+                [(cos_t + r.sin()) * k, (sin_t - r.cos()) * k]
+            }
+            Variation::Hyperbolic => {
+                let k = r + EPS;
+                [x / (k * k), y]
+            }
+            Variation::Diamond => {
+                let k = 1.0 / (r + EPS);
+                [x * k * r.cos(), y * k * r.sin()]
+            }
+            Variation::Ex => {
+                let t = theta();
+                let n0 = (t + r).sin();
+                let n1 = (t - r).cos();
+                let (m0, m1) = (n0 * n0 * n0, n1 * n1 * n1);
+                [r * (m0 + m1), r * (m0 - m1)]
+            }
+            Variation::Julia => {
+                let mut a = theta() / 2.0;
+                if rng.next_u64() & 1 == 1 {
+                    a += PI;
+                }
+                let (s, c) = a.sin_cos();
+                let k = r.sqrt();
+                [k * c, k * s]
+            }
+            Variation::Bent => [if x < 0.0 { 2.0 * x } else { x }, if y < 0.0 { y / 2.0 } else { y }],
+            Variation::Waves => [
+                x + affine.b * (y / (affine.e * affine.e + EPS)).sin(),
+                y + affine.d * (x / (affine.f * affine.f + EPS)).sin(),
+            ],
+            Variation::Fisheye => {
+                let k = 2.0 / (r + 1.0);
+                [k * y, k * x]
+            }
+            Variation::Popcorn => [x + affine.e * (3.0 * y).tan().sin(), y + affine.f * (3.0 * x).tan().sin()],
+            Variation::Exponential => {
+                let k = (x - 1.0).exp();
+                let (s, c) = (PI * y).sin_cos();
+                [k * c, k * s]
+            }
+            Variation::Power => {
+                let k = 1.0 / (r + EPS);
+                let (sin_t, cos_t) = (x * k, y * k);
+                let p = r.powf(sin_t);
+                [p * cos_t, p * sin_t]
+            }
+            Variation::Cosine => {
+                let a = PI * x;
+                [a.cos() * y.cosh(), -a.sin() * y.sinh()]
+            }
+            Variation::Rings => {
+                let dx = affine.e * affine.e + EPS;
+                let k = (r + dx) % (2.0 * dx) - dx + r * (1.0 - dx);
+                let inv = 1.0 / (r + EPS);
+                [k * y * inv, k * x * inv]
+            }
+            Variation::Fan => {
+                let dx = PI * (affine.e * affine.e + EPS);
+                let half = dx / 2.0;
+                let t = theta();
+                let a = if (t + affine.f) % dx > half { t - half } else { t + half };
+                let (s, c) = a.sin_cos();
+                [r * c, r * s]
+            }
+            Variation::Blur => {
+                let (s, c) = (2.0 * PI * rng.next_f64()).sin_cos();
+                let k = rng.next_f64();
+                [k * c, k * s]
+            }
+            Variation::Eyefish => {
+                let k = 2.0 / (r + 1.0);
+                [k * x, k * y]
+            }
+            Variation::Bubble => {
+                let k = 4.0 / (r2 + 4.0);
+                [k * x, k * y]
+            }
+            Variation::Cylinder => [x.sin(), y],
+            Variation::Noise => {
+                let (s, c) = (2.0 * PI * rng.next_f64()).sin_cos();
+                let k = rng.next_f64();
+                [x * k * c, y * k * s]
+            }
+            Variation::GaussianBlur => {
+                let (s, c) = (2.0 * PI * rng.next_f64()).sin_cos();
+                let k = rng.next_f64() + rng.next_f64() + rng.next_f64() + rng.next_f64() - 2.0;
+                [k * c, k * s]
+            }
+            Variation::Tangent => [x.sin() / y.cos(), y.tan()],
+            Variation::Cross => {
+                let s = x * x - y * y;
+                let k = (1.0 / (s * s + EPS)).sqrt();
+                [x * k, y * k]
+            }
+        }
+    }
+}
+
+/// Transformation d'une flamme : partie affine, somme pondérée de variations,
+/// puis transformation « post » facultative.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Xform {
+    /// `probability` est le poids de la transformation, `color` son indice
+    /// dans la palette.
+    pub affine: AffineMap,
+    pub post: Option<AffineMap>,
+    pub variations: Vec<(Variation, f64)>,
+    /// Vitesse à laquelle la couleur du point rejoint `affine.color`.
+    pub color_speed: f64,
+    pub opacity: f64,
+}
+
+impl Xform {
+    pub fn new(affine: AffineMap, variations: Vec<(Variation, f64)>) -> Self {
+        Self { affine, post: None, variations, color_speed: 0.5, opacity: 1.0 }
+    }
+
+    #[inline]
+    fn apply(&self, p: [f64; 2], rng: &mut Rng) -> [f64; 2] {
+        let t = self.affine.apply(p);
+        let mut out = [0.0, 0.0];
+        for &(variation, weight) in &self.variations {
+            let [x, y] = variation.apply(t, &self.affine, rng);
+            out[0] += weight * x;
+            out[1] += weight * y;
+        }
+        match &self.post {
+            Some(post) => post.apply(out),
+            None => out,
+        }
+    }
+
+    #[inline]
+    fn blend_color(&self, color: f64) -> f64 {
+        color * (1.0 - self.color_speed) + self.affine.color * self.color_speed
+    }
+}
+
+/// Flamme fractale à la Draves : caméra, transformations, palette de 256
+/// couleurs et réglages de rendu, repris des attributs de flam3.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Flame {
+    pub name: String,
+    pub xforms: Vec<Xform>,
+    /// Appliquée au point affiché seulement, pas à l'orbite.
+    pub final_xform: Option<Xform>,
+    pub palette: Vec<[u8; 3]>,
+    /// Taille de référence ; `scale` est en pixels par unité pour cette taille.
+    pub size: (usize, usize),
+    pub center: [f64; 2],
+    pub scale: f64,
+    /// Rotation de la caméra, en degrés.
+    pub rotate: f64,
+    /// Points tirés par pixel de l'image finale.
+    pub quality: f64,
+    /// Suréchantillonnage : l'histogramme fait `oversample`² cellules par pixel.
+    pub oversample: usize,
+    /// Estimation de densité : rayon du flou (en pixels) pour un point isolé,
+    /// décroissant comme densité^-curve jusqu'à `estimator_minimum`.
+    pub estimator_radius: f64,
+    pub estimator_minimum: f64,
+    pub estimator_curve: f64,
+    pub brightness: f64,
+    pub gamma: f64,
+    pub vibrancy: f64,
+    pub background: [u8; 3],
+    pub seed: u64,
+}
+
+impl Default for Flame {
+    /// Triangle de Sierpiński déformé par des variations sphérique et swirl.
+    fn default() -> Self {
+        let h = 3f64.sqrt() / 2.0;
+        let corners = [[-1.0, -h / 1.5], [1.0, -h / 1.5], [0.0, h * 4.0 / 3.0]];
+        let mut xforms: Vec<Xform> = corners
+            .iter()
+            .enumerate()
+            .map(|(i, &[x, y])| {
+                let mut affine = AffineMap::new([0.5, 0.0, 0.0, 0.5], [x / 2.0, y / 2.0], 1.0);
+                affine.color = i as f64 / 2.0;
+                Xform::new(affine, vec![(Variation::Linear, 1.0)])
+            })
+            .collect();
+        xforms[1].variations.push((Variation::Spherical, 0.15));
+        xforms[2].variations = vec![(Variation::Linear, 0.7), (Variation::Swirl, 0.3)];
+        Self {
+            name: String::from("Sierpiński tourbillonnant"),
+            xforms,
+            final_xform: None,
+            palette: (0..256u32).map(|i| [i as u8, (i * 3 / 4) as u8, (255 - i) as u8]).collect(),
+            size: (800, 600),
+            center: [0.0, 0.0],
+            scale: 220.0,
+            rotate: 0.0,
+            quality: 50.0,
+            oversample: 1,
+            estimator_radius: 9.0,
+            estimator_minimum: 0.0,
+            estimator_curve: 0.4,
+            brightness: 4.0,
+            gamma: 4.0,
+            vibrancy: 1.0,
+            background: [0, 0, 0],
+            seed: 1,
+        }
+    }
+}
+
+// Itérations ignorées au départ et après un point dégénéré.
+const FUSE: usize = 20;
+
+impl Flame {
+    /// Remplace la palette par 256 échantillons d'une palette de l'application.
+    pub fn set_palette(&mut self, scheme: &(dyn ColorScheme + Sync)) {
+        self.palette = (0..256)
+            .map(|i| {
+                let (r, g, b) = scheme.get_color(i as f64 / 255.0);
+                [r, g, b]
+            })
+            .collect();
+    }
+
+    // Poids cumulés des transformations, normalisés à 1.
+    fn cumulative(&self) -> Vec<f64> {
+        let total: f64 = self.xforms.iter().map(|x| x.affine.probability.max(0.0)).sum();
+        let mut sum = 0.0;
+        self.xforms
+            .iter()
+            .map(|x| {
+                sum += x.affine.probability.max(0.0) / total.max(f64::MIN_POSITIVE);
+                sum
+            })
+            .collect()
+    }
+
+    /// Rendu RGBA en `width` × `height`, cadré comme la taille de référence.
+    pub fn render(&self, (width, height): (usize, usize)) -> Vec<u8> {
+        let ss = self.oversample.clamp(1, 4);
+        let mut density = DensityBuffer::new(width * ss, height * ss);
+        let samples = (self.quality * (width * height) as f64) as usize;
+        if !self.xforms.is_empty() && width > 0 && height > 0 {
+            self.accumulate(&mut density, samples, (width, height), ss);
+        }
+        if self.estimator_radius > 0.0 {
+            density = self.estimate_density(&density, ss);
+        }
+        let pixels = downsample(&density, ss);
+        self.tone_map(&pixels, samples)
+    }
+
+    fn accumulate(&self, density: &mut DensityBuffer, samples: usize, (width, height): (usize, usize), ss: usize) {
+        let cumulative = self.cumulative();
+        let palette: Vec<[f32; 3]> = (0..256)
+            .map(|i| {
+                let [r, g, b] = self.palette.get(i).copied().unwrap_or([255; 3]);
+                [r as f32, g as f32, b as f32]
+            })
+            .collect();
+        // Caméra : même cadrage que la taille de référence, quel que soit le format.
+        let ppu = self.scale * (width as f64 / self.size.0.max(1) as f64).min(height as f64 / self.size.1.max(1) as f64);
+        let (sin_r, cos_r) = (-self.rotate.to_radians()).sin_cos();
+        let project = |[x, y]: [f64; 2]| {
+            let (dx, dy) = (x - self.center[0], y - self.center[1]);
+            let (rx, ry) = (dx * cos_r - dy * sin_r, dx * sin_r + dy * cos_r);
+            ((rx * ppu + width as f64 / 2.0) * ss as f64, (ry * ppu + height as f64 / 2.0) * ss as f64)
+        };
+
+        let threads = rayon::current_num_threads().max(1);
+        let per_thread = samples / threads + 1;
+        let (full_width, full_height) = (density.width, density.height);
+        let buffers: Vec<DensityBuffer> = (0..threads)
+            .into_par_iter()
+            .map(|thread| {
+                let mut local = DensityBuffer::new(full_width, full_height);
+                let mut rng = Rng::stream(self.seed, thread as u64);
+                let mut p = [rng.range(-1.0, 1.0), rng.range(-1.0, 1.0)];
+                let mut color = rng.next_f64();
+                let mut fuse = FUSE;
+                for _ in 0..per_thread + FUSE {
+                    let xform = &self.xforms[pick(&cumulative, &mut rng)];
+                    p = xform.apply(p, &mut rng);
+                    color = xform.blend_color(color);
+                    if !(p[0].is_finite() && p[1].is_finite()) || p[0].abs() > 1e10 || p[1].abs() > 1e10 {
+                        p = [rng.range(-1.0, 1.0), rng.range(-1.0, 1.0)];
+                        fuse = FUSE;
+                        continue;
+                    }
+                    if fuse > 0 {
+                        fuse -= 1;
+                        continue;
+                    }
+                    let (q, shown_color, opacity) = match &self.final_xform {
+                        Some(last) => (last.apply(p, &mut rng), last.blend_color(color), xform.opacity * last.opacity),
+                        None => (p, color, xform.opacity),
+                    };
+                    if opacity <= 0.0 {
+                        continue;
+                    }
+                    let (x, y) = project(q);
+                    if let Some(index) = local.index(x, y) {
+                        let entry = (shown_color.clamp(0.0, 1.0) * 255.0) as usize;
+                        local.add_color(index, palette[entry], opacity as f32);
+                    }
+                }
+                local
+            })
+            .collect();
+        for buffer in &buffers {
+            density.merge(buffer);
+        }
+    }
+
+    /// Flou à noyau variable : les cellules peu remplies sont étalées sur un
+    /// disque d'autant plus large qu'elles sont rares, ce qui lisse le bruit
+    /// sans brouiller les zones denses.
+    fn estimate_density(&self, density: &DensityBuffer, ss: usize) -> DensityBuffer {
+        let mut out = DensityBuffer::new(density.width, density.height);
+        let mut kernels: HashMap<u32, Vec<(isize, isize, f32)>> = HashMap::new();
+        let (width, height) = (density.width as isize, density.height as isize);
+        for y in 0..height {
+            for x in 0..width {
+                let cell = density.data[(y * width + x) as usize];
+                let count = cell[3];
+                if count <= 0.0 {
+                    continue;
+                }
+                // Un compte pondéré inférieur à 1 ne doit pas élargir le noyau au-delà du rayon maximal.
+                let radius = (self.estimator_radius / (count as f64).powf(self.estimator_curve))
+                    .min(self.estimator_radius)
+                    .max(self.estimator_minimum)
+                    * ss as f64;
+                // Rayon arrondi au quart de cellule, pour réutiliser les noyaux.
+                let key = (radius * 4.0).round() as u32;
+                if key < 4 {
+                    let target = &mut out.data[(y * width + x) as usize];
+                    for channel in 0..4 {
+                        target[channel] += cell[channel];
+                    }
+                    continue;
+                }
+                let kernel = kernels.entry(key).or_insert_with(|| kernel(key as f64 / 4.0));
+                for &(dx, dy, weight) in kernel.iter() {
+                    let (tx, ty) = (x + dx, y + dy);
+                    if tx < 0 || ty < 0 || tx >= width || ty >= height {
+                        continue;
+                    }
+                    let target = &mut out.data[(ty * width + tx) as usize];
+                    for channel in 0..4 {
+                        target[channel] += cell[channel] * weight;
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Échelle logarithmique de flam3 : α = brightness·ln(1 + n·k), où k
+    /// ramène n à la densité moyenne ; la couleur moyenne est éclaircie par
+    /// α^(1/γ) (vibrance) ou corrigée canal par canal, puis posée sur le fond.
+    fn tone_map(&self, pixels: &DensityBuffer, samples: usize) -> Vec<u8> {
+        let k = (pixels.width * pixels.height) as f64 / samples.max(1) as f64;
+        let inv_gamma = 1.0 / self.gamma.max(0.01);
+        let background = self.background.map(|c| c as f64 / 255.0);
+        let mut out = Vec::with_capacity(pixels.data.len() * 4);
+        for cell in &pixels.data {
+            let count = cell[3] as f64;
+            let mut rgb = background;
+            if count > 0.0 {
+                let a = self.brightness * 268.0 / 256.0 * (1.0 + count * k).ln();
+                let alpha = a.powf(inv_gamma);
+                for (channel, value) in rgb.iter_mut().enumerate() {
+                    let linear = cell[channel] as f64 / count / 255.0 * a;
+                    let vivid = linear * alpha / a;
+                    let plain = linear.max(0.0).powf(inv_gamma);
+                    *value = self.vibrancy * vivid + (1.0 - self.vibrancy) * plain + (1.0 - alpha.min(1.0)) * *value;
+                }
+            }
+            for value in rgb {
+                out.push((value.clamp(0.0, 1.0) * 255.0) as u8);
+            }
+            out.push(255);
+        }
+        out
+    }
+}
+
+#[inline]
+fn pick(cumulative: &[f64], rng: &mut Rng) -> usize {
+    let r = rng.next_f64();
+    cumulative.iter().position(|&p| r < p).unwrap_or(cumulative.len() - 1)
+}
+
+// Noyau gaussien tronqué au rayon, normalisé à 1.
+fn kernel(radius: f64) -> Vec<(isize, isize, f32)> {
+    let extent = radius.ceil() as isize;
+    let sigma2 = (radius / 2.0).powi(2);
+    let mut kernel = Vec::new();
+    for dy in -extent..=extent {
+        for dx in -extent..=extent {
+            let d2 = (dx * dx + dy * dy) as f64;
+            if d2 <= radius * radius {
+                kernel.push((dx, dy, (-d2 / (2.0 * sigma2)).exp()));
+            }
+        }
+    }
+    let total: f64 = kernel.iter().map(|k| k.2).sum();
+    kernel.into_iter().map(|(dx, dy, w)| (dx, dy, (w / total) as f32)).collect()
+}
+
+// Somme des blocs ss × ss de l'histogramme suréchantillonné.
+fn downsample(density: &DensityBuffer, ss: usize) -> DensityBuffer {
+    if ss == 1 {
+        return density.clone();
+    }
+    let (width, height) = (density.width / ss, density.height / ss);
+    let mut out = DensityBuffer::new(width, height);
+    for y in 0..density.height {
+        for x in 0..density.width {
+            let cell = density.data[y * density.width + x];
+            let target = &mut out.data[(y / ss) * width + x / ss];
+            for channel in 0..4 {
+                target[channel] += cell[channel];
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,416 @@
+use crate::flame::{Flame, Variation, Xform};
+use crate::ifs::AffineMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug)]
+pub enum FlameError {
+    Io(std::io::Error),
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for FlameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlameError::Io(err) => write!(f, "erreur d'entrée/sortie : {}", err),
+            FlameError::Parse { line, message } => write!(f, "ligne {} : {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for FlameError {}
+
+impl From<std::io::Error> for FlameError {
+    fn from(err: std::io::Error) -> Self {
+        FlameError::Io(err)
+    }
+}
+
+fn parse_error(line: usize, message: impl Into<String>) -> FlameError {
+    FlameError::Parse { line, message: message.into() }
+}
+
+/// Flamme importée, avec les variations inconnues qui ont été ignorées.
+#[derive(Clone, Debug)]
+pub struct ImportedFlame {
+    pub flame: Flame,
+    pub unsupported: Vec<String>,
+}
+
+/// Charge toutes les flammes d'un fichier `.flame` (un fichier en contient
+/// souvent plusieurs, dans un élément `<flames>`).
+pub fn load_flames(path: &Path) -> Result<Vec<ImportedFlame>, FlameError> {
+    parse_flames(&fs::read_to_string(path)?)
+}
+
+pub fn parse_flames(source: &str) -> Result<Vec<ImportedFlame>, FlameError> {
+    let root = parse_xml(source)?;
+    let mut flames = Vec::new();
+    collect_flames(&root, &mut flames)?;
+    if flames.is_empty() {
+        return Err(parse_error(1, "aucun élément <flame>"));
+    }
+    Ok(flames)
+}
+
+fn collect_flames(element: &Element, flames: &mut Vec<ImportedFlame>) -> Result<(), FlameError> {
+    if element.name == "flame" {
+        flames.push(parse_flame(element)?);
+    } else {
+        for child in &element.children {
+            collect_flames(child, flames)?;
+        }
+    }
+    Ok(())
+}
+
+// --- Éléments flam3 --------------------------------------------------------
+
+fn parse_flame(element: &Element) -> Result<ImportedFlame, FlameError> {
+    let mut flame = Flame { name: element.attr("name").unwrap_or("flamme").to_string(), xforms: Vec::new(), ..Flame::default() };
+    let mut unsupported = Vec::new();
+
+    if let Some(size) = element.numbers("size")? {
+        if let [width, height] = size[..] {
+            flame.size = (width.max(1.0) as usize, height.max(1.0) as usize);
+        }
+    }
+    if let Some(center) = element.numbers("center")? {
+        if let [x, y] = center[..] {
+            flame.center = [x, y];
+        }
+    }
+    flame.scale = element.number("scale")?.unwrap_or(flame.size.0 as f64 / 4.0);
+    if let Some(zoom) = element.number("zoom")? {
+        flame.scale *= zoom.exp2();
+    }
+    flame.rotate = element.number("rotate")?.unwrap_or(0.0);
+    flame.quality = element.number("quality")?.unwrap_or(flame.quality);
+    // « supersample » dans les fichiers récents de flam3, « oversample » dans les anciens.
+    let oversample = match element.number("supersample")? {
+        Some(value) => Some(value),
+        None => element.number("oversample")?,
+    };
+    flame.oversample = oversample.map_or(flame.oversample, |v| v.max(1.0) as usize);
+    flame.estimator_radius = element.number("estimator_radius")?.unwrap_or(flame.estimator_radius);
+    flame.estimator_minimum = element.number("estimator_minimum")?.unwrap_or(flame.estimator_minimum);
+    flame.estimator_curve = element.number("estimator_curve")?.unwrap_or(flame.estimator_curve);
+    flame.brightness = element.number("brightness")?.unwrap_or(flame.brightness);
+    flame.gamma = element.number("gamma")?.unwrap_or(flame.gamma);
+    flame.vibrancy = element.number("vibrancy")?.unwrap_or(flame.vibrancy);
+    if let Some(background) = element.numbers("background")? {
+        if let [r, g, b] = background[..] {
+            // flam3 écrit le fond en 0–1.
+            flame.background = [r, g, b].map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+        }
+    }
+
+    let mut palette = vec![[0u8; 3]; 256];
+    let mut has_palette = false;
+    for child in &element.children {
+        match child.name.as_str() {
+            "xform" => flame.xforms.push(parse_xform(child, &mut unsupported)?),
+            "finalxform" => flame.final_xform = Some(parse_xform(child, &mut unsupported)?),
+            "color" => {
+                let index = child.number("index")?.ok_or_else(|| parse_error(child.line, "<color> sans index"))?;
+                let rgb = child.numbers("rgb")?.ok_or_else(|| parse_error(child.line, "<color> sans rgb"))?;
+                if let (&[r, g, b], true) = (&rgb[..], (0.0..256.0).contains(&index)) {
+                    palette[index as usize] = [r, g, b].map(|c| c.clamp(0.0, 255.0) as u8);
+                    has_palette = true;
+                }
+            }
+            "palette" => {
+                parse_hex_palette(child, &mut palette)?;
+                has_palette = true;
+            }
+            _ => {}
+        }
+    }
+    if has_palette {
+        flame.palette = palette;
+    }
+    if flame.xforms.is_empty() {
+        return Err(parse_error(element.line, format!("la flamme « {} » n'a aucune transformation", flame.name)));
+    }
+    unsupported.sort();
+    unsupported.dedup();
+    Ok(ImportedFlame { flame, unsupported })
+}
+
+// Palette compacte : six chiffres hexadécimaux RRGGBB par couleur.
+fn parse_hex_palette(element: &Element, palette: &mut [[u8; 3]]) -> Result<(), FlameError> {
+    let digits: Vec<u8> = element
+        .text
+        .bytes()
+        .filter(|b| !b.is_ascii_whitespace())
+        .map(|b| match (b as char).to_digit(16) {
+            Some(d) => Ok(d as u8),
+            None => Err(parse_error(element.line, format!("caractère « {} » invalide dans la palette", b as char))),
+        })
+        .collect::<Result<_, _>>()?;
+    for (entry, rgb) in palette.iter_mut().zip(digits.chunks_exact(6)) {
+        *entry = [rgb[0] << 4 | rgb[1], rgb[2] << 4 | rgb[3], rgb[4] << 4 | rgb[5]];
+    }
+    Ok(())
+}
+
+// Les coefficients flam3 « a d b e c f » décrivent x' = a·x + b·y + c et
+// y' = d·x + e·y + f ; on les range dans l'ordre de `AffineMap`.
+fn parse_coefs(element: &Element, name: &str) -> Result<Option<AffineMap>, FlameError> {
+    match element.numbers(name)? {
+        None => Ok(None),
+        Some(values) => match values[..] {
+            [xx, yx, xy, yy, ox, oy] => Ok(Some(AffineMap::new([xx, xy, yx, yy], [ox, oy], 0.0))),
+            _ => Err(parse_error(element.line, format!("« {} » doit contenir six nombres", name))),
+        },
+    }
+}
+
+// Attributs d'une transformation qui ne sont pas des variations.
+const XFORM_ATTRIBUTES: [&str; 10] =
+    ["weight", "color", "coefs", "post", "symmetry", "color_speed", "opacity", "animate", "var_color", "name"];
+
+fn parse_xform(element: &Element, unsupported: &mut Vec<String>) -> Result<Xform, FlameError> {
+    let mut affine = parse_coefs(element, "coefs")?
+        .ok_or_else(|| parse_error(element.line, format!("<{}> sans coefs", element.name)))?;
+    affine.probability = element.number("weight")?.unwrap_or(if element.name == "finalxform" { 1.0 } else { 0.5 });
+    affine.color = element.number("color")?.unwrap_or(0.0).clamp(0.0, 1.0);
+
+    let mut variations = Vec::new();
+    for (name, value) in &element.attributes {
+        if XFORM_ATTRIBUTES.contains(&name.as_str()) {
+            continue;
+        }
+        match Variation::from_name(name) {
+            Some(variation) => {
+                let weight = value
+                    .trim()
+                    .parse::<f64>()
+                    .map_err(|_| parse_error(element.line, format!("poids « {} » invalide pour {}", value, name)))?;
+                if weight != 0.0 {
+                    variations.push((variation, weight));
+                }
+            }
+            // Paramètre (« julia_power », « blob_low »…) d'une variation connue ou
+            // présente dans l'élément ; « radial_blur » seul est une variation.
+            None if is_variation_parameter(element, name) => {}
+            None => unsupported.push(name.clone()),
+        }
+    }
+
+    let mut xform = Xform::new(affine, variations);
+    xform.post = parse_coefs(element, "post")?;
+    xform.opacity = element.number("opacity")?.unwrap_or(1.0);
+    xform.color_speed = match (element.number("color_speed")?, element.number("symmetry")?) {
+        (Some(speed), _) => speed,
+        (None, Some(symmetry)) => (1.0 - symmetry) / 2.0,
+        (None, None) if element.name == "finalxform" => 0.0,
+        (None, None) => 0.5,
+    };
+    Ok(xform)
+}
+
+fn is_variation_parameter(element: &Element, name: &str) -> bool {
+    let variations = Variation::ALL.iter().map(|v| v.name()).chain(
+        element
+            .attributes
+            .iter()
+            .map(|(n, _)| n.as_str())
+            .filter(|n| !XFORM_ATTRIBUTES.contains(n)),
+    );
+    variations
+        .filter(|&variation| variation != name)
+        .any(|variation| name.strip_prefix(variation).is_some_and(|rest| rest.starts_with('_')))
+}
+
+// --- XML minimal -----------------------------------------------------------
+
+/// Élément XML : de quoi lire les fichiers flam3, sans espaces de noms, DTD
+/// ni sections CDATA.
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Element>,
+    text: String,
+    line: usize,
+}
+
+impl Element {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+
+    fn numbers(&self, name: &str) -> Result<Option<Vec<f64>>, FlameError> {
+        match self.attr(name) {
+            None => Ok(None),
+            Some(value) => value
+                .split_whitespace()
+                .map(|v| v.parse::<f64>())
+                .collect::<Result<Vec<_>, _>>()
+                .map(Some)
+                .map_err(|_| parse_error(self.line, format!("valeur « {} » invalide pour {}", value, name))),
+        }
+    }
+
+    fn number(&self, name: &str) -> Result<Option<f64>, FlameError> {
+        Ok(self.numbers(name)?.and_then(|values| values.first().copied()))
+    }
+}
+
+// Profondeur d'éléments au-delà de laquelle le document est refusé, pour
+// borner la récursion ; un fichier flam3 en utilise trois.
+const MAX_DEPTH: usize = 256;
+
+struct XmlParser<'a> {
+    source: &'a str,
+    pos: usize,
+    /// Numéro de la ligne de `line_pos` : les retours à la ligne sont
+    /// comptés au fur et à mesure, `pos` ne reculant jamais.
+    line: usize,
+    line_pos: usize,
+    depth: usize,
+}
+
+impl<'a> XmlParser<'a> {
+    fn new(source: &'a str) -> Self {
+        Self { source, pos: 0, line: 1, line_pos: 0, depth: 0 }
+    }
+
+    fn line(&mut self) -> usize {
+        self.line += self.source[self.line_pos..self.pos].matches('\n').count();
+        self.line_pos = self.pos;
+        self.line
+    }
+
+    fn error(&mut self, message: impl Into<String>) -> FlameError {
+        parse_error(self.line(), message)
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.source[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    // Avance jusqu'après `end`.
+    fn skip_past(&mut self, end: &str) -> Result<(), FlameError> {
+        match self.rest().find(end) {
+            Some(offset) => {
+                self.pos += offset + end.len();
+                Ok(())
+            }
+            None => Err(self.error(format!("« {} » manquant", end))),
+        }
+    }
+
+    fn name(&mut self) -> Result<String, FlameError> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '-' | ':' | '.')))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error("nom attendu"));
+        }
+        self.pos += len;
+        Ok(rest[..len].to_string())
+    }
+
+    /// Lit un élément à partir de son « < ».
+    fn element(&mut self) -> Result<Element, FlameError> {
+        let line = self.line();
+        self.pos += 1;
+        let mut element = Element { name: self.name()?, line, ..Element::default() };
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("/>") {
+                self.pos += 2;
+                return Ok(element);
+            }
+            if rest.starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+            let name = self.name()?;
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                return Err(self.error(format!("« = » attendu après l'attribut {}", name)));
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+            let quote = match self.rest().chars().next() {
+                Some(q @ ('"' | '\'')) => q,
+                _ => return Err(self.error(format!("valeur entre guillemets attendue pour {}", name))),
+            };
+            self.pos += 1;
+            let end = self.rest().find(quote).ok_or_else(|| self.error("guillemet fermant manquant"))?;
+            let value = unescape(&self.rest()[..end]);
+            self.pos += end + 1;
+            element.attributes.push((name, value));
+        }
+
+        // Contenu : texte, commentaires et éléments enfants.
+        loop {
+            let rest = self.rest();
+            let next = rest.find('<').ok_or_else(|| self.error(format!("</{}> manquant", element.name)))?;
+            element.text.push_str(&unescape(&rest[..next]));
+            self.pos += next;
+            let rest = self.rest();
+            if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("</") {
+                self.pos += 2;
+                let name = self.name()?;
+                if name != element.name {
+                    return Err(self.error(format!("</{}> attendu, </{}> trouvé", element.name, name)));
+                }
+                self.skip_past(">")?;
+                return Ok(element);
+            } else {
+                if self.depth == MAX_DEPTH {
+                    return Err(self.error("éléments trop imbriqués"));
+                }
+                self.depth += 1;
+                let child = self.element();
+                self.depth -= 1;
+                element.children.push(child?);
+            }
+        }
+    }
+}
+
+fn unescape(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Racine du document ; prologue, commentaires et doctype sont ignorés.
+fn parse_xml(source: &str) -> Result<Element, FlameError> {
+    let mut parser = XmlParser::new(source);
+    loop {
+        parser.skip_whitespace();
+        let rest = parser.rest();
+        if rest.starts_with("<?") {
+            parser.skip_past("?>")?;
+        } else if rest.starts_with("<!--") {
+            parser.skip_past("-->")?;
+        } else if rest.starts_with("<!") {
+            parser.skip_past(">")?;
+        } else if rest.starts_with('<') {
+            return parser.element();
+        } else {
+            return Err(parser.error("élément racine attendu"));
+        }
+    }
+}
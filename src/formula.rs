@@ -0,0 +1,837 @@
+use crate::fractal_types::{FractalFunction, IterationResult, OrbitState};
+use num::Complex;
+use std::fmt;
+
+/// Langage de formules, par exemple :
+///
+/// ```text
+/// init z = c
+/// z = z^2 + p
+/// bailout |z| > 4
+/// ```
+///
+/// Les instructions sont séparées par `;` ou des retours à la ligne. Les
+/// instructions `init` s'exécutent une fois par pixel, les autres à chaque
+/// itération ; `bailout` donne le critère d'échappement (|z| > 2 par défaut).
+/// Variables prédéfinies : `z` (0 au départ), `c` (le pixel), `p` (le
+/// paramètre de la formule) et `zprev` (l'itéré précédent).
+pub const DEFAULT_FORMULA: &str = "z = z^2 + c\nbailout |z| > 2";
+
+const PREDEFINED: [&str; 4] = ["z", "c", "p", "zprev"];
+const Z: usize = 0;
+const C: usize = 1;
+const P: usize = 2;
+const ZPREV: usize = 3;
+const MAX_REGISTERS: usize = 16;
+const MAX_STACK: usize = 32;
+// Parenthèses, appels, négations et puissances imbriqués : borne la
+// récursion de l'analyseur.
+const MAX_NESTING: usize = 256;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FormulaError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for FormulaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ligne {}, colonne {} : {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for FormulaError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Function {
+    Sin,
+    Cos,
+    Tan,
+    Sinh,
+    Cosh,
+    Tanh,
+    Exp,
+    Log,
+    Sqrt,
+    Conj,
+    Abs,
+    Re,
+    Im,
+    Arg,
+    Pow,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Function> {
+        Some(match name {
+            "sin" => Function::Sin,
+            "cos" => Function::Cos,
+            "tan" => Function::Tan,
+            "sinh" => Function::Sinh,
+            "cosh" => Function::Cosh,
+            "tanh" => Function::Tanh,
+            "exp" => Function::Exp,
+            "log" => Function::Log,
+            "sqrt" => Function::Sqrt,
+            "conj" => Function::Conj,
+            "abs" => Function::Abs,
+            "re" => Function::Re,
+            "im" => Function::Im,
+            "arg" => Function::Arg,
+            "pow" => Function::Pow,
+            _ => return None,
+        })
+    }
+
+    fn arity(&self) -> usize {
+        match self {
+            Function::Pow => 2,
+            _ => 1,
+        }
+    }
+
+    #[inline]
+    fn apply(&self, z: Complex<f64>) -> Complex<f64> {
+        match self {
+            Function::Sin => z.sin(),
+            Function::Cos => z.cos(),
+            Function::Tan => z.tan(),
+            Function::Sinh => z.sinh(),
+            Function::Cosh => z.cosh(),
+            Function::Tanh => z.tanh(),
+            Function::Exp => z.exp(),
+            Function::Log => z.ln(),
+            Function::Sqrt => z.sqrt(),
+            Function::Conj => z.conj(),
+            Function::Abs => Complex::new(z.norm(), 0.0),
+            Function::Re => Complex::new(z.re, 0.0),
+            Function::Im => Complex::new(z.im, 0.0),
+            Function::Arg => Complex::new(z.arg(), 0.0),
+            Function::Pow => unreachable!("pow est binaire"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Number(Complex<f64>),
+    Variable(usize),
+    Neg(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Call(Function, Vec<Expr>),
+}
+
+/// Condition d'échappement ; les comparaisons portent sur les parties réelles.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Condition {
+    Compare(Comparison, Expr, Expr),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Statement {
+    pub target: usize,
+    pub expr: Expr,
+    pub init: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Program {
+    pub statements: Vec<Statement>,
+    pub bailout: Option<Condition>,
+    pub variables: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Imaginary(f64),
+    Ident(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Caret,
+    LParen,
+    RParen,
+    Pipe,
+    Comma,
+    Assign,
+    Separator,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+    End,
+}
+
+#[derive(Clone, Debug)]
+struct Spanned {
+    token: Token,
+    line: usize,
+    column: usize,
+}
+
+fn error(line: usize, column: usize, message: impl Into<String>) -> FormulaError {
+    FormulaError { line, column, message: message.into() }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Spanned>, FormulaError> {
+    let mut tokens = Vec::new();
+    for (line_index, line) in source.lines().enumerate() {
+        let line_number = line_index + 1;
+        let chars: Vec<char> = line.chars().collect();
+        let mut i = 0;
+        while i < chars.len() {
+            let ch = chars[i];
+            let column = i + 1;
+            if ch.is_whitespace() {
+                i += 1;
+                continue;
+            }
+            // Commentaire jusqu'à la fin de la ligne.
+            if ch == '#' {
+                break;
+            }
+            let token = if ch.is_ascii_digit() || (ch == '.' && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit())) {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                // Exposant, sans confondre 2e avec 2·e.
+                if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                    let mut j = i + 1;
+                    if j < chars.len() && (chars[j] == '+' || chars[j] == '-') {
+                        j += 1;
+                    }
+                    if j < chars.len() && chars[j].is_ascii_digit() {
+                        i = j;
+                        while i < chars.len() && chars[i].is_ascii_digit() {
+                            i += 1;
+                        }
+                    }
+                }
+                let text: String = chars[start..i].iter().collect();
+                let value = text
+                    .parse::<f64>()
+                    .map_err(|_| error(line_number, column, format!("nombre invalide « {} »", text)))?;
+                let imaginary = i < chars.len()
+                    && chars[i] == 'i'
+                    && !chars.get(i + 1).is_some_and(|c| c.is_alphanumeric() || *c == '_');
+                if imaginary {
+                    i += 1;
+                    Token::Imaginary(value)
+                } else {
+                    Token::Number(value)
+                }
+            } else if ch.is_alphabetic() || ch == '_' {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                Token::Ident(chars[start..i].iter().collect())
+            } else {
+                let next = chars.get(i + 1).copied();
+                let (token, width) = match (ch, next) {
+                    ('>', Some('=')) => (Token::GreaterEq, 2),
+                    ('<', Some('=')) => (Token::LessEq, 2),
+                    ('>', _) => (Token::Greater, 1),
+                    ('<', _) => (Token::Less, 1),
+                    ('+', _) => (Token::Plus, 1),
+                    ('-', _) => (Token::Minus, 1),
+                    ('*', _) => (Token::Star, 1),
+                    ('/', _) => (Token::Slash, 1),
+                    ('^', _) => (Token::Caret, 1),
+                    ('(', _) => (Token::LParen, 1),
+                    (')', _) => (Token::RParen, 1),
+                    ('|', _) => (Token::Pipe, 1),
+                    (',', _) => (Token::Comma, 1),
+                    ('=', _) => (Token::Assign, 1),
+                    (';', _) => (Token::Separator, 1),
+                    _ => return Err(error(line_number, column, format!("caractère inattendu « {} »", ch))),
+                };
+                i += width;
+                token
+            };
+            tokens.push(Spanned { token, line: line_number, column });
+        }
+        tokens.push(Spanned { token: Token::Separator, line: line_number, column: chars.len() + 1 });
+    }
+    let (line, column) = tokens.last().map(|t| (t.line, t.column)).unwrap_or((1, 1));
+    tokens.push(Spanned { token: Token::End, line, column });
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Spanned>,
+    pos: usize,
+    variables: Vec<String>,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos].token
+    }
+
+    fn current(&self) -> &Spanned {
+        &self.tokens[self.pos]
+    }
+
+    fn advance(&mut self) -> Spanned {
+        let spanned = self.tokens[self.pos].clone();
+        if self.pos + 1 < self.tokens.len() {
+            self.pos += 1;
+        }
+        spanned
+    }
+
+    fn error_here(&self, message: impl Into<String>) -> FormulaError {
+        let current = self.current();
+        error(current.line, current.column, message)
+    }
+
+    fn expect(&mut self, token: Token, what: &str) -> Result<(), FormulaError> {
+        if *self.peek() == token {
+            self.advance();
+            Ok(())
+        } else {
+            Err(self.error_here(format!("{} attendu", what)))
+        }
+    }
+
+    fn program(&mut self) -> Result<Program, FormulaError> {
+        let mut statements = Vec::new();
+        let mut bailout = None;
+        loop {
+            match self.peek().clone() {
+                Token::End => break,
+                Token::Separator => {
+                    self.advance();
+                }
+                Token::Ident(word) if word == "bailout" => {
+                    if bailout.is_some() {
+                        return Err(self.error_here("un seul critère bailout est permis"));
+                    }
+                    self.advance();
+                    bailout = Some(self.condition()?);
+                    self.end_of_statement()?;
+                }
+                Token::Ident(word) => {
+                    let init = word == "init";
+                    if init {
+                        self.advance();
+                    }
+                    statements.push(self.assignment(init)?);
+                    self.end_of_statement()?;
+                }
+                _ => return Err(self.error_here("instruction attendue (affectation ou bailout)")),
+            }
+        }
+        if statements.iter().all(|s| s.init) {
+            return Err(error(1, 1, "la formule n'a aucune instruction d'itération"));
+        }
+        Ok(Program { statements, bailout, variables: self.variables.clone() })
+    }
+
+    fn end_of_statement(&mut self) -> Result<(), FormulaError> {
+        match self.peek() {
+            Token::Separator | Token::End => Ok(()),
+            _ => Err(self.error_here("fin d'instruction attendue")),
+        }
+    }
+
+    fn assignment(&mut self, init: bool) -> Result<Statement, FormulaError> {
+        let target = self.advance();
+        let name = match target.token {
+            Token::Ident(name) => name,
+            _ => return Err(error(target.line, target.column, "nom de variable attendu")),
+        };
+        if is_reserved(&name) {
+            return Err(error(target.line, target.column, format!("« {} » est réservé", name)));
+        }
+        self.expect(Token::Assign, "« = »")?;
+        let expr = self.expr()?;
+        // La variable n'existe qu'après son affectation.
+        let target = match self.variables.iter().position(|v| *v == name) {
+            Some(index) => index,
+            None => {
+                if self.variables.len() == MAX_REGISTERS {
+                    return Err(error(target.line, target.column, "trop de variables"));
+                }
+                self.variables.push(name);
+                self.variables.len() - 1
+            }
+        };
+        Ok(Statement { target, expr, init })
+    }
+
+    fn condition(&mut self) -> Result<Condition, FormulaError> {
+        let mut left = self.comparison()?;
+        loop {
+            match self.peek() {
+                Token::Ident(word) if word == "and" => {
+                    self.advance();
+                    left = Condition::And(Box::new(left), Box::new(self.comparison()?));
+                }
+                Token::Ident(word) if word == "or" => {
+                    self.advance();
+                    left = Condition::Or(Box::new(left), Box::new(self.comparison()?));
+                }
+                _ => return Ok(left),
+            }
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Condition, FormulaError> {
+        let left = self.expr()?;
+        let comparison = match self.peek() {
+            Token::Greater => Comparison::Greater,
+            Token::GreaterEq => Comparison::GreaterEq,
+            Token::Less => Comparison::Less,
+            Token::LessEq => Comparison::LessEq,
+            _ => return Err(self.error_here("comparaison attendue (>, >=, <, <=)")),
+        };
+        self.advance();
+        let right = self.expr()?;
+        Ok(Condition::Compare(comparison, left, right))
+    }
+
+    fn expr(&mut self) -> Result<Expr, FormulaError> {
+        let mut left = self.term()?;
+        loop {
+            let op = match self.peek() {
+                Token::Plus => BinaryOp::Add,
+                Token::Minus => BinaryOp::Sub,
+                _ => return Ok(left),
+            };
+            self.advance();
+            left = Expr::Binary(op, Box::new(left), Box::new(self.term()?));
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr, FormulaError> {
+        let mut left = self.unary()?;
+        loop {
+            let op = match self.peek() {
+                Token::Star => BinaryOp::Mul,
+                Token::Slash => BinaryOp::Div,
+                _ => return Ok(left),
+            };
+            self.advance();
+            left = Expr::Binary(op, Box::new(left), Box::new(self.unary()?));
+        }
+    }
+
+    // Toute récursion de l'analyseur passe par ici.
+    fn unary(&mut self) -> Result<Expr, FormulaError> {
+        if self.depth >= MAX_NESTING {
+            return Err(self.error_here("expression trop imbriquée"));
+        }
+        self.depth += 1;
+        let result = self.signed_power();
+        self.depth -= 1;
+        result
+    }
+
+    // -z^2 vaut -(z^2) ; ^ est associatif à droite.
+    fn signed_power(&mut self) -> Result<Expr, FormulaError> {
+        if *self.peek() == Token::Minus {
+            self.advance();
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        let base = self.primary()?;
+        if *self.peek() == Token::Caret {
+            self.advance();
+            let exponent = self.unary()?;
+            return Ok(Expr::Binary(BinaryOp::Pow, Box::new(base), Box::new(exponent)));
+        }
+        Ok(base)
+    }
+
+    fn primary(&mut self) -> Result<Expr, FormulaError> {
+        let spanned = self.advance();
+        match spanned.token {
+            Token::Number(value) => Ok(Expr::Number(Complex::new(value, 0.0))),
+            Token::Imaginary(value) => Ok(Expr::Number(Complex::new(0.0, value))),
+            Token::LParen => {
+                let expr = self.expr()?;
+                self.expect(Token::RParen, "« ) »")?;
+                Ok(expr)
+            }
+            Token::Pipe => {
+                let expr = self.expr()?;
+                self.expect(Token::Pipe, "« | »")?;
+                Ok(Expr::Call(Function::Abs, vec![expr]))
+            }
+            Token::Ident(name) => {
+                if *self.peek() == Token::LParen {
+                    let function = Function::from_name(&name)
+                        .ok_or_else(|| error(spanned.line, spanned.column, format!("fonction inconnue « {} »", name)))?;
+                    self.advance();
+                    let mut args = vec![self.expr()?];
+                    while *self.peek() == Token::Comma {
+                        self.advance();
+                        args.push(self.expr()?);
+                    }
+                    self.expect(Token::RParen, "« ) »")?;
+                    if args.len() != function.arity() {
+                        return Err(error(
+                            spanned.line,
+                            spanned.column,
+                            format!("« {} » attend {} argument(s)", name, function.arity()),
+                        ));
+                    }
+                    return Ok(Expr::Call(function, args));
+                }
+                match name.as_str() {
+                    "i" => Ok(Expr::Number(Complex::new(0.0, 1.0))),
+                    "pi" => Ok(Expr::Number(Complex::new(std::f64::consts::PI, 0.0))),
+                    "e" => Ok(Expr::Number(Complex::new(std::f64::consts::E, 0.0))),
+                    _ => match self.variables.iter().position(|v| *v == name) {
+                        Some(index) => Ok(Expr::Variable(index)),
+                        None => Err(error(spanned.line, spanned.column, format!("variable inconnue « {} »", name))),
+                    },
+                }
+            }
+            Token::End | Token::Separator => Err(error(spanned.line, spanned.column, "expression incomplète")),
+            _ => Err(error(spanned.line, spanned.column, "expression attendue")),
+        }
+    }
+}
+
+fn is_reserved(name: &str) -> bool {
+    matches!(name, "init" | "bailout" | "and" | "or" | "i" | "pi" | "e") || Function::from_name(name).is_some()
+}
+
+pub fn parse(source: &str) -> Result<Program, FormulaError> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        pos: 0,
+        variables: PREDEFINED.iter().map(|s| s.to_string()).collect(),
+        depth: 0,
+    };
+    parser.program()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Op {
+    Const(Complex<f64>),
+    Load(u8),
+    Store(u8),
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Neg,
+    Square,
+    /// Puissance entière constante, par multiplications successives.
+    PowI(i32),
+    /// |x|², pour comparer un module sans racine carrée.
+    NormSqr,
+    Pow,
+    Call(Function),
+    Compare(Comparison),
+    And,
+    Or,
+}
+
+fn compile_expr(expr: &Expr, code: &mut Vec<Op>) {
+    match expr {
+        Expr::Number(value) => code.push(Op::Const(*value)),
+        Expr::Variable(index) => code.push(Op::Load(*index as u8)),
+        Expr::Neg(inner) => {
+            compile_expr(inner, code);
+            code.push(Op::Neg);
+        }
+        Expr::Binary(BinaryOp::Pow, base, exponent) => {
+            compile_expr(base, code);
+            match **exponent {
+                Expr::Number(n) if n.im == 0.0 && n.re == 2.0 => code.push(Op::Square),
+                Expr::Number(n) if n.im == 0.0 && n.re.fract() == 0.0 && n.re.abs() <= 64.0 => {
+                    code.push(Op::PowI(n.re as i32));
+                }
+                _ => {
+                    compile_expr(exponent, code);
+                    code.push(Op::Pow);
+                }
+            }
+        }
+        Expr::Binary(op, left, right) => {
+            compile_expr(left, code);
+            compile_expr(right, code);
+            code.push(match op {
+                BinaryOp::Add => Op::Add,
+                BinaryOp::Sub => Op::Sub,
+                BinaryOp::Mul => Op::Mul,
+                BinaryOp::Div => Op::Div,
+                BinaryOp::Pow => unreachable!(),
+            });
+        }
+        Expr::Call(Function::Pow, args) => {
+            compile_expr(&Expr::Binary(BinaryOp::Pow, Box::new(args[0].clone()), Box::new(args[1].clone())), code);
+        }
+        Expr::Call(function, args) => {
+            compile_expr(&args[0], code);
+            code.push(Op::Call(*function));
+        }
+    }
+}
+
+fn compile_condition(condition: &Condition, code: &mut Vec<Op>) {
+    match condition {
+        // |x| > k devient |x|² > k², bien plus rapide.
+        Condition::Compare(comparison, Expr::Call(Function::Abs, args), Expr::Number(k)) if k.im == 0.0 && k.re >= 0.0 => {
+            compile_expr(&args[0], code);
+            code.push(Op::NormSqr);
+            code.push(Op::Const(Complex::new(k.re * k.re, 0.0)));
+            code.push(Op::Compare(*comparison));
+        }
+        Condition::Compare(comparison, left, right) => {
+            compile_expr(left, code);
+            compile_expr(right, code);
+            code.push(Op::Compare(*comparison));
+        }
+        Condition::And(left, right) => {
+            compile_condition(left, code);
+            compile_condition(right, code);
+            code.push(Op::And);
+        }
+        Condition::Or(left, right) => {
+            compile_condition(left, code);
+            compile_condition(right, code);
+            code.push(Op::Or);
+        }
+    }
+}
+
+fn stack_depth(code: &[Op]) -> usize {
+    let (mut depth, mut max) = (0usize, 0usize);
+    for op in code {
+        match op {
+            Op::Const(_) | Op::Load(_) => depth += 1,
+            Op::Store(_) => depth -= 1,
+            Op::Add | Op::Sub | Op::Mul | Op::Div | Op::Pow | Op::Compare(_) | Op::And | Op::Or => depth -= 1,
+            Op::Neg | Op::Square | Op::PowI(_) | Op::NormSqr | Op::Call(_) => {}
+        }
+        max = max.max(depth);
+    }
+    max
+}
+
+type Registers = [Complex<f64>; MAX_REGISTERS];
+type Stack = [Complex<f64>; MAX_STACK];
+
+const EMPTY_STACK: Stack = [Complex::new(0.0, 0.0); MAX_STACK];
+
+#[inline]
+fn truth(value: bool) -> Complex<f64> {
+    Complex::new(if value { 1.0 } else { 0.0 }, 0.0)
+}
+
+#[inline]
+fn run(code: &[Op], registers: &mut Registers, stack: &mut Stack) -> Complex<f64> {
+    let zero = Complex::new(0.0, 0.0);
+    let mut top = 0usize;
+    for op in code {
+        match *op {
+            Op::Const(value) => {
+                stack[top] = value;
+                top += 1;
+            }
+            Op::Load(index) => {
+                stack[top] = registers[index as usize];
+                top += 1;
+            }
+            Op::Store(index) => {
+                top -= 1;
+                registers[index as usize] = stack[top];
+            }
+            Op::Neg => stack[top - 1] = -stack[top - 1],
+            Op::Square => stack[top - 1] = stack[top - 1] * stack[top - 1],
+            Op::NormSqr => stack[top - 1] = Complex::new(stack[top - 1].norm_sqr(), 0.0),
+            Op::PowI(n) => stack[top - 1] = stack[top - 1].powi(n),
+            Op::Call(function) => stack[top - 1] = function.apply(stack[top - 1]),
+            Op::Add => {
+                top -= 1;
+                stack[top - 1] += stack[top];
+            }
+            Op::Sub => {
+                top -= 1;
+                stack[top - 1] -= stack[top];
+            }
+            Op::Mul => {
+                top -= 1;
+                stack[top - 1] *= stack[top];
+            }
+            Op::Div => {
+                top -= 1;
+                stack[top - 1] /= stack[top];
+            }
+            Op::Pow => {
+                top -= 1;
+                let (a, b) = (stack[top - 1], stack[top]);
+                stack[top - 1] = if a == zero { zero } else { a.powc(b) };
+            }
+            Op::Compare(comparison) => {
+                top -= 1;
+                let (a, b) = (stack[top - 1].re, stack[top].re);
+                let result = match comparison {
+                    Comparison::Greater => a > b,
+                    Comparison::GreaterEq => a >= b,
+                    Comparison::Less => a < b,
+                    Comparison::LessEq => a <= b,
+                };
+                stack[top - 1] = truth(result);
+            }
+            Op::And => {
+                top -= 1;
+                stack[top - 1] = truth(stack[top - 1].re != 0.0 && stack[top].re != 0.0);
+            }
+            Op::Or => {
+                top -= 1;
+                stack[top - 1] = truth(stack[top - 1].re != 0.0 || stack[top].re != 0.0);
+            }
+        }
+    }
+    if top > 0 {
+        stack[top - 1]
+    } else {
+        zero
+    }
+}
+
+/// Formule compilée en bytecode à pile, exécutée pour chaque pixel.
+#[derive(Clone)]
+pub struct CompiledFormula {
+    init: Vec<Op>,
+    step: Vec<Op>,
+    bailout: Option<Vec<Op>>,
+    /// Rayon² quand le critère se réduit à |z| > r : testé sans interpréteur.
+    norm_bailout: Option<f64>,
+    parameter: Complex<f64>,
+}
+
+impl Default for CompiledFormula {
+    fn default() -> Self {
+        Self::compile(DEFAULT_FORMULA).expect("formule par défaut valide")
+    }
+}
+
+impl CompiledFormula {
+    pub fn compile(source: &str) -> Result<Self, FormulaError> {
+        let program = parse(source)?;
+        let mut init = Vec::new();
+        let mut step = Vec::new();
+        for statement in &program.statements {
+            let code = if statement.init { &mut init } else { &mut step };
+            compile_expr(&statement.expr, code);
+            code.push(Op::Store(statement.target as u8));
+        }
+        let bailout = program.bailout.as_ref().map(|condition| {
+            let mut code = Vec::new();
+            compile_condition(condition, &mut code);
+            code
+        });
+        let depth = [&init, &step].into_iter().chain(bailout.as_ref()).map(|code| stack_depth(code)).max().unwrap_or(0);
+        if depth > MAX_STACK {
+            return Err(error(1, 1, "expression trop imbriquée"));
+        }
+        let norm_bailout = match bailout.as_deref() {
+            None => Some(4.0),
+            // Load(0) : le registre de z.
+            Some([Op::Load(0), Op::NormSqr, Op::Const(r2), Op::Compare(Comparison::Greater)]) => Some(r2.re),
+            Some(_) => None,
+        };
+        Ok(Self {
+            init,
+            step,
+            bailout,
+            norm_bailout,
+            parameter: Complex::new(0.0, 0.0),
+        })
+    }
+
+    /// Valeur de la variable `p`.
+    pub fn with_parameter(mut self, p: Complex<f64>) -> Self {
+        self.parameter = p;
+        self
+    }
+
+    fn registers(&self, c: Complex<f64>) -> Registers {
+        let mut registers = [Complex::new(0.0, 0.0); MAX_REGISTERS];
+        registers[C] = c;
+        registers[P] = self.parameter;
+        registers
+    }
+
+    #[inline]
+    fn bailout_reached(&self, registers: &mut Registers, stack: &mut Stack) -> bool {
+        match (self.norm_bailout, &self.bailout) {
+            (Some(radius_sqr), _) => registers[Z].norm_sqr() > radius_sqr,
+            (None, Some(code)) => run(code, registers, stack).re != 0.0,
+            (None, None) => unreachable!("critère par défaut |z| > 2"),
+        }
+    }
+}
+
+impl FractalFunction for CompiledFormula {
+    fn iterate(&self, c: Complex<f64>, _z: Complex<f64>, max_iter: u32) -> IterationResult {
+        let mut registers = self.registers(c);
+        let mut stack = EMPTY_STACK;
+        run(&self.init, &mut registers, &mut stack);
+        let mut i = 0;
+
+        while i < max_iter && !self.bailout_reached(&mut registers, &mut stack) {
+            let previous = registers[Z];
+            run(&self.step, &mut registers, &mut stack);
+            registers[ZPREV] = previous;
+            i += 1;
+        }
+        IterationResult::new(i, registers[Z])
+    }
+
+    /// Seuls z et zprev passent d'un pas à l'autre ; les instructions `init`
+    /// ne sont pas rejouées.
+    fn step(&self, c: Complex<f64>, state: &mut OrbitState) {
+        let mut registers = self.registers(c);
+        registers[Z] = state.z;
+        registers[ZPREV] = state.previous;
+        let mut stack = EMPTY_STACK;
+        run(&self.step, &mut registers, &mut stack);
+        state.advance(registers[Z]);
+    }
+
+    fn initial_z(&self, c: Complex<f64>) -> Complex<f64> {
+        let mut registers = self.registers(c);
+        let mut stack = EMPTY_STACK;
+        run(&self.init, &mut registers, &mut stack);
+        registers[Z]
+    }
+
+    fn escaped(&self, z: Complex<f64>) -> bool {
+        let mut registers = self.registers(Complex::new(0.0, 0.0));
+        registers[Z] = z;
+        let mut stack = EMPTY_STACK;
+        self.bailout_reached(&mut registers, &mut stack)
+    }
+}
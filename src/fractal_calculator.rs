@@ -1,8 +1,10 @@
 use num::Complex;
 use rayon::prelude::*;
 use crate::fractal_params::FractalParams;
-use crate::fractal_types::{FractalFunction, MandelbrotSet, JuliaSet, BurningShip, Tricorn, NewtonSet};
-use crate::color_schemes::ColorScheme;
+use crate::formula::{CompiledFormula, FormulaError};
+use crate::hybrid::HybridSet;
+use crate::fractal_types::{FractalFunction, IterationResult, MandelbrotSet, JuliaSet, Multibrot, BurningShip, Tricorn, NewtonSet, NovaSet, PhoenixSet, MagnetSet, AbsFormula, TranscendentalMap, TranscendentalSet, McMullenSet, LyapunovSet};
+use crate::color_schemes::{basin_color, smooth_convergence, smooth_distance, smooth_iteration, ColorScheme};
 use std::collections::HashMap;
 use std::sync::Arc;
 use parking_lot::RwLock;
@@ -13,7 +15,7 @@ pub struct FractalCalculator<F: FractalFunction> {
     fractal: F,
     thread_count: usize,
     quality_level: QualityLevel,
-    cache: HashMap<(i32, i32), (u32, Complex<f64>)>,
+    cache: HashMap<(i32, i32), IterationResult>,
     cache_enabled: bool,
     shared_buffer: Option<SharedMemoryBuffer>,
 }
@@ -38,6 +40,63 @@ pub struct PrecomputedData {
     color_lookup: Vec<(u8, u8, u8)>,
 }
 
+/// Itérations lissées par pixel, conservées pour recoloriser sans recalculer.
+/// Les points restés dans l'ensemble (ou n'ayant convergé vers aucune racine)
+/// valent `NaN`.
+#[derive(Clone)]
+pub struct IterationData {
+    pub size: (usize, usize),
+    pub max_iterations: u32,
+    pub smooth: Vec<f64>,
+    /// Nombre de racines des fractales de recherche de racines, 0 sinon.
+    pub root_count: usize,
+    /// Racine atteinte par chaque pixel ; vide si `root_count` vaut 0.
+    pub basins: Vec<Option<u16>>,
+    /// Pixels dont l'orbite a convergé vers un attracteur fini plutôt que de
+    /// s'échapper ; leur valeur lissée compte alors les pas de convergence.
+    pub converged: Vec<bool>,
+}
+
+impl IterationData {
+    /// Colorise en RGBA. Un `offset` non nul décale la palette et l'enroule
+    /// sur elle-même (cycle de couleurs).
+    pub fn colorize(&self, color_scheme: &(dyn ColorScheme + Sync), offset: f64) -> Vec<u8> {
+        let mut image_data = vec![0u8; self.smooth.len() * 4];
+        let max_iterations = self.max_iterations.max(1) as f64;
+
+        if self.root_count > 0 {
+            image_data
+                .par_chunks_mut(4)
+                .zip(self.smooth.par_iter().zip(self.basins.par_iter()))
+                .for_each(|(pixel, (&smooth_iter, &root))| {
+                    let color = match root {
+                        Some(root) => basin_color(root as usize, self.root_count, smooth_iter, self.max_iterations, offset),
+                        None => (0, 0, 0),
+                    };
+                    pixel.copy_from_slice(&[color.0, color.1, color.2, 255]);
+                });
+            return image_data;
+        }
+
+        image_data
+            .par_chunks_mut(4)
+            .zip(self.smooth.par_iter().zip(self.converged.par_iter()))
+            .for_each(|(pixel, (&smooth_iter, &converged))| {
+                let color = if smooth_iter.is_nan() {
+                    (0, 0, 0)
+                } else {
+                    let t = smooth_iter / max_iterations;
+                    let t = if offset == 0.0 { t } else { (t + offset).rem_euclid(1.0) };
+                    // La région de convergence parcourt la palette à rebours.
+                    color_scheme.get_color(if converged { 1.0 - t } else { t })
+                };
+                pixel.copy_from_slice(&[color.0, color.1, color.2, 255]);
+            });
+
+        image_data
+    }
+}
+
 #[derive(Clone)]
 pub struct SharedMemoryBuffer {
     data: Arc<RwLock<Vec<u8>>>,
@@ -58,8 +117,12 @@ impl<F: FractalFunction + Sync> FractalCalculator<F> {
     }
 
     pub fn generate(&self, color_scheme: &(dyn ColorScheme + Sync)) -> Vec<u8> {
+        self.compute().colorize(color_scheme, 0.0)
+    }
+
+    /// Calcule les itérations lissées de chaque pixel, sans les coloriser.
+    pub fn compute(&self) -> IterationData {
         let (width, height) = self.params.size;
-        let mut image_data = vec![0u8; width * height * 4];
 
         let scale = match self.quality_level {
             QualityLevel::Low => 4,
@@ -67,47 +130,89 @@ impl<F: FractalFunction + Sync> FractalCalculator<F> {
             QualityLevel::High => 1,
         };
 
-        let scaled_width = width / scale;
-        let scaled_height = height / scale;
+        let scaled_width = (width / scale).max(1);
+        let scaled_height = (height / scale).max(1);
 
-        let pixels: Vec<(usize, (u8, u8, u8))> = (0..scaled_height)
+        let roots = self.fractal.roots();
+        let tolerance = self.fractal.tolerance();
+        let degree = self.fractal.escape_degree();
+        let attractor = self.fractal.attractor();
+        let pixels_per_unit = self.params.zoom * scaled_width as f64;
+
+        let values: Vec<(f64, Option<u16>, bool)> = (0..scaled_height)
             .into_par_iter()
-            .with_max_len(height / self.thread_count)
+            .with_max_len((height / self.thread_count).max(1))
             .flat_map(|y| {
                 (0..scaled_width).into_par_iter().map(move |x| {
                     let cx = (x as f64 / scaled_width as f64 - 0.5) / self.params.zoom + self.params.center.re;
                     let cy = (y as f64 / scaled_height as f64 - 0.5) / self.params.zoom + self.params.center.im;
                     let c = Complex::new(cx, cy);
+                    // L'estimation de distance remplace l'itération lissée.
+                    if self.params.distance_estimation {
+                        if let Some(distance) = self.fractal.distance(c, self.params.max_iterations) {
+                            return (smooth_distance(distance * pixels_per_unit, self.params.max_iterations).unwrap_or(f64::NAN), None, false);
+                        }
+                    }
                     
                     let key = (x as i32, y as i32);
-                    let (iterations, z) = if let Some(&result) = self.cache.get(&key) {
+                    let result = if let Some(&result) = self.cache.get(&key) {
                         result
                     } else {
-                        self.fractal.iterate(c, self.fractal.initial_z(), self.params.max_iterations)
+                        self.fractal.iterate(c, self.fractal.initial_z(c), self.params.max_iterations)
                     };
                     
-                    let color = color_scheme.smooth_color(iterations, self.params.max_iterations, z.norm_sqr());
-                    (y * scaled_width + x, color)
+                    if let Some(value) = self.fractal.smooth_value(&result, self.params.max_iterations) {
+                        return (value, None, result.is_converged());
+                    }
+                    match result.root {
+                        Some(root) => {
+                            let distance = (result.z - roots[root]).norm();
+                            (smooth_convergence(result.iterations, distance, tolerance), Some(root as u16), true)
+                        }
+                        None if result.is_converged() => {
+                            let smooth = match attractor {
+                                Some(attractor) => smooth_convergence(result.iterations, (result.z - attractor).norm(), tolerance),
+                                None => result.iterations as f64,
+                            };
+                            (smooth, None, true)
+                        }
+                        // Sans racines connues, l'orbite s'échappe ; avec, elle n'a pas convergé.
+                        None if roots.is_empty() => (
+                            smooth_iteration(result.iterations, self.params.max_iterations, result.z.norm_sqr(), degree)
+                                .unwrap_or(f64::NAN),
+                            None,
+                            false,
+                        ),
+                        None => (f64::NAN, None, false),
+                    }
                 })
             })
             .collect();
 
-        for (index, color) in pixels {
-            let base_x = (index % scaled_width) * scale;
-            let base_y = (index / scaled_width) * scale;
-            
-            for dy in 0..scale {
-                for dx in 0..scale {
-                    let pixel_idx = ((base_y + dy) * width + base_x + dx) * 4;
-                    if pixel_idx + 3 < image_data.len() {
-                        image_data[pixel_idx..pixel_idx + 3].copy_from_slice(&[color.0, color.1, color.2]);
-                        image_data[pixel_idx + 3] = 255;
-                    }
+        // Chaque valeur couvre un bloc de scale × scale pixels.
+        let mut smooth = Vec::with_capacity(width * height);
+        let mut basins = Vec::with_capacity(if roots.is_empty() { 0 } else { width * height });
+        let mut converged = Vec::with_capacity(width * height);
+        for y in 0..height {
+            let row = (y / scale).min(scaled_height - 1) * scaled_width;
+            for x in 0..width {
+                let (value, root, has_converged) = values[row + (x / scale).min(scaled_width - 1)];
+                smooth.push(value);
+                converged.push(has_converged);
+                if !roots.is_empty() {
+                    basins.push(root);
                 }
             }
         }
 
-        image_data
+        IterationData {
+            size: (width, height),
+            max_iterations: self.params.max_iterations,
+            smooth,
+            root_count: roots.len(),
+            basins,
+            converged,
+        }
     }
 
     pub fn new_mandelbrot(params: FractalParams) -> FractalCalculator<MandelbrotSet> {
@@ -130,6 +235,18 @@ impl<F: FractalFunction + Sync> FractalCalculator<F> {
         }
     }
 
+    pub fn new_multibrot(params: FractalParams) -> FractalCalculator<Multibrot> {
+        let mut multibrot = if params.conjugate {
+            Multibrot::multicorn(params.exponent)
+        } else {
+            Multibrot::new(params.exponent)
+        };
+        if params.julia_mode {
+            multibrot = multibrot.with_julia(params.julia_c);
+        }
+        FractalCalculator::<Multibrot>::new(params, multibrot)
+    }
+
     pub fn new_burning_ship(params: FractalParams) -> FractalCalculator<BurningShip> {
         FractalCalculator::<BurningShip>::new(params, BurningShip)
     }
@@ -139,7 +256,74 @@ impl<F: FractalFunction + Sync> FractalCalculator<F> {
     }
 
     pub fn new_newton(params: FractalParams) -> FractalCalculator<NewtonSet> {
-        FractalCalculator::<NewtonSet>::new(params, NewtonSet)
+        let newton = NewtonSet::from_roots(params.newton_roots.clone(), params.newton_relaxation)
+            .with_method(params.root_method);
+        FractalCalculator::<NewtonSet>::new(params, newton)
+    }
+
+    pub fn new_nova(params: FractalParams) -> FractalCalculator<NovaSet> {
+        let mut nova = NovaSet::from_roots(&params.newton_roots, params.nova_relaxation);
+        if params.julia_mode {
+            nova = nova.with_julia(params.julia_c);
+        }
+        FractalCalculator::<NovaSet>::new(params, nova)
+    }
+
+    pub fn new_phoenix(params: FractalParams) -> FractalCalculator<PhoenixSet> {
+        let mut phoenix = PhoenixSet::new(params.phoenix_p);
+        if params.julia_mode {
+            phoenix = phoenix.with_julia(params.julia_c);
+        }
+        FractalCalculator::<PhoenixSet>::new(params, phoenix)
+    }
+
+    pub fn new_magnet(params: FractalParams) -> FractalCalculator<MagnetSet> {
+        let magnet = params.magnet;
+        FractalCalculator::<MagnetSet>::new(params, magnet)
+    }
+
+    pub fn new_abs_variant(params: FractalParams) -> FractalCalculator<AbsFormula> {
+        let mut formula = params.abs_variant.formula(params.abs_power);
+        if params.julia_mode {
+            formula = formula.with_julia(params.julia_c);
+        }
+        FractalCalculator::<AbsFormula>::new(params, formula)
+    }
+
+    pub fn new_transcendental(params: FractalParams) -> FractalCalculator<TranscendentalSet> {
+        let set = match params.transcendental {
+            TranscendentalMap::LambdaExp => TranscendentalSet::lambda_exp(params.lambda),
+            map if params.julia_mode => TranscendentalSet::new(map).with_julia(params.julia_c),
+            map => TranscendentalSet::new(map),
+        };
+        FractalCalculator::<TranscendentalSet>::new(params, set)
+    }
+
+    pub fn new_mcmullen(params: FractalParams) -> FractalCalculator<McMullenSet> {
+        let set = if params.julia_mode {
+            McMullenSet::dynamic_plane(params.mcmullen_n, params.mcmullen_m, params.mcmullen_lambda)
+        } else {
+            McMullenSet::parameter_plane(params.mcmullen_n, params.mcmullen_m)
+        };
+        FractalCalculator::<McMullenSet>::new(params, set)
+    }
+
+    pub fn new_lyapunov(params: FractalParams) -> FractalCalculator<LyapunovSet> {
+        let set = LyapunovSet::new(&params.lyapunov_sequence, params.lyapunov_warmup, params.lyapunov_samples);
+        FractalCalculator::<LyapunovSet>::new(params, set)
+    }
+
+    pub fn new_formula(params: FractalParams) -> Result<FractalCalculator<CompiledFormula>, FormulaError> {
+        let formula = CompiledFormula::compile(&params.formula)?.with_parameter(params.formula_param);
+        Ok(FractalCalculator::<CompiledFormula>::new(params, formula))
+    }
+
+    pub fn new_hybrid(params: FractalParams) -> Result<FractalCalculator<HybridSet>, FormulaError> {
+        let mut hybrid = HybridSet::new(&params.hybrid)?;
+        if params.julia_mode {
+            hybrid = hybrid.with_julia(params.julia_c);
+        }
+        Ok(FractalCalculator::<HybridSet>::new(params, hybrid))
     }
 
     pub fn set_quality_level(&mut self, quality: QualityLevel) {
@@ -1,12 +1,69 @@
+use crate::fractal_types::{default_newton_roots, AbsVariant, Exponent, MagnetSet, RootMethod, TranscendentalMap};
+use crate::formula::DEFAULT_FORMULA;
+use crate::hybrid::{default_hybrid, HybridStep};
+use crate::attractor::Attractor;
+use crate::kleinian::KleinianGroup;
 use num::Complex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
 
-#[derive(Clone)]
+/// Les champs absents d'un fichier enregistré prennent leur valeur par défaut.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct FractalParams {
     pub zoom: f64,
     pub center: Complex<f64>,
     pub max_iterations: u32,
+    /// Coloration par estimation de distance au bord plutôt que par nombre
+    /// d'itérations, pour les fractales qui la proposent.
+    pub distance_estimation: bool,
+    /// Taille de la fenêtre, jamais enregistrée.
+    #[serde(skip)]
     pub size: (usize, usize),
     pub julia_c: Complex<f64>,
+    pub newton_roots: Vec<Complex<f64>>,
+    pub newton_relaxation: Complex<f64>,
+    pub root_method: RootMethod,
+    pub exponent: Exponent,
+    /// Variante conjuguée (Multicorn) des familles qui en ont une.
+    pub conjugate: bool,
+    /// Ensemble de Julia de paramètre `julia_c` plutôt que plan des paramètres.
+    pub julia_mode: bool,
+    /// Relaxation R de Nova, qui reprend le polynôme de `newton_roots`.
+    pub nova_relaxation: Complex<f64>,
+    /// Coefficient p de l'itéré précédent dans Phoenix.
+    pub phoenix_p: Complex<f64>,
+    pub magnet: MagnetSet,
+    pub abs_variant: AbsVariant,
+    /// Puissance (2 ou 3) de la famille « abs ».
+    pub abs_power: u32,
+    pub transcendental: TranscendentalMap,
+    /// Paramètre λ de la famille λ·exp(z).
+    pub lambda: Complex<f64>,
+    /// Exposants n et m de la famille de McMullen zⁿ + λ/zᵐ.
+    pub mcmullen_n: u32,
+    pub mcmullen_m: u32,
+    /// λ du plan dynamique de McMullen (mode Julia).
+    pub mcmullen_lambda: Complex<f64>,
+    /// Séquence de A et de B de la fractale de Lyapunov.
+    pub lyapunov_sequence: String,
+    /// Itérations ignorées, puis moyennées, pour l'exposant de Lyapunov.
+    pub lyapunov_warmup: u32,
+    pub lyapunov_samples: u32,
+    /// Source de la formule utilisateur (voir `formula`).
+    pub formula: String,
+    /// Valeur de la variable `p` de la formule.
+    pub formula_param: Complex<f64>,
+    /// Étapes de l'hybride, appliquées à tour de rôle.
+    pub hybrid: Vec<HybridStep>,
+    pub attractor: Attractor,
+    /// Nombre de points tracés pour un attracteur.
+    pub attractor_points: usize,
+    pub kleinian: KleinianGroup,
+    /// Longueur maximale des mots du groupe de Klein.
+    pub kleinian_depth: u32,
 }
 
 impl Default for FractalParams {
@@ -15,8 +72,54 @@ impl Default for FractalParams {
             zoom: 1.0,
             center: Complex::new(-0.5, 0.0),
             max_iterations: 100,
+            distance_estimation: false,
             size: (800, 600),
             julia_c: Complex::new(-0.4, 0.6),
+            newton_roots: default_newton_roots(),
+            newton_relaxation: Complex::new(1.0, 0.0),
+            root_method: RootMethod::Newton,
+            exponent: Exponent::Integer(3),
+            conjugate: false,
+            julia_mode: false,
+            nova_relaxation: Complex::new(1.0, 0.0),
+            phoenix_p: Complex::new(-0.5, 0.0),
+            magnet: MagnetSet::TypeI,
+            abs_variant: AbsVariant::BurningShip,
+            abs_power: 2,
+            transcendental: TranscendentalMap::Exp,
+            lambda: Complex::new(0.3, 0.0),
+            mcmullen_n: 3,
+            mcmullen_m: 3,
+            mcmullen_lambda: Complex::new(-0.01, 0.0),
+            lyapunov_sequence: "AB".to_string(),
+            lyapunov_warmup: 200,
+            lyapunov_samples: 400,
+            formula: DEFAULT_FORMULA.to_string(),
+            formula_param: Complex::new(-0.4, 0.6),
+            hybrid: default_hybrid(),
+            attractor: Attractor::default(),
+            attractor_points: 2_000_000,
+            kleinian: KleinianGroup::default(),
+            kleinian_depth: 60,
         }
     }
+}
+
+/// Fichier de paramètres : type de fractale et paramètres, en JSON.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ParameterFile {
+    pub fractal: String,
+    pub params: FractalParams,
+}
+
+impl ParameterFile {
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(path, json)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let json = fs::read_to_string(path)?;
+        serde_json::from_str(&json).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
 }
\ No newline at end of file
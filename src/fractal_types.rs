@@ -1,10 +1,111 @@
+use crate::distance;
+use crate::polynomial::Polynomial;
+use serde::{Deserialize, Serialize};
 use num::Complex;
 
+/// Issue d'une orbite : échappement (ou maximum d'itérations atteint, l'orbite
+/// restant bornée) ou convergence vers un attracteur fini.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Escaped,
+    Converged,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IterationResult {
+    pub iterations: u32,
+    pub z: Complex<f64>,
+    pub outcome: Outcome,
+    /// Index de la racine atteinte, pour les fractales de recherche de racines.
+    pub root: Option<usize>,
+}
+
+impl IterationResult {
+    pub fn new(iterations: u32, z: Complex<f64>) -> Self {
+        Self { iterations, z, outcome: Outcome::Escaped, root: None }
+    }
+
+    pub fn converged(iterations: u32, z: Complex<f64>, root: usize) -> Self {
+        Self { iterations, z, outcome: Outcome::Converged, root: Some(root) }
+    }
+
+    /// Convergence vers un attracteur qui n'est pas une racine indexée.
+    pub fn attracted(iterations: u32, z: Complex<f64>) -> Self {
+        Self { iterations, z, outcome: Outcome::Converged, root: None }
+    }
+
+    pub fn is_converged(&self) -> bool {
+        self.outcome == Outcome::Converged
+    }
+}
+
+/// État d'une orbite : l'itéré courant et le précédent, pour les formules
+/// qui en dépendent (Phoenix, sécante), et le nombre de pas effectués
+/// (position dans le cycle d'un hybride).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OrbitState {
+    pub z: Complex<f64>,
+    pub previous: Complex<f64>,
+    pub iteration: u32,
+}
+
+impl OrbitState {
+    pub fn new(z: Complex<f64>) -> Self {
+        Self { z, previous: Complex::new(0.0, 0.0), iteration: 0 }
+    }
+
+    #[inline]
+    pub fn advance(&mut self, next: Complex<f64>) {
+        self.previous = self.z;
+        self.z = next;
+        self.iteration += 1;
+    }
+}
+
 pub trait FractalFunction: Clone {
-    fn iterate(&self, c: Complex<f64>, z: Complex<f64>, max_iter: u32) -> (u32, Complex<f64>);
-    fn initial_z(&self) -> Complex<f64> {
+    fn iterate(&self, c: Complex<f64>, z: Complex<f64>, max_iter: u32) -> IterationResult;
+    /// Un pas de la formule pour le point c.
+    fn step(&self, c: Complex<f64>, state: &mut OrbitState);
+    /// Point de départ de l'orbite du point c, en général un point critique
+    /// de la formule (qui peut dépendre de c).
+    fn initial_z(&self, _c: Complex<f64>) -> Complex<f64> {
         Complex::new(0.0, 0.0)
     }
+    /// État de départ de l'orbite du point c.
+    fn initial_state(&self, c: Complex<f64>) -> OrbitState {
+        OrbitState::new(self.initial_z(c))
+    }
+    /// Racines vers lesquelles l'itération converge ; vide pour les fractales
+    /// à temps d'échappement.
+    fn roots(&self) -> &[Complex<f64>] {
+        &[]
+    }
+    /// Distance à une racine en dessous de laquelle l'orbite a convergé.
+    fn tolerance(&self) -> f64 {
+        1e-6
+    }
+    /// Critère d'échappement de l'orbite ; |z| > 2 par défaut.
+    fn escaped(&self, z: Complex<f64>) -> bool {
+        z.norm_sqr() > 4.0
+    }
+    /// Point fixe fini vers lequel convergent les orbites non échappées, s'il est connu.
+    fn attractor(&self) -> Option<Complex<f64>> {
+        None
+    }
+    /// Degré d'échappement d de z ↦ z^d + c, utilisé pour lisser la coloration.
+    fn escape_degree(&self) -> f64 {
+        2.0
+    }
+    /// Valeur lissée calculée par la fractale elle-même plutôt que déduite
+    /// du nombre d'itérations (exposant de Lyapunov) ; `None` par défaut.
+    fn smooth_value(&self, _result: &IterationResult, _max_iter: u32) -> Option<f64> {
+        None
+    }
+    /// Estimation de la distance du point c à l'ensemble (nulle à
+    /// l'intérieur), pour les fractales qui savent la calculer.
+    fn distance(&self, _c: Complex<f64>, _max_iter: u32) -> Option<f64> {
+        None
+    }
 }
 
 #[derive(Clone)]
@@ -18,90 +119,949 @@ pub struct JuliaSet {
 #[derive(Clone)]
 pub struct BurningShip;
 
+/// Exposant d de z^d + c : les entiers passent par des multiplications
+/// successives, les réels et complexes par la forme polaire.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Exponent {
+    Integer(u32),
+    Real(f64),
+    Complex(Complex<f64>),
+}
+
+impl Exponent {
+    #[inline]
+    pub fn apply(&self, z: Complex<f64>) -> Complex<f64> {
+        match *self {
+            Exponent::Integer(n) => z.powu(n),
+            Exponent::Real(d) => {
+                if z == Complex::new(0.0, 0.0) {
+                    z
+                } else {
+                    z.powf(d)
+                }
+            }
+            Exponent::Complex(d) => {
+                if z == Complex::new(0.0, 0.0) {
+                    z
+                } else {
+                    z.powc(d)
+                }
+            }
+        }
+    }
+
+    /// Croissance de |z| loin de l'origine : |z^d| ≈ |z|^Re(d).
+    pub fn degree(&self) -> f64 {
+        match *self {
+            Exponent::Integer(n) => n as f64,
+            Exponent::Real(d) => d.abs(),
+            Exponent::Complex(d) => d.re.abs(),
+        }
+    }
+}
+
+/// Famille Multibrot z ← z^d + c ; `conjugate` donne le Multicorn
+/// z ← conj(z)^d + c, et `julia` fixe c pour l'ensemble de Julia associé.
+#[derive(Clone)]
+pub struct Multibrot {
+    pub exponent: Exponent,
+    pub conjugate: bool,
+    pub julia: Option<Complex<f64>>,
+}
+
+impl Multibrot {
+    pub fn new(exponent: Exponent) -> Self {
+        Self { exponent, conjugate: false, julia: None }
+    }
+
+    pub fn multicorn(exponent: Exponent) -> Self {
+        Self { exponent, conjugate: true, julia: None }
+    }
+
+    pub fn with_julia(mut self, c: Complex<f64>) -> Self {
+        self.julia = Some(c);
+        self
+    }
+}
+
 #[derive(Clone)]
 pub struct Tricorn;
 
+/// Applications rationnelles de McMullen z ← zⁿ + λ/zᵐ. Dans le plan des
+/// paramètres, le pixel donne λ et l'orbite part d'un point critique libre ;
+/// dans le plan dynamique, λ est fixé et le pixel donne z.
+#[derive(Clone)]
+pub struct McMullenSet {
+    pub n: u32,
+    pub m: u32,
+    pub lambda: Option<Complex<f64>>,
+}
+
+impl McMullenSet {
+    pub fn parameter_plane(n: u32, m: u32) -> Self {
+        Self { n: n.max(1), m: m.max(1), lambda: None }
+    }
+
+    pub fn dynamic_plane(n: u32, m: u32, lambda: Complex<f64>) -> Self {
+        Self { n: n.max(1), m: m.max(1), lambda: Some(lambda) }
+    }
+
+    /// Point critique libre : f'(z) = 0 ⇔ z^(n+m) = mλ/n. Les n + m solutions
+    /// ont des orbites symétriques, la racine principale suffit.
+    pub fn critical_point(&self, lambda: Complex<f64>) -> Complex<f64> {
+        let w = lambda * (self.m as f64 / self.n as f64);
+        if w == Complex::new(0.0, 0.0) {
+            w
+        } else {
+            w.powf(1.0 / (self.n + self.m) as f64)
+        }
+    }
+}
+
+/// Fractale de Markus–Lyapunov : l'application logistique x ↦ r·x·(1 − x),
+/// où r vaut tour à tour a ou b selon la séquence, pour (a, b) = (re c, im c).
+/// Chaque point est coloré par le signe et la grandeur de l'exposant de
+/// Lyapunov de son orbite.
+#[derive(Clone)]
+pub struct LyapunovSet {
+    /// `false` pour A, `true` pour B.
+    pub sequence: Vec<bool>,
+    /// Itérations ignorées avant de mesurer l'exposant.
+    pub warmup: u32,
+    /// Itérations sur lesquelles l'exposant est moyenné.
+    pub samples: u32,
+}
+
+impl LyapunovSet {
+    /// Les caractères autres que A et B sont ignorés ; une séquence vide vaut « AB ».
+    pub fn new(sequence: &str, warmup: u32, samples: u32) -> Self {
+        let mut sequence: Vec<bool> = sequence
+            .chars()
+            .filter_map(|ch| match ch.to_ascii_uppercase() {
+                'A' => Some(false),
+                'B' => Some(true),
+                _ => None,
+            })
+            .collect();
+        if sequence.is_empty() {
+            sequence = vec![false, true];
+        }
+        Self { sequence, warmup, samples: samples.max(1) }
+    }
+
+    /// Exposant de Lyapunov λ = moyenne de ln |r·(1 − 2x)| le long de l'orbite
+    /// de x₀ = 1/2 ; négatif si l'orbite est stable, positif si elle est
+    /// chaotique, `NaN` si elle diverge.
+    pub fn exponent(&self, a: f64, b: f64) -> f64 {
+        let mut state = OrbitState::new(Complex::new(0.5, 0.0));
+        for _ in 0..self.warmup {
+            self.step(Complex::new(a, b), &mut state);
+        }
+        let mut x = state.z.re;
+        let mut position = state.iteration as usize % self.sequence.len();
+        let mut sum = 0.0;
+        // Le logarithme n'est pris que lorsque le produit des dérivées
+        // risque de sortir de la plage des flottants.
+        let mut product = 1.0;
+        for _ in 0..self.samples {
+            let r = if self.sequence[position] { b } else { a };
+            position = (position + 1) % self.sequence.len();
+            product *= (r * (1.0 - 2.0 * x)).abs();
+            x = r * x * (1.0 - x);
+            if !x.is_finite() {
+                return f64::NAN;
+            }
+            if !(1e-100..=1e100).contains(&product) {
+                sum += product.ln();
+                product = 1.0;
+            }
+        }
+        (sum + product.ln()) / self.samples as f64
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TranscendentalMap {
+    /// z ← c·exp(z)
+    Exp,
+    /// z ← c·sin(z)
+    Sin,
+    /// z ← c·cos(z)
+    Cos,
+    /// z ← sinh(z) + c
+    Sinh,
+    /// z ← λ·exp(z), λ fixé et z parcourant le plan.
+    LambdaExp,
+}
+
+impl TranscendentalMap {
+    pub const ALL: [TranscendentalMap; 5] = [
+        TranscendentalMap::Exp,
+        TranscendentalMap::Sin,
+        TranscendentalMap::Cos,
+        TranscendentalMap::Sinh,
+        TranscendentalMap::LambdaExp,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TranscendentalMap::Exp => "c·exp(z)",
+            TranscendentalMap::Sin => "c·sin(z)",
+            TranscendentalMap::Cos => "c·cos(z)",
+            TranscendentalMap::Sinh => "sinh(z) + c",
+            TranscendentalMap::LambdaExp => "λ·exp(z)",
+        }
+    }
+}
+
+/// Fractales à fonctions transcendantes. Sans `julia`, l'orbite part d'un
+/// point critique (ou de la valeur asymptotique 0 pour l'exponentielle) ;
+/// λ·exp(z) est toujours en mode Julia, de paramètre λ.
 #[derive(Clone)]
-pub struct NewtonSet;
+pub struct TranscendentalSet {
+    pub map: TranscendentalMap,
+    pub julia: Option<Complex<f64>>,
+}
+
+impl TranscendentalSet {
+    pub fn new(map: TranscendentalMap) -> Self {
+        Self { map, julia: None }
+    }
+
+    pub fn lambda_exp(lambda: Complex<f64>) -> Self {
+        Self { map: TranscendentalMap::LambdaExp, julia: Some(lambda) }
+    }
+
+    pub fn with_julia(mut self, c: Complex<f64>) -> Self {
+        self.julia = Some(c);
+        self
+    }
+}
+
+/// Variantes nommées de la famille « abs » : chacune est un jeu de drapeaux
+/// de `AbsFormula`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AbsVariant {
+    BurningShip,
+    Celtic,
+    Buffalo,
+    PerpendicularBurningShip,
+    PerpendicularMandelbrot,
+    Heart,
+    Mandelbar,
+}
+
+impl AbsVariant {
+    pub const ALL: [AbsVariant; 7] = [
+        AbsVariant::BurningShip,
+        AbsVariant::Celtic,
+        AbsVariant::Buffalo,
+        AbsVariant::PerpendicularBurningShip,
+        AbsVariant::PerpendicularMandelbrot,
+        AbsVariant::Heart,
+        AbsVariant::Mandelbar,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AbsVariant::BurningShip => "Burning Ship",
+            AbsVariant::Celtic => "Celtic",
+            AbsVariant::Buffalo => "Buffalo",
+            AbsVariant::PerpendicularBurningShip => "Burning Ship perpendiculaire",
+            AbsVariant::PerpendicularMandelbrot => "Mandelbrot perpendiculaire",
+            AbsVariant::Heart => "Heart",
+            AbsVariant::Mandelbar => "Mandelbar",
+        }
+    }
+
+    pub fn formula(&self, power: u32) -> AbsFormula {
+        let base = AbsFormula {
+            power,
+            abs_re: false,
+            abs_im: false,
+            negate_im: false,
+            abs_out_re: false,
+            abs_out_im: false,
+            julia: None,
+        };
+        match self {
+            AbsVariant::BurningShip => AbsFormula { abs_re: true, abs_im: true, ..base },
+            AbsVariant::Celtic => AbsFormula { abs_out_re: true, ..base },
+            AbsVariant::Buffalo => AbsFormula { abs_out_re: true, abs_out_im: true, ..base },
+            AbsVariant::PerpendicularBurningShip => AbsFormula { abs_im: true, negate_im: true, ..base },
+            AbsVariant::PerpendicularMandelbrot => AbsFormula { abs_re: true, negate_im: true, ..base },
+            AbsVariant::Heart => AbsFormula { abs_re: true, ..base },
+            AbsVariant::Mandelbar => AbsFormula { negate_im: true, ..base },
+        }
+    }
+}
+
+/// z ← w^d + c, où w = (±|x|, ±|y|) selon les drapeaux d'entrée ; les parties
+/// réelle et imaginaire de w^d peuvent encore passer en valeur absolue.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AbsFormula {
+    pub power: u32,
+    pub abs_re: bool,
+    pub abs_im: bool,
+    pub negate_im: bool,
+    pub abs_out_re: bool,
+    pub abs_out_im: bool,
+    pub julia: Option<Complex<f64>>,
+}
+
+impl AbsFormula {
+    pub fn with_julia(mut self, c: Complex<f64>) -> Self {
+        self.julia = Some(c);
+        self
+    }
+
+    #[inline]
+    fn apply(&self, z: Complex<f64>, c: Complex<f64>) -> Complex<f64> {
+        let re = if self.abs_re { z.re.abs() } else { z.re };
+        let im = if self.abs_im { z.im.abs() } else { z.im };
+        let w = Complex::new(re, if self.negate_im { -im } else { im });
+        let p = if self.power == 2 { w * w } else { w.powu(self.power) };
+        let re = if self.abs_out_re { p.re.abs() } else { p.re };
+        let im = if self.abs_out_im { p.im.abs() } else { p.im };
+        Complex::new(re, im) + c
+    }
+}
+
+/// Fractales « Magnet » issues de la renormalisation du modèle d'Ising :
+/// type I z ← ((z² + c − 1) / (2z + c − 2))², type II de degré 3 au
+/// numérateur. Les orbites s'échappent ou convergent vers le point fixe 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MagnetSet {
+    TypeI,
+    TypeII,
+}
+
+impl MagnetSet {
+    pub const ALL: [MagnetSet; 2] = [MagnetSet::TypeI, MagnetSet::TypeII];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            MagnetSet::TypeI => "Magnet I",
+            MagnetSet::TypeII => "Magnet II",
+        }
+    }
+}
+
+/// Nova : z ← z − R·p(z)/p'(z) + c. Sans `julia`, c parcourt le plan et
+/// l'orbite part de `start` ; sinon z parcourt le plan et c est fixé.
+#[derive(Clone)]
+pub struct NovaSet {
+    pub polynomial: Polynomial,
+    pub relaxation: Complex<f64>,
+    pub start: Complex<f64>,
+    pub julia: Option<Complex<f64>>,
+}
+
+impl NovaSet {
+    /// Part de la première racine, point critique de la formule pour R = 1.
+    pub fn from_roots(roots: &[Complex<f64>], relaxation: Complex<f64>) -> Self {
+        Self {
+            polynomial: Polynomial::from_roots(roots),
+            relaxation,
+            start: roots.first().copied().unwrap_or(Complex::new(1.0, 0.0)),
+            julia: None,
+        }
+    }
+
+    pub fn with_julia(mut self, c: Complex<f64>) -> Self {
+        self.julia = Some(c);
+        self
+    }
+}
+
+/// Phoenix : z ← z² + c + p·z₋₁, avec z₋₁ l'itéré précédent.
+#[derive(Clone)]
+pub struct PhoenixSet {
+    pub p: Complex<f64>,
+    pub julia: Option<Complex<f64>>,
+}
+
+impl PhoenixSet {
+    pub fn new(p: Complex<f64>) -> Self {
+        Self { p, julia: None }
+    }
+
+    pub fn with_julia(mut self, c: Complex<f64>) -> Self {
+        self.julia = Some(c);
+        self
+    }
+}
+
+/// Fractale de recherche de racines : z ← z − a·Δ(z), où Δ est le pas de la
+/// méthode choisie (p/p' pour Newton) et `relaxation` le facteur complexe a
+/// (1 pour la méthode classique).
+#[derive(Clone)]
+pub struct NewtonSet {
+    pub polynomial: Polynomial,
+    pub roots: Vec<Complex<f64>>,
+    pub relaxation: Complex<f64>,
+    pub method: RootMethod,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RootMethod {
+    Newton,
+    Halley,
+    /// Méthode de Householder d'ordre 3.
+    Householder,
+    /// Newton modifiée de Schröder, adaptée aux racines multiples.
+    Schroder,
+    Secant,
+}
+
+impl RootMethod {
+    pub const ALL: [RootMethod; 5] = [
+        RootMethod::Newton,
+        RootMethod::Halley,
+        RootMethod::Householder,
+        RootMethod::Schroder,
+        RootMethod::Secant,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RootMethod::Newton => "Newton",
+            RootMethod::Halley => "Halley",
+            RootMethod::Householder => "Householder (ordre 3)",
+            RootMethod::Schroder => "Schröder",
+            RootMethod::Secant => "Sécante",
+        }
+    }
+}
+
+// Point de départ décalé de la méthode de la sécante, qui a besoin de deux itérés.
+const SECANT_OFFSET: Complex<f64> = Complex::new(1e-3, 0.0);
 
 impl FractalFunction for MandelbrotSet {
-    fn iterate(&self, c: Complex<f64>, z: Complex<f64>, max_iter: u32) -> (u32, Complex<f64>) {
+    fn iterate(&self, c: Complex<f64>, z: Complex<f64>, max_iter: u32) -> IterationResult {
         let mut z = z;
         let mut i = 0;
         
-        while i < max_iter && z.norm_sqr() <= 4.0 {
+        while i < max_iter && !self.escaped(z) {
             z = z * z + c;
             i += 1;
         }
-        (i, z)
+        IterationResult::new(i, z)
+    }
+
+    fn step(&self, c: Complex<f64>, state: &mut OrbitState) {
+        state.advance(state.z * state.z + c);
+    }
+
+    fn distance(&self, c: Complex<f64>, max_iter: u32) -> Option<f64> {
+        Some(distance::mandelbrot(c, max_iter).distance)
     }
 }
 
 impl FractalFunction for JuliaSet {
-    fn iterate(&self, c: Complex<f64>, _z: Complex<f64>, max_iter: u32) -> (u32, Complex<f64>) {
+    fn iterate(&self, c: Complex<f64>, _z: Complex<f64>, max_iter: u32) -> IterationResult {
         let mut z = c;
         let mut i = 0;
         
-        while i < max_iter && z.norm_sqr() <= 4.0 {
+        while i < max_iter && !self.escaped(z) {
             z = z * z + self.c;
             i += 1;
         }
-        (i, z)
+        IterationResult::new(i, z)
+    }
+
+    fn step(&self, _c: Complex<f64>, state: &mut OrbitState) {
+        state.advance(state.z * state.z + self.c);
+    }
+
+    fn initial_state(&self, c: Complex<f64>) -> OrbitState {
+        OrbitState::new(c)
+    }
+
+    fn distance(&self, c: Complex<f64>, max_iter: u32) -> Option<f64> {
+        Some(distance::julia(c, self.c, max_iter).distance)
+    }
+}
+
+impl FractalFunction for Multibrot {
+    fn iterate(&self, c: Complex<f64>, z: Complex<f64>, max_iter: u32) -> IterationResult {
+        let (mut z, c) = match self.julia {
+            Some(julia_c) => (c, julia_c),
+            None => (z, c),
+        };
+        let mut i = 0;
+        
+        while i < max_iter && !self.escaped(z) {
+            let base = if self.conjugate { z.conj() } else { z };
+            z = self.exponent.apply(base) + c;
+            i += 1;
+        }
+        IterationResult::new(i, z)
+    }
+
+    fn step(&self, c: Complex<f64>, state: &mut OrbitState) {
+        let c = self.julia.unwrap_or(c);
+        let base = if self.conjugate { state.z.conj() } else { state.z };
+        state.advance(self.exponent.apply(base) + c);
+    }
+
+    fn initial_state(&self, c: Complex<f64>) -> OrbitState {
+        match self.julia {
+            Some(_) => OrbitState::new(c),
+            None => OrbitState::new(self.initial_z(c)),
+        }
+    }
+
+    fn escape_degree(&self) -> f64 {
+        self.exponent.degree()
     }
 }
 
 impl FractalFunction for BurningShip {
-    fn iterate(&self, c: Complex<f64>, z: Complex<f64>, max_iter: u32) -> (u32, Complex<f64>) {
+    fn iterate(&self, c: Complex<f64>, z: Complex<f64>, max_iter: u32) -> IterationResult {
         let mut z = z;
         let mut i = 0;
         
-        while i < max_iter && z.norm_sqr() <= 4.0 {
+        while i < max_iter && !self.escaped(z) {
             let re = f64::abs(z.re);
             let im = f64::abs(z.im);
             z = Complex::new(re, im) * Complex::new(re, im) + c;
             i += 1;
         }
-        (i, z)
+        IterationResult::new(i, z)
+    }
+
+    fn step(&self, c: Complex<f64>, state: &mut OrbitState) {
+        let folded = Complex::new(state.z.re.abs(), state.z.im.abs());
+        state.advance(folded * folded + c);
     }
 }
 
 impl FractalFunction for Tricorn {
-    fn iterate(&self, c: Complex<f64>, z: Complex<f64>, max_iter: u32) -> (u32, Complex<f64>) {
+    fn iterate(&self, c: Complex<f64>, z: Complex<f64>, max_iter: u32) -> IterationResult {
         let mut z = z;
         let mut i = 0;
         
-        while i < max_iter && z.norm_sqr() <= 4.0 {
+        while i < max_iter && !self.escaped(z) {
             z = Complex::new(z.re, -z.im) * Complex::new(z.re, -z.im) + c;
             i += 1;
         }
-        (i, z)
+        IterationResult::new(i, z)
+    }
+
+    fn step(&self, c: Complex<f64>, state: &mut OrbitState) {
+        state.advance(state.z.conj() * state.z.conj() + c);
+    }
+}
+
+impl FractalFunction for AbsFormula {
+    fn iterate(&self, c: Complex<f64>, _z: Complex<f64>, max_iter: u32) -> IterationResult {
+        let mut state = self.initial_state(c);
+        let c = self.julia.unwrap_or(c);
+        let mut i = 0;
+
+        while i < max_iter && !self.escaped(state.z) {
+            state.z = self.apply(state.z, c);
+            i += 1;
+        }
+        IterationResult::new(i, state.z)
+    }
+
+    fn step(&self, c: Complex<f64>, state: &mut OrbitState) {
+        state.advance(self.apply(state.z, self.julia.unwrap_or(c)));
+    }
+
+    fn initial_state(&self, c: Complex<f64>) -> OrbitState {
+        match self.julia {
+            Some(_) => OrbitState::new(c),
+            None => OrbitState::new(self.initial_z(c)),
+        }
+    }
+
+    fn escape_degree(&self) -> f64 {
+        self.power as f64
+    }
+}
+
+// Au-delà, exp(z), sin(z) ou sinh(z) débordent rapidement : l'orbite s'échappe.
+const TRANSCENDENTAL_BAILOUT: f64 = 50.0;
+
+impl FractalFunction for TranscendentalSet {
+    fn iterate(&self, c: Complex<f64>, _z: Complex<f64>, max_iter: u32) -> IterationResult {
+        let mut state = self.initial_state(c);
+        let mut i = 0;
+
+        while i < max_iter && !self.escaped(state.z) {
+            self.step(c, &mut state);
+            i += 1;
+        }
+        IterationResult::new(i, state.z)
+    }
+
+    fn step(&self, c: Complex<f64>, state: &mut OrbitState) {
+        let c = self.julia.unwrap_or(c);
+        let z = state.z;
+        state.advance(match self.map {
+            TranscendentalMap::Exp | TranscendentalMap::LambdaExp => c * z.exp(),
+            TranscendentalMap::Sin => c * z.sin(),
+            TranscendentalMap::Cos => c * z.cos(),
+            TranscendentalMap::Sinh => z.sinh() + c,
+        });
+    }
+
+    fn initial_z(&self, _c: Complex<f64>) -> Complex<f64> {
+        match self.map {
+            TranscendentalMap::Sin => Complex::new(std::f64::consts::FRAC_PI_2, 0.0),
+            TranscendentalMap::Sinh => Complex::new(0.0, std::f64::consts::FRAC_PI_2),
+            _ => Complex::new(0.0, 0.0),
+        }
+    }
+
+    fn initial_state(&self, c: Complex<f64>) -> OrbitState {
+        match self.julia {
+            Some(_) => OrbitState::new(c),
+            None => OrbitState::new(self.initial_z(c)),
+        }
+    }
+
+    /// L'exponentielle s'échappe par la partie réelle, sin et cos par la
+    /// partie imaginaire ; sinh croît dans les deux sens de l'axe réel.
+    fn escaped(&self, z: Complex<f64>) -> bool {
+        !z.is_finite()
+            || match self.map {
+                TranscendentalMap::Exp | TranscendentalMap::LambdaExp => z.re > TRANSCENDENTAL_BAILOUT,
+                TranscendentalMap::Sin | TranscendentalMap::Cos => z.im.abs() > TRANSCENDENTAL_BAILOUT,
+                TranscendentalMap::Sinh => z.re.abs() > TRANSCENDENTAL_BAILOUT,
+            }
+    }
+
+    // Croissance plus qu'exponentielle : pas de lissage logarithmique.
+    fn escape_degree(&self) -> f64 {
+        1.0
+    }
+}
+
+// L'infini est super-attractif ; au-delà de ce rayon l'orbite y est captée.
+const MCMULLEN_BAILOUT: f64 = 1e3;
+
+impl FractalFunction for McMullenSet {
+    fn iterate(&self, c: Complex<f64>, _z: Complex<f64>, max_iter: u32) -> IterationResult {
+        let mut state = self.initial_state(c);
+        let mut i = 0;
+
+        while i < max_iter && !self.escaped(state.z) {
+            self.step(c, &mut state);
+            i += 1;
+        }
+        IterationResult::new(i, state.z)
+    }
+
+    fn step(&self, c: Complex<f64>, state: &mut OrbitState) {
+        let lambda = self.lambda.unwrap_or(c);
+        let z = state.z;
+        // 0 est un pôle : son image est l'infini.
+        if z == Complex::new(0.0, 0.0) {
+            state.advance(Complex::new(f64::INFINITY, 0.0));
+            return;
+        }
+        state.advance(z.powu(self.n) + lambda / z.powu(self.m));
+    }
+
+    fn initial_z(&self, c: Complex<f64>) -> Complex<f64> {
+        match self.lambda {
+            Some(_) => c,
+            None => self.critical_point(c),
+        }
+    }
+
+    fn escaped(&self, z: Complex<f64>) -> bool {
+        !z.is_finite() || z.norm_sqr() > MCMULLEN_BAILOUT * MCMULLEN_BAILOUT
+    }
+
+    fn escape_degree(&self) -> f64 {
+        self.n as f64
+    }
+}
+
+impl FractalFunction for LyapunovSet {
+    // L'exposant est rangé dans la partie réelle de z ; les orbites stables
+    // sont marquées comme convergées pour parcourir la palette à rebours.
+    fn iterate(&self, c: Complex<f64>, _z: Complex<f64>, _max_iter: u32) -> IterationResult {
+        let exponent = Complex::new(self.exponent(c.re, c.im), 0.0);
+        if exponent.re < 0.0 {
+            IterationResult::attracted(self.samples, exponent)
+        } else {
+            IterationResult::new(self.samples, exponent)
+        }
+    }
+
+    fn step(&self, c: Complex<f64>, state: &mut OrbitState) {
+        let position = state.iteration as usize % self.sequence.len();
+        let r = if self.sequence[position] { c.im } else { c.re };
+        let x = state.z.re;
+        state.advance(Complex::new(r * x * (1.0 - x), 0.0));
+    }
+
+    fn initial_z(&self, _c: Complex<f64>) -> Complex<f64> {
+        Complex::new(0.5, 0.0)
+    }
+
+    // |λ| ramené dans [0, 1[ ; les points superstables (λ = −∞) valent 1.
+    fn smooth_value(&self, result: &IterationResult, max_iter: u32) -> Option<f64> {
+        let exponent = result.z.re;
+        Some(if exponent.is_nan() { f64::NAN } else { (1.0 - (-exponent.abs()).exp()) * max_iter as f64 })
+    }
+}
+
+// Rayon d'échappement des Magnet, plus grand que 2 car l'attracteur 1 en est proche.
+const MAGNET_BAILOUT: f64 = 100.0;
+
+impl FractalFunction for MagnetSet {
+    fn iterate(&self, c: Complex<f64>, _z: Complex<f64>, max_iter: u32) -> IterationResult {
+        let mut state = self.initial_state(c);
+        let one = Complex::new(1.0, 0.0);
+        let tolerance = self.tolerance();
+        let mut i = 0;
+
+        while i < max_iter {
+            if (state.z - one).norm_sqr() < tolerance * tolerance {
+                return IterationResult::attracted(i, state.z);
+            }
+            if self.escaped(state.z) {
+                break;
+            }
+            self.step(c, &mut state);
+            i += 1;
+        }
+        IterationResult::new(i, state.z)
+    }
+
+    fn step(&self, c: Complex<f64>, state: &mut OrbitState) {
+        let z = state.z;
+        let (numerator, denominator) = match self {
+            MagnetSet::TypeI => (z * z + c - 1.0, 2.0 * z + c - 2.0),
+            MagnetSet::TypeII => {
+                let (c1, c2) = (c - 1.0, c - 2.0);
+                (
+                    z * z * z + 3.0 * c1 * z + c1 * c2,
+                    3.0 * z * z + 3.0 * c2 * z + c1 * c2 + 1.0,
+                )
+            }
+        };
+        // Pôle : l'orbite part à l'infini.
+        if denominator == Complex::new(0.0, 0.0) {
+            state.advance(Complex::new(f64::INFINITY, 0.0));
+            return;
+        }
+        let ratio = numerator / denominator;
+        state.advance(ratio * ratio);
+    }
+
+    fn tolerance(&self) -> f64 {
+        1e-4
+    }
+
+    fn escaped(&self, z: Complex<f64>) -> bool {
+        z.norm_sqr() > MAGNET_BAILOUT * MAGNET_BAILOUT
+    }
+
+    fn attractor(&self) -> Option<Complex<f64>> {
+        Some(Complex::new(1.0, 0.0))
+    }
+}
+
+// Au-delà, l'orbite de Nova est considérée comme divergente.
+const NOVA_BAILOUT: f64 = 1e12;
+
+impl FractalFunction for NovaSet {
+    /// Compte les pas jusqu'à stabilisation de l'orbite (|Δz| < tolérance)
+    /// ou divergence.
+    fn iterate(&self, c: Complex<f64>, _z: Complex<f64>, max_iter: u32) -> IterationResult {
+        let mut state = self.initial_state(c);
+        let tolerance = self.tolerance();
+        let mut i = 0;
+
+        while i < max_iter {
+            self.step(c, &mut state);
+            i += 1;
+            if (state.z - state.previous).norm_sqr() < tolerance * tolerance {
+                return IterationResult::attracted(i, state.z);
+            }
+            if self.escaped(state.z) {
+                return IterationResult::new(i, state.z);
+            }
+        }
+        IterationResult::new(max_iter, state.z)
+    }
+
+    fn step(&self, c: Complex<f64>, state: &mut OrbitState) {
+        let c = self.julia.unwrap_or(c);
+        let [p, dp] = self.polynomial.eval_derivatives::<2>(state.z);
+        let delta = if dp == Complex::new(0.0, 0.0) { Complex::new(0.0, 0.0) } else { p / dp };
+        state.advance(state.z - self.relaxation * delta + c);
+    }
+
+    fn initial_z(&self, _c: Complex<f64>) -> Complex<f64> {
+        self.start
+    }
+
+    fn initial_state(&self, c: Complex<f64>) -> OrbitState {
+        match self.julia {
+            Some(_) => OrbitState::new(c),
+            None => OrbitState::new(self.start),
+        }
+    }
+
+    fn escaped(&self, z: Complex<f64>) -> bool {
+        z.norm_sqr() > NOVA_BAILOUT
+    }
+
+    // Orbites convergentes : pas de lissage logarithmique.
+    fn escape_degree(&self) -> f64 {
+        1.0
+    }
+}
+
+impl FractalFunction for PhoenixSet {
+    fn iterate(&self, c: Complex<f64>, _z: Complex<f64>, max_iter: u32) -> IterationResult {
+        let mut state = self.initial_state(c);
+        let mut i = 0;
+
+        while i < max_iter && !self.escaped(state.z) {
+            self.step(c, &mut state);
+            i += 1;
+        }
+        IterationResult::new(i, state.z)
+    }
+
+    fn step(&self, c: Complex<f64>, state: &mut OrbitState) {
+        let c = self.julia.unwrap_or(c);
+        state.advance(state.z * state.z + c + self.p * state.previous);
+    }
+
+    fn initial_state(&self, c: Complex<f64>) -> OrbitState {
+        match self.julia {
+            Some(_) => OrbitState::new(c),
+            None => OrbitState::new(self.initial_z(c)),
+        }
+    }
+}
+
+impl Default for NewtonSet {
+    /// z³ − 1 et ses trois racines exactes.
+    fn default() -> Self {
+        Self::from_roots(default_newton_roots(), Complex::new(1.0, 0.0))
+    }
+}
+
+pub fn default_newton_roots() -> Vec<Complex<f64>> {
+    vec![
+        Complex::new(1.0, 0.0),
+        Complex::new(-0.5, 3f64.sqrt() / 2.0),
+        Complex::new(-0.5, -(3f64.sqrt()) / 2.0),
+    ]
+}
+
+impl NewtonSet {
+    pub fn from_roots(roots: Vec<Complex<f64>>, relaxation: Complex<f64>) -> Self {
+        Self {
+            polynomial: Polynomial::from_roots(&roots),
+            roots,
+            relaxation,
+            method: RootMethod::Newton,
+        }
+    }
+
+    /// Les racines, nécessaires à la coloration par bassin, sont calculées numériquement.
+    pub fn from_coefficients(coefficients: Vec<Complex<f64>>, relaxation: Complex<f64>) -> Self {
+        let polynomial = Polynomial::new(coefficients);
+        Self {
+            roots: polynomial.roots(),
+            polynomial,
+            relaxation,
+            method: RootMethod::Newton,
+        }
+    }
+
+    pub fn with_method(mut self, method: RootMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Pas de la méthode en z, avant relaxation ; `None` si le dénominateur s'annule.
+    fn delta(&self, z: Complex<f64>, previous: &mut (Complex<f64>, Complex<f64>)) -> Option<Complex<f64>> {
+        let zero = Complex::new(0.0, 0.0);
+        let (numerator, denominator) = match self.method {
+            RootMethod::Newton => {
+                let [p, dp] = self.polynomial.eval_derivatives::<2>(z);
+                (p, dp)
+            }
+            RootMethod::Halley => {
+                let [p, dp, d2p] = self.polynomial.eval_derivatives::<3>(z);
+                (2.0 * p * dp, 2.0 * dp * dp - p * d2p)
+            }
+            RootMethod::Householder => {
+                let [p, dp, d2p, d3p] = self.polynomial.eval_derivatives::<4>(z);
+                (
+                    6.0 * p * dp * dp - 3.0 * p * p * d2p,
+                    6.0 * dp * dp * dp - 6.0 * p * dp * d2p + p * p * d3p,
+                )
+            }
+            RootMethod::Schroder => {
+                let [p, dp, d2p] = self.polynomial.eval_derivatives::<3>(z);
+                (p * dp, dp * dp - p * d2p)
+            }
+            RootMethod::Secant => {
+                let (z_prev, p_prev) = *previous;
+                let p = self.polynomial.eval(z);
+                *previous = (z, p);
+                (p * (z - z_prev), p - p_prev)
+            }
+        };
+        if denominator == zero {
+            None
+        } else {
+            Some(numerator / denominator)
+        }
     }
 }
 
 impl FractalFunction for NewtonSet {
-    fn iterate(&self, c: Complex<f64>, _z: Complex<f64>, max_iter: u32) -> (u32, Complex<f64>) {
+    fn iterate(&self, c: Complex<f64>, _z: Complex<f64>, max_iter: u32) -> IterationResult {
         let mut z = c;
         let mut i = 0;
-        let tolerance = 1e-6;
+        let tolerance = self.tolerance();
+        let start = c + SECANT_OFFSET;
+        let mut previous = (start, self.polynomial.eval(start));
         
         while i < max_iter {
-            let z2 = z * z;
-            let z3 = z2 * z;
-            
-            let root1 = Complex::new(1.0, 0.0);
-            let root2 = Complex::new(-0.5, 0.866);
-            let root3 = Complex::new(-0.5, -0.866);
-            
-            if (z - root1).norm() < tolerance || 
-               (z - root2).norm() < tolerance || 
-               (z - root3).norm() < tolerance {
-                break;
+            if let Some(root) = self.roots.iter().position(|root| (z - root).norm() < tolerance) {
+                return IterationResult::converged(i, z, root);
             }
             
-            z = z - (z3 - Complex::new(1.0, 0.0)) / (Complex::new(3.0, 0.0) * z2);
+            match self.delta(z, &mut previous) {
+                Some(delta) => z -= self.relaxation * delta,
+                None => break,
+            }
             i += 1;
         }
-        (i, z)
+        IterationResult::new(i, z)
+    }
+
+    fn step(&self, _c: Complex<f64>, state: &mut OrbitState) {
+        let mut previous = (state.previous, self.polynomial.eval(state.previous));
+        if let Some(delta) = self.delta(state.z, &mut previous) {
+            state.advance(state.z - self.relaxation * delta);
+        }
+    }
+
+    // La sécante a besoin d'un second point de départ.
+    fn initial_state(&self, c: Complex<f64>) -> OrbitState {
+        OrbitState { z: c, previous: c + SECANT_OFFSET, iteration: 0 }
+    }
+
+    fn roots(&self) -> &[Complex<f64>] {
+        &self.roots
     }
 }
 
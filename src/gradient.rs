@@ -0,0 +1,146 @@
+use crate::color_schemes::ColorScheme;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ColorStop {
+    pub position: f64,
+    pub color: (u8, u8, u8),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Interpolation {
+    Linear,
+    Smooth,
+    OkLab,
+    Step,
+}
+
+/// Palette définie par des arrêts de couleur, format commun à tous les
+/// imports/exports de palettes.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GradientScheme {
+    pub name: String,
+    pub stops: Vec<ColorStop>,
+    pub interpolation: Interpolation,
+}
+
+impl GradientScheme {
+    pub fn new(name: &str, mut stops: Vec<ColorStop>, interpolation: Interpolation) -> Self {
+        stops.sort_by(|a, b| a.position.total_cmp(&b.position));
+        Self {
+            name: name.to_string(),
+            stops,
+            interpolation,
+        }
+    }
+
+    /// Échantillonne un schéma existant en `count` arrêts régulièrement espacés.
+    pub fn from_scheme(name: &str, scheme: &dyn ColorScheme, count: usize) -> Self {
+        let count = count.max(2);
+        let stops = (0..count)
+            .map(|i| {
+                let position = i as f64 / (count - 1) as f64;
+                ColorStop { position, color: scheme.get_color(position) }
+            })
+            .collect();
+        Self::new(name, stops, Interpolation::Linear)
+    }
+
+    pub fn sort_stops(&mut self) {
+        self.stops.sort_by(|a, b| a.position.total_cmp(&b.position));
+    }
+
+    pub fn sample(&self, t: f64) -> (u8, u8, u8) {
+        let (first, last) = match (self.stops.first(), self.stops.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return (0, 0, 0),
+        };
+        let t = if t.is_nan() { 0.0 } else { t };
+        if t <= first.position {
+            return first.color;
+        }
+        if t >= last.position {
+            return last.color;
+        }
+
+        // Premier arrêt strictement après t : deux arrêts à la même position
+        // donnent ainsi une transition franche.
+        let right = self.stops.partition_point(|stop| stop.position <= t);
+        let a = &self.stops[right - 1];
+        let b = &self.stops[right];
+        let width = b.position - a.position;
+        let f = if width > 0.0 { (t - a.position) / width } else { 0.0 };
+
+        match self.interpolation {
+            Interpolation::Linear => lerp_rgb(a.color, b.color, f),
+            Interpolation::Smooth => lerp_rgb(a.color, b.color, f * f * (3.0 - 2.0 * f)),
+            Interpolation::OkLab => {
+                let la = rgb_to_oklab(a.color);
+                let lb = rgb_to_oklab(b.color);
+                oklab_to_rgb([
+                    la[0] + (lb[0] - la[0]) * f,
+                    la[1] + (lb[1] - la[1]) * f,
+                    la[2] + (lb[2] - la[2]) * f,
+                ])
+            }
+            Interpolation::Step => a.color,
+        }
+    }
+}
+
+impl ColorScheme for GradientScheme {
+    fn get_color(&self, t: f64) -> (u8, u8, u8) {
+        self.sample(t)
+    }
+}
+
+pub(crate) fn lerp_rgb(a: (u8, u8, u8), b: (u8, u8, u8), f: f64) -> (u8, u8, u8) {
+    let mix = |x: u8, y: u8| (x as f64 + (y as f64 - x as f64) * f).round().clamp(0.0, 255.0) as u8;
+    (mix(a.0, b.0), mix(a.1, b.1), mix(a.2, b.2))
+}
+
+fn srgb_to_linear(c: f64) -> f64 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f64) -> f64 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+pub fn rgb_to_oklab(color: (u8, u8, u8)) -> [f64; 3] {
+    let r = srgb_to_linear(color.0 as f64 / 255.0);
+    let g = srgb_to_linear(color.1 as f64 / 255.0);
+    let b = srgb_to_linear(color.2 as f64 / 255.0);
+
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+    [
+        0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+        1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+        0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+    ]
+}
+
+pub fn oklab_to_rgb(lab: [f64; 3]) -> (u8, u8, u8) {
+    let l = lab[0] + 0.3963377774 * lab[1] + 0.2158037573 * lab[2];
+    let m = lab[0] - 0.1055613458 * lab[1] - 0.0638541728 * lab[2];
+    let s = lab[0] - 0.0894841775 * lab[1] - 1.2914855480 * lab[2];
+    let (l, m, s) = (l * l * l, m * m * m, s * s * s);
+
+    let r = 4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s;
+    let g = -1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s;
+    let b = -0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s;
+
+    let to_u8 = |c: f64| (linear_to_srgb(c.clamp(0.0, 1.0)) * 255.0).round() as u8;
+    (to_u8(r), to_u8(g), to_u8(b))
+}
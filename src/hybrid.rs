@@ -0,0 +1,167 @@
+use crate::formula::{CompiledFormula, FormulaError};
+use crate::fractal_types::{
+    AbsFormula, AbsVariant, Exponent, FractalFunction, IterationResult, MandelbrotSet, Multibrot, OrbitState, PhoenixSet,
+};
+use num::Complex;
+use serde::{Deserialize, Serialize};
+
+/// Formule utilisable comme étape d'un hybride, sous forme enregistrable.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum HybridFormula {
+    Mandelbrot,
+    Multibrot(Exponent),
+    Abs { variant: AbsVariant, power: u32 },
+    Phoenix(Complex<f64>),
+    Formula(String),
+}
+
+impl HybridFormula {
+    /// Une formule par famille, pour la liste déroulante de l'éditeur.
+    pub fn choices() -> [HybridFormula; 5] {
+        [
+            HybridFormula::Mandelbrot,
+            HybridFormula::Multibrot(Exponent::Integer(3)),
+            HybridFormula::Abs { variant: AbsVariant::BurningShip, power: 2 },
+            HybridFormula::Phoenix(Complex::new(-0.5, 0.0)),
+            HybridFormula::Formula(String::from("z = z^2 + c")),
+        ]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            HybridFormula::Mandelbrot => "Mandelbrot",
+            HybridFormula::Multibrot(_) => "Multibrot",
+            HybridFormula::Abs { .. } => "Famille Burning Ship",
+            HybridFormula::Phoenix(_) => "Phoenix",
+            HybridFormula::Formula(_) => "Formule",
+        }
+    }
+}
+
+/// `repeat` applications consécutives d'une formule.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HybridStep {
+    pub formula: HybridFormula,
+    pub repeat: u32,
+}
+
+impl HybridStep {
+    pub fn new(formula: HybridFormula, repeat: u32) -> Self {
+        Self { formula, repeat }
+    }
+}
+
+/// 2 × Mandelbrot puis 1 × Burning Ship.
+pub fn default_hybrid() -> Vec<HybridStep> {
+    vec![
+        HybridStep::new(HybridFormula::Mandelbrot, 2),
+        HybridStep::new(HybridFormula::Abs { variant: AbsVariant::BurningShip, power: 2 }, 1),
+    ]
+}
+
+#[derive(Clone)]
+enum Component {
+    Mandelbrot(MandelbrotSet),
+    Multibrot(Multibrot),
+    Abs(AbsFormula),
+    Phoenix(PhoenixSet),
+    Formula(CompiledFormula),
+}
+
+impl Component {
+    fn build(formula: &HybridFormula) -> Result<Component, FormulaError> {
+        Ok(match formula {
+            HybridFormula::Mandelbrot => Component::Mandelbrot(MandelbrotSet),
+            HybridFormula::Multibrot(exponent) => Component::Multibrot(Multibrot::new(*exponent)),
+            HybridFormula::Abs { variant, power } => Component::Abs(variant.formula(*power)),
+            HybridFormula::Phoenix(p) => Component::Phoenix(PhoenixSet::new(*p)),
+            HybridFormula::Formula(source) => Component::Formula(CompiledFormula::compile(source)?),
+        })
+    }
+
+    #[inline]
+    fn step(&self, c: Complex<f64>, state: &mut OrbitState) {
+        match self {
+            Component::Mandelbrot(f) => f.step(c, state),
+            Component::Multibrot(f) => f.step(c, state),
+            Component::Abs(f) => f.step(c, state),
+            Component::Phoenix(f) => f.step(c, state),
+            Component::Formula(f) => f.step(c, state),
+        }
+    }
+
+    fn escape_degree(&self) -> f64 {
+        match self {
+            Component::Mandelbrot(f) => f.escape_degree(),
+            Component::Multibrot(f) => f.escape_degree(),
+            Component::Abs(f) => f.escape_degree(),
+            Component::Phoenix(f) => f.escape_degree(),
+            Component::Formula(f) => f.escape_degree(),
+        }
+    }
+}
+
+/// Hybride : les étapes s'appliquent à tour de rôle sur une même orbite, avec
+/// un critère d'échappement commun |z| > 2.
+#[derive(Clone)]
+pub struct HybridSet {
+    cycle: Vec<Component>,
+    degree: f64,
+    pub julia: Option<Complex<f64>>,
+}
+
+impl HybridSet {
+    /// Échoue si une étape est une formule qui ne compile pas.
+    pub fn new(steps: &[HybridStep]) -> Result<Self, FormulaError> {
+        let mut cycle = Vec::new();
+        for step in steps {
+            let component = Component::build(&step.formula)?;
+            for _ in 0..step.repeat.max(1) {
+                cycle.push(component.clone());
+            }
+        }
+        if cycle.is_empty() {
+            cycle.push(Component::Mandelbrot(MandelbrotSet));
+        }
+        // Sur un cycle, |z| croît comme le produit des degrés : le degré
+        // effectif par itération en est la moyenne géométrique.
+        let log_sum: f64 = cycle.iter().map(|c| c.escape_degree().max(1.0).ln()).sum();
+        let degree = (log_sum / cycle.len() as f64).exp();
+        Ok(Self { cycle, degree, julia: None })
+    }
+
+    pub fn with_julia(mut self, c: Complex<f64>) -> Self {
+        self.julia = Some(c);
+        self
+    }
+}
+
+impl FractalFunction for HybridSet {
+    fn iterate(&self, c: Complex<f64>, _z: Complex<f64>, max_iter: u32) -> IterationResult {
+        let mut state = self.initial_state(c);
+        let mut i = 0;
+
+        while i < max_iter && !self.escaped(state.z) {
+            self.step(c, &mut state);
+            i += 1;
+        }
+        IterationResult::new(i, state.z)
+    }
+
+    /// Applique l'étape du cycle correspondant au nombre de pas déjà faits.
+    fn step(&self, c: Complex<f64>, state: &mut OrbitState) {
+        let component = &self.cycle[state.iteration as usize % self.cycle.len()];
+        component.step(self.julia.unwrap_or(c), state);
+    }
+
+    fn initial_state(&self, c: Complex<f64>) -> OrbitState {
+        match self.julia {
+            Some(_) => OrbitState::new(c),
+            None => OrbitState::new(self.initial_z(c)),
+        }
+    }
+
+    fn escape_degree(&self) -> f64 {
+        self.degree
+    }
+}
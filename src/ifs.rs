@@ -0,0 +1,297 @@
+use crate::color_schemes::ColorScheme;
+use crate::density::DensityBuffer;
+use crate::rng::Rng;
+use rayon::prelude::*;
+
+/// Transformation affine (x, y) ↦ (a·x + b·y + e, c·x + d·y + f), choisie
+/// avec la probabilité `probability` ; `color` est sa position dans la palette.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AffineMap {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+    pub probability: f64,
+    pub color: f64,
+}
+
+impl AffineMap {
+    /// `matrix` = [a, b, c, d], `translation` = [e, f].
+    pub fn new(matrix: [f64; 4], translation: [f64; 2], probability: f64) -> Self {
+        let [a, b, c, d] = matrix;
+        let [e, f] = translation;
+        Self { a, b, c, d, e, f, probability, color: 0.0 }
+    }
+
+    #[inline]
+    pub fn apply(&self, [x, y]: [f64; 2]) -> [f64; 2] {
+        [self.a * x + self.b * y + self.e, self.c * x + self.d * y + self.f]
+    }
+
+    /// Image du triangle unité (0, 0), (1, 0), (0, 1) : les poignées de l'éditeur.
+    pub fn triangle(&self) -> [[f64; 2]; 3] {
+        [self.apply([0.0, 0.0]), self.apply([1.0, 0.0]), self.apply([0.0, 1.0])]
+    }
+
+    /// Transformation qui envoie le triangle unité sur `triangle`.
+    pub fn set_triangle(&mut self, [origin, x_axis, y_axis]: [[f64; 2]; 3]) {
+        self.e = origin[0];
+        self.f = origin[1];
+        self.a = x_axis[0] - origin[0];
+        self.c = x_axis[1] - origin[1];
+        self.b = y_axis[0] - origin[0];
+        self.d = y_axis[1] - origin[1];
+    }
+
+    pub fn determinant(&self) -> f64 {
+        self.a * self.d - self.b * self.c
+    }
+}
+
+/// Système de fonctions itérées, rendu par le jeu du chaos.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ifs {
+    pub maps: Vec<AffineMap>,
+}
+
+impl Ifs {
+    /// Répartit les couleurs des transformations le long de la palette.
+    pub fn new(mut maps: Vec<AffineMap>) -> Self {
+        let last = maps.len().saturating_sub(1).max(1) as f64;
+        for (i, map) in maps.iter_mut().enumerate() {
+            map.color = i as f64 / last;
+        }
+        Self { maps }
+    }
+
+    /// Probabilités proportionnelles à l'aire de chaque image, comme le
+    /// veut la règle de Barnsley ; les transformations dégénérées gardent
+    /// une petite part pour rester visibles.
+    pub fn balance_probabilities(&mut self) {
+        let areas: Vec<f64> = self.maps.iter().map(|m| m.determinant().abs().max(0.01)).collect();
+        let total: f64 = areas.iter().sum();
+        for (map, area) in self.maps.iter_mut().zip(areas) {
+            map.probability = area / total;
+        }
+    }
+
+    // Probabilités cumulées, normalisées à 1.
+    fn cumulative(&self) -> Vec<f64> {
+        let total: f64 = self.maps.iter().map(|m| m.probability.max(0.0)).sum();
+        let mut sum = 0.0;
+        self.maps
+            .iter()
+            .map(|m| {
+                sum += m.probability.max(0.0) / total.max(f64::MIN_POSITIVE);
+                sum
+            })
+            .collect()
+    }
+
+    /// Rectangle englobant l'attracteur, estimé par une courte partie du jeu du chaos.
+    pub fn bounds(&self) -> IfsView {
+        let mut view = IfsView { min: [f64::INFINITY; 2], max: [f64::NEG_INFINITY; 2] };
+        if self.maps.is_empty() {
+            return IfsView { min: [-1.0; 2], max: [1.0; 2] };
+        }
+        let cumulative = self.cumulative();
+        let mut rng = Rng::new(0);
+        let mut p = [0.0, 0.0];
+        for i in 0..20_000 {
+            p = self.maps[pick(&cumulative, &mut rng)].apply(p);
+            if i >= WARMUP && p[0].is_finite() && p[1].is_finite() {
+                view.min = [view.min[0].min(p[0]), view.min[1].min(p[1])];
+                view.max = [view.max[0].max(p[0]), view.max[1].max(p[1])];
+            }
+        }
+        if !(view.min[0] <= view.max[0] && view.min[1] <= view.max[1]) {
+            return IfsView { min: [-1.0; 2], max: [1.0; 2] };
+        }
+        // Marge de 5 %, et une taille minimale si l'attracteur est un point.
+        for axis in 0..2 {
+            let margin = ((view.max[axis] - view.min[axis]) * 0.05).max(1e-3);
+            view.min[axis] -= margin;
+            view.max[axis] += margin;
+        }
+        view
+    }
+}
+
+// Itérations ignorées avant que le point n'atteigne l'attracteur.
+const WARMUP: usize = 20;
+
+#[inline]
+fn pick(cumulative: &[f64], rng: &mut Rng) -> usize {
+    let r = rng.next_f64();
+    cumulative.iter().position(|&p| r < p).unwrap_or(cumulative.len() - 1)
+}
+
+/// Fenêtre du plan affichée, y vers le haut.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IfsView {
+    pub min: [f64; 2],
+    pub max: [f64; 2],
+}
+
+impl IfsView {
+    /// Élargit la fenêtre pour que les pixels soient carrés.
+    pub fn fit(&self, width: usize, height: usize) -> IfsView {
+        let size = [self.max[0] - self.min[0], self.max[1] - self.min[1]];
+        let scale = (size[0] / width.max(1) as f64).max(size[1] / height.max(1) as f64);
+        let half = [scale * width as f64 / 2.0, scale * height as f64 / 2.0];
+        let center = [(self.min[0] + self.max[0]) / 2.0, (self.min[1] + self.max[1]) / 2.0];
+        IfsView {
+            min: [center[0] - half[0], center[1] - half[1]],
+            max: [center[0] + half[0], center[1] + half[1]],
+        }
+    }
+
+    /// Coordonnées en pixels (fractionnaires) d'un point du plan.
+    #[inline]
+    pub fn to_pixel(self, [x, y]: [f64; 2], width: usize, height: usize) -> (f64, f64) {
+        (
+            (x - self.min[0]) / (self.max[0] - self.min[0]) * width as f64,
+            (self.max[1] - y) / (self.max[1] - self.min[1]) * height as f64,
+        )
+    }
+
+    pub fn to_plane(self, (px, py): (f64, f64), width: usize, height: usize) -> [f64; 2] {
+        [
+            self.min[0] + px / width as f64 * (self.max[0] - self.min[0]),
+            self.max[1] - py / height as f64 * (self.max[1] - self.min[1]),
+        ]
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IfsPreset {
+    BarnsleyFern,
+    SierpinskiTriangle,
+    SierpinskiCarpet,
+    LevyC,
+    HeighwayDragon,
+}
+
+impl IfsPreset {
+    pub const ALL: [IfsPreset; 5] = [
+        IfsPreset::BarnsleyFern,
+        IfsPreset::SierpinskiTriangle,
+        IfsPreset::SierpinskiCarpet,
+        IfsPreset::LevyC,
+        IfsPreset::HeighwayDragon,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            IfsPreset::BarnsleyFern => "Fougère de Barnsley",
+            IfsPreset::SierpinskiTriangle => "Triangle de Sierpiński",
+            IfsPreset::SierpinskiCarpet => "Tapis de Sierpiński",
+            IfsPreset::LevyC => "Courbe C de Lévy",
+            IfsPreset::HeighwayDragon => "Dragon de Heighway",
+        }
+    }
+
+    pub fn ifs(&self) -> Ifs {
+        match self {
+            IfsPreset::BarnsleyFern => Ifs::new(vec![
+                AffineMap::new([0.0, 0.0, 0.0, 0.16], [0.0, 0.0], 0.01),
+                AffineMap::new([0.85, 0.04, -0.04, 0.85], [0.0, 1.6], 0.85),
+                AffineMap::new([0.2, -0.26, 0.23, 0.22], [0.0, 1.6], 0.07),
+                AffineMap::new([-0.15, 0.28, 0.26, 0.24], [0.0, 0.44], 0.07),
+            ]),
+            IfsPreset::SierpinskiTriangle => {
+                let h = 3f64.sqrt() / 4.0;
+                Ifs::new(
+                    [[0.0, 0.0], [0.5, 0.0], [0.25, h]]
+                        .iter()
+                        .map(|&t| AffineMap::new([0.5, 0.0, 0.0, 0.5], t, 1.0 / 3.0))
+                        .collect(),
+                )
+            }
+            IfsPreset::SierpinskiCarpet => Ifs::new(
+                (0..9)
+                    .filter(|&i| i != 4)
+                    .map(|i| {
+                        let t = [(i % 3) as f64 / 3.0, (i / 3) as f64 / 3.0];
+                        AffineMap::new([1.0 / 3.0, 0.0, 0.0, 1.0 / 3.0], t, 1.0 / 8.0)
+                    })
+                    .collect(),
+            ),
+            IfsPreset::LevyC => Ifs::new(vec![
+                AffineMap::new([0.5, -0.5, 0.5, 0.5], [0.0, 0.0], 0.5),
+                AffineMap::new([0.5, 0.5, -0.5, 0.5], [0.5, 0.5], 0.5),
+            ]),
+            IfsPreset::HeighwayDragon => Ifs::new(vec![
+                AffineMap::new([0.5, -0.5, 0.5, 0.5], [0.0, 0.0], 0.5),
+                AffineMap::new([-0.5, -0.5, 0.5, -0.5], [1.0, 0.0], 0.5),
+            ]),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct IfsSettings {
+    pub samples: usize,
+    pub seed: u64,
+    pub gamma: f64,
+}
+
+impl Default for IfsSettings {
+    fn default() -> Self {
+        Self { samples: 2_000_000, seed: 1, gamma: 2.2 }
+    }
+}
+
+/// Jeu du chaos : chaque point porte une coordonnée de couleur qui se
+/// rapproche, à chaque étape, de celle de la transformation appliquée ; elle
+/// est lue dans la palette puis accumulée dans l'histogramme.
+pub fn render(
+    ifs: &Ifs,
+    view: &IfsView,
+    scheme: &(dyn ColorScheme + Sync),
+    settings: &IfsSettings,
+    (width, height): (usize, usize),
+) -> Vec<u8> {
+    let mut density = DensityBuffer::new(width, height);
+    if ifs.maps.is_empty() {
+        return density.to_rgba_log_density(settings.gamma);
+    }
+    let cumulative = ifs.cumulative();
+    let palette: Vec<[f32; 3]> = (0..256)
+        .map(|i| {
+            let (r, g, b) = scheme.get_color(i as f64 / 255.0);
+            [r as f32, g as f32, b as f32]
+        })
+        .collect();
+
+    let threads = rayon::current_num_threads().max(1);
+    let per_thread = settings.samples / threads + 1;
+    let buffers: Vec<DensityBuffer> = (0..threads)
+        .into_par_iter()
+        .map(|thread| {
+            let mut local = DensityBuffer::new(width, height);
+            let mut rng = Rng::stream(settings.seed, thread as u64);
+            let mut p = [rng.range(-1.0, 1.0), rng.range(-1.0, 1.0)];
+            let mut color = rng.next_f64();
+            for i in 0..per_thread + WARMUP {
+                let map = &ifs.maps[pick(&cumulative, &mut rng)];
+                p = map.apply(p);
+                color = (color + map.color) / 2.0;
+                if i < WARMUP {
+                    continue;
+                }
+                let (x, y) = view.to_pixel(p, width, height);
+                if let Some(index) = local.index(x, y) {
+                    local.add_color(index, palette[(color.clamp(0.0, 1.0) * 255.0) as usize], 1.0);
+                }
+            }
+            local
+        })
+        .collect();
+    for buffer in &buffers {
+        density.merge(buffer);
+    }
+    density.to_rgba_log_density(settings.gamma)
+}
@@ -0,0 +1,238 @@
+use crate::fractal_calculator::IterationData;
+use crate::fractal_params::FractalParams;
+use crate::mobius::{is_infinite, Mobius};
+use num::Complex;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// Garde-fou pour les groupes non discrets, dont l'arbre des mots ne se
+// referme jamais à l'échelle du pixel.
+const MAX_SEGMENTS: usize = 8_000_000;
+
+/// Groupe de Klein à deux générateurs a et b.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum KleinianGroup {
+    /// Recette de Grand-mère : traces de a et de b, commutateur parabolique.
+    Recipe { ta: Complex<f64>, tb: Complex<f64> },
+    Generators { a: Mobius, b: Mobius },
+}
+
+impl Default for KleinianGroup {
+    fn default() -> Self {
+        KleinianPreset::ApollonianGasket.group()
+    }
+}
+
+impl KleinianGroup {
+    pub fn generators(&self) -> [Mobius; 2] {
+        match *self {
+            KleinianGroup::Recipe { ta, tb } => grandma_recipe(ta, tb),
+            KleinianGroup::Generators { a, b } => [a.normalized(), b.normalized()],
+        }
+    }
+}
+
+/// Générateurs de trace `ta` et `tb` dont le commutateur abAB est
+/// parabolique de trace −2 (Indra's Pearls, chapitre 8).
+pub fn grandma_recipe(ta: Complex<f64>, tb: Complex<f64>) -> [Mobius; 2] {
+    let i = Complex::new(0.0, 1.0);
+    let tab = (ta * tb - (ta * ta * tb * tb - 4.0 * (ta * ta + tb * tb)).sqrt()) / 2.0;
+    let z0 = (tab - 2.0) * tb / (tb * tab - 2.0 * ta + 2.0 * i * tab);
+    let a = Mobius::new(
+        ta / 2.0,
+        (ta * tab - 2.0 * tb + 4.0 * i) / ((2.0 * tab + 4.0) * z0),
+        (ta * tab - 2.0 * tb - 4.0 * i) * z0 / (2.0 * tab - 4.0),
+        ta / 2.0,
+    );
+    let b = Mobius::new((tb - 2.0 * i) / 2.0, tb / 2.0, tb / 2.0, (tb + 2.0 * i) / 2.0);
+    [a, b]
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KleinianPreset {
+    ApollonianGasket,
+    Spirals,
+    QuasiCircle,
+    RileyHalfCusp,
+    RileyCusp,
+    RileySlice,
+}
+
+impl KleinianPreset {
+    pub const ALL: [KleinianPreset; 6] = [
+        KleinianPreset::ApollonianGasket,
+        KleinianPreset::Spirals,
+        KleinianPreset::QuasiCircle,
+        KleinianPreset::RileyHalfCusp,
+        KleinianPreset::RileyCusp,
+        KleinianPreset::RileySlice,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            KleinianPreset::ApollonianGasket => "Baderne d'Apollonius",
+            KleinianPreset::Spirals => "Spirales (ta = 1,91 + 0,05i, tb = 2)",
+            KleinianPreset::QuasiCircle => "Quasi-cercle (ta = 1,87 + 0,1i, tb = 1,87 − 0,1i)",
+            KleinianPreset::RileyHalfCusp => "Tranche de Riley, cusp 1/2 (ρ = i)",
+            KleinianPreset::RileyCusp => "Tranche de Riley, cusp (ρ = (3 + i√7)/4)",
+            KleinianPreset::RileySlice => "Tranche de Riley, groupe de Schottky (ρ = 1,15i)",
+        }
+    }
+
+    pub fn group(&self) -> KleinianGroup {
+        let recipe = |ta: (f64, f64), tb: (f64, f64)| KleinianGroup::Recipe {
+            ta: Complex::new(ta.0, ta.1),
+            tb: Complex::new(tb.0, tb.1),
+        };
+        // Groupe de Riley : deux paraboliques, z ↦ z + 2 et z ↦ z/(ρz + 1) ;
+        // les cusps du bord de la tranche rendent parabolique un mot de plus.
+        let riley = |rho: Complex<f64>| KleinianGroup::Generators {
+            a: Mobius::new(Complex::new(1.0, 0.0), Complex::new(2.0, 0.0), Complex::new(0.0, 0.0), Complex::new(1.0, 0.0)),
+            b: Mobius::new(Complex::new(1.0, 0.0), Complex::new(0.0, 0.0), rho, Complex::new(1.0, 0.0)),
+        };
+        match self {
+            // Les générateurs explicites du livre, équivalents à ta = tb = 2.
+            KleinianPreset::ApollonianGasket => KleinianGroup::Generators {
+                a: Mobius::new(Complex::new(1.0, 0.0), Complex::new(0.0, 0.0), Complex::new(0.0, -2.0), Complex::new(1.0, 0.0)),
+                b: Mobius::new(Complex::new(1.0, -1.0), Complex::new(1.0, 0.0), Complex::new(1.0, 0.0), Complex::new(1.0, 1.0)),
+            },
+            KleinianPreset::Spirals => recipe((1.91, 0.05), (2.0, 0.0)),
+            KleinianPreset::QuasiCircle => recipe((1.87, 0.1), (1.87, -0.1)),
+            KleinianPreset::RileyHalfCusp => riley(Complex::new(0.0, 1.0)),
+            KleinianPreset::RileyCusp => riley(Complex::new(0.75, 7f64.sqrt() / 4.0)),
+            KleinianPreset::RileySlice => riley(Complex::new(0.0, 1.15)),
+        }
+    }
+
+    /// Centre et zoom qui cadrent l'ensemble limite.
+    pub fn view(&self) -> (Complex<f64>, f64) {
+        match self {
+            KleinianPreset::ApollonianGasket | KleinianPreset::Spirals => (Complex::new(0.0, 0.0), 0.45),
+            KleinianPreset::QuasiCircle => (Complex::new(0.0, 0.0), 0.08),
+            _ => (Complex::new(0.0, 0.0), 0.4),
+        }
+    }
+}
+
+// Parcours en profondeur des mots réduits ; `gens` contient a, b, A = a⁻¹,
+// B = b⁻¹, de sorte que l'inverse du générateur k est k + 2 (mod 4).
+struct Search {
+    gens: [Mobius; 4],
+    /// Pour chaque générateur, des points fixes de l'ensemble limite : ceux
+    /// des deux permutations circulaires du commutateur qui finissent par
+    /// lui, et le sien.
+    fixed: [[Complex<f64>; 3]; 4],
+    epsilon: f64,
+    max_depth: u32,
+    budget: usize,
+    segments: Vec<[Complex<f64>; 2]>,
+}
+
+impl Search {
+    fn new(gens: [Mobius; 4], epsilon: f64, max_depth: u32, budget: usize) -> Self {
+        let fixed = [0, 1, 2, 3].map(|k| {
+            let g = |offset: usize| gens[(k + offset) % 4];
+            [
+                (g(1) * g(2) * g(3) * g(0)).attracting_fixed_point(),
+                g(0).attracting_fixed_point(),
+                (g(3) * g(2) * g(1) * g(0)).attracting_fixed_point(),
+            ]
+        });
+        Self { gens, fixed, epsilon, max_depth, budget, segments: Vec::new() }
+    }
+
+    /// `word` finit par le générateur `tag` ; la branche s'arrête quand les
+    /// images des points fixes sont à moins d'un pixel les unes des autres.
+    fn explore(&mut self, word: Mobius, tag: usize, depth: u32) {
+        if self.segments.len() >= self.budget {
+            return;
+        }
+        let [p, q, r] = self.fixed[tag].map(|z| word.apply(z));
+        let small = (p - q).norm() < self.epsilon && (q - r).norm() < self.epsilon;
+        if small || depth >= self.max_depth {
+            self.segments.push([p, q]);
+            self.segments.push([q, r]);
+            return;
+        }
+        // De droite à gauche, en évitant l'inverse : l'ensemble limite est
+        // parcouru dans l'ordre.
+        for next in [tag + 1, tag, tag + 3] {
+            let next = next % 4;
+            self.explore(word * self.gens[next], next, depth + 1);
+        }
+    }
+}
+
+/// Ensemble limite d'un groupe de Klein, tracé dans la vue courante.
+pub struct KleinianRenderer {
+    pub params: FractalParams,
+}
+
+impl KleinianRenderer {
+    pub fn new(params: FractalParams) -> Self {
+        Self { params }
+    }
+
+    /// Segments de l'ensemble limite, dans l'ordre du parcours, à la
+    /// précision d'un pixel de la vue.
+    pub fn limit_set(&self) -> Vec<[Complex<f64>; 2]> {
+        let (width, height) = self.params.size;
+        let [a, b] = self.params.kleinian.generators();
+        let gens = [a, b, a.inverse(), b.inverse()];
+        let epsilon = 1.0 / (self.params.zoom * width.max(height).max(1) as f64);
+        let max_depth = self.params.kleinian_depth.max(1);
+        let branches: Vec<Vec<[Complex<f64>; 2]>> = (0..4)
+            .into_par_iter()
+            .map(|tag| {
+                let mut search = Search::new(gens, epsilon, max_depth, MAX_SEGMENTS / 4);
+                search.explore(gens[tag], tag, 1);
+                search.segments
+            })
+            .collect();
+        branches.concat()
+    }
+
+    /// Les pixels traversés prennent la position de leur segment dans le
+    /// parcours des segments visibles, ramenée à [0, max_iterations] ; les
+    /// autres restent noirs.
+    pub fn compute(&self) -> IterationData {
+        let (width, height) = self.params.size;
+        let max_iterations = self.params.max_iterations.max(1);
+        let mut smooth = vec![f64::NAN; width * height];
+        let to_pixel = |z: Complex<f64>| {
+            [
+                ((z.re - self.params.center.re) * self.params.zoom + 0.5) * width as f64,
+                ((z.im - self.params.center.im) * self.params.zoom + 0.5) * height as f64,
+            ]
+        };
+        let inside = |[x, y]: [f64; 2]| x >= 0.0 && y >= 0.0 && x < width as f64 && y < height as f64;
+        let segments: Vec<[[f64; 2]; 2]> = self
+            .limit_set()
+            .into_iter()
+            .filter(|&[from, to]| !is_infinite(from) && !is_infinite(to))
+            .map(|[from, to]| [to_pixel(from), to_pixel(to)])
+            .filter(|&[p, q]| inside(p) || inside(q))
+            .collect();
+        let last = segments.len().saturating_sub(1).max(1) as f64;
+        for (i, &[p, q]) in segments.iter().enumerate() {
+            let value = i as f64 / last * max_iterations as f64;
+            // Les segments qui débordent largement de l'image sont tronqués.
+            let steps = (q[0] - p[0]).abs().max((q[1] - p[1]).abs()).ceil().clamp(1.0, 4.0 * (width + height) as f64) as usize;
+            for s in 0..=steps {
+                let t = s as f64 / steps as f64;
+                let (x, y) = (p[0] + (q[0] - p[0]) * t, p[1] + (q[1] - p[1]) * t);
+                if x >= 0.0 && y >= 0.0 && (x as usize) < width && (y as usize) < height {
+                    smooth[y as usize * width + x as usize] = value;
+                }
+            }
+        }
+        IterationData {
+            size: (width, height),
+            max_iterations,
+            converged: vec![false; smooth.len()],
+            smooth,
+            root_count: 0,
+            basins: Vec::new(),
+        }
+    }
+}
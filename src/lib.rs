@@ -1,6 +1,27 @@
 // pub mod app;
 pub mod fractal_params;
 pub mod fractal_types;
+pub mod polynomial;
+pub mod formula;
+pub mod hybrid;
 pub mod color_schemes;
 pub mod fractal_calculator;
+pub mod gradient;
+pub mod palette_io;
+pub mod palette_extract;
+pub mod color_cycle;
+pub mod rng;
+pub mod density;
+pub mod buddhabrot;
+pub mod ifs;
+pub mod flame;
+pub mod flame_io;
+pub mod attractor;
+pub mod lsystem;
+pub mod mobius;
+pub mod kleinian;
+pub mod distance;
+pub mod raymarch;
+pub mod mesh;
 // pub mod ui;
+// pub mod palette_editor;
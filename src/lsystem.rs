@@ -0,0 +1,655 @@
+use crate::color_schemes::ColorScheme;
+use crate::rng::Rng;
+use std::f64::consts::PI;
+use std::fmt;
+use std::fmt::Write as _;
+
+// Au-delà, la chaîne dérivée ne tiendrait plus raisonnablement en mémoire.
+const MAX_MODULES: usize = 5_000_000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LSystemError {
+    Axiom(String),
+    /// `line` numérote les lignes du texte des règles à partir de 1.
+    Rule { line: usize, message: String },
+    TooLong(usize),
+    /// Écriture de l'export impossible.
+    Export(String),
+}
+
+impl fmt::Display for LSystemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LSystemError::Axiom(message) => write!(f, "axiome : {}", message),
+            LSystemError::Rule { line, message } => write!(f, "règle ligne {} : {}", line, message),
+            LSystemError::TooLong(max) => write!(f, "la chaîne dépasse {} symboles, réduire la profondeur", max),
+            LSystemError::Export(message) => write!(f, "échec de l'export : {}", message),
+        }
+    }
+}
+
+impl std::error::Error for LSystemError {}
+
+/// Symbole de la chaîne, avec ses paramètres effectifs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Module {
+    pub symbol: char,
+    pub params: Vec<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Expr {
+    Number(f64),
+    Param(usize),
+    Neg(Box<Expr>),
+    Binary(char, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    fn eval(&self, params: &[f64]) -> f64 {
+        match self {
+            Expr::Number(value) => *value,
+            Expr::Param(index) => params[*index],
+            Expr::Neg(expr) => -expr.eval(params),
+            Expr::Binary(op, lhs, rhs) => {
+                let (a, b) = (lhs.eval(params), rhs.eval(params));
+                match op {
+                    '+' => a + b,
+                    '-' => a - b,
+                    '*' => a * b,
+                    '/' => a / b,
+                    _ => a.powf(b),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Condition {
+    Compare(&'static str, Expr, Expr),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+}
+
+impl Condition {
+    fn holds(&self, params: &[f64]) -> bool {
+        match self {
+            Condition::Compare(op, lhs, rhs) => {
+                let (a, b) = (lhs.eval(params), rhs.eval(params));
+                match *op {
+                    "<" => a < b,
+                    "<=" => a <= b,
+                    ">" => a > b,
+                    ">=" => a >= b,
+                    "!=" => a != b,
+                    _ => a == b,
+                }
+            }
+            Condition::And(lhs, rhs) => lhs.holds(params) && rhs.holds(params),
+            Condition::Or(lhs, rhs) => lhs.holds(params) || rhs.holds(params),
+        }
+    }
+}
+
+/// Symbole d'un successeur ; ses paramètres sont des expressions des
+/// paramètres formels du prédécesseur.
+#[derive(Clone, Debug, PartialEq)]
+struct Production {
+    symbol: char,
+    args: Vec<Expr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Rule {
+    symbol: char,
+    arity: usize,
+    condition: Option<Condition>,
+    successor: Vec<Production>,
+    probability: f64,
+}
+
+// Analyseur des expressions et conditions, entre parenthèses dans les mots.
+struct ExprParser<'a> {
+    chars: &'a [char],
+    pos: usize,
+    params: &'a [String],
+}
+
+impl ExprParser<'_> {
+    fn peek(&mut self) -> Option<char> {
+        while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, text: &str) -> bool {
+        self.peek();
+        let matches = text.chars().enumerate().all(|(i, c)| self.chars.get(self.pos + i) == Some(&c));
+        if matches {
+            self.pos += text.chars().count();
+        }
+        matches
+    }
+
+    fn expr(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.term()?;
+        while let Some(op @ ('+' | '-')) = self.peek() {
+            self.pos += 1;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.term()?));
+        }
+        Ok(lhs)
+    }
+
+    fn term(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.unary()?;
+        while let Some(op @ ('*' | '/')) = self.peek() {
+            self.pos += 1;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.unary()?));
+        }
+        Ok(lhs)
+    }
+
+    // −x^2 vaut −(x^2) ; la puissance est associative à droite.
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat("-") {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        let base = self.primary()?;
+        if self.eat("^") {
+            return Ok(Expr::Binary('^', Box::new(base), Box::new(self.unary()?)));
+        }
+        Ok(base)
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                let expr = self.expr()?;
+                if !self.eat(")") {
+                    return Err("« ) » attendue".to_string());
+                }
+                Ok(expr)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let start = self.pos;
+                while self.chars.get(self.pos).is_some_and(|c| c.is_ascii_digit() || *c == '.') {
+                    self.pos += 1;
+                }
+                let text: String = self.chars[start..self.pos].iter().collect();
+                text.parse().map(Expr::Number).map_err(|_| format!("nombre invalide « {} »", text))
+            }
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let start = self.pos;
+                while self.chars.get(self.pos).is_some_and(|c| c.is_alphanumeric() || *c == '_') {
+                    self.pos += 1;
+                }
+                let name: String = self.chars[start..self.pos].iter().collect();
+                self.params
+                    .iter()
+                    .position(|p| *p == name)
+                    .map(Expr::Param)
+                    .ok_or_else(|| format!("paramètre inconnu « {} »", name))
+            }
+            Some(c) => Err(format!("caractère inattendu « {} »", c)),
+            None => Err("expression incomplète".to_string()),
+        }
+    }
+
+    fn condition(&mut self) -> Result<Condition, String> {
+        let mut lhs = self.conjunction()?;
+        while self.eat("||") {
+            lhs = Condition::Or(Box::new(lhs), Box::new(self.conjunction()?));
+        }
+        Ok(lhs)
+    }
+
+    fn conjunction(&mut self) -> Result<Condition, String> {
+        let mut lhs = self.comparison()?;
+        while self.eat("&&") {
+            lhs = Condition::And(Box::new(lhs), Box::new(self.comparison()?));
+        }
+        Ok(lhs)
+    }
+
+    fn comparison(&mut self) -> Result<Condition, String> {
+        let lhs = self.expr()?;
+        // Les opérateurs de deux caractères d'abord.
+        let op = ["<=", ">=", "==", "!=", "<", ">", "="]
+            .into_iter()
+            .find(|op| self.eat(op))
+            .ok_or("comparaison attendue")?;
+        Ok(Condition::Compare(op, lhs, self.expr()?))
+    }
+}
+
+/// Suite de symboles, chacun suivi éventuellement de ses arguments entre
+/// parenthèses : `F(l*0.5)+(30)A(l, w)`.
+fn parse_word(text: &str, params: &[String]) -> Result<Vec<Production>, String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut word = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let symbol = chars[i];
+        i += 1;
+        if symbol.is_whitespace() {
+            continue;
+        }
+        if matches!(symbol, '(' | ')' | ',') {
+            return Err(format!("« {} » inattendu", symbol));
+        }
+        let mut args = Vec::new();
+        if chars.get(i) == Some(&'(') {
+            let mut parser = ExprParser { chars: &chars, pos: i + 1, params };
+            loop {
+                args.push(parser.expr()?);
+                if parser.eat(")") {
+                    break;
+                }
+                if !parser.eat(",") {
+                    return Err(format!("« ) » attendue après les arguments de « {} »", symbol));
+                }
+            }
+            i = parser.pos;
+        }
+        word.push(Production { symbol, args });
+    }
+    Ok(word)
+}
+
+fn constant(text: &str) -> Result<f64, String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut parser = ExprParser { chars: &chars, pos: 0, params: &[] };
+    let value = parser.expr()?.eval(&[]);
+    match parser.peek() {
+        None => Ok(value),
+        Some(c) => Err(format!("caractère inattendu « {} »", c)),
+    }
+}
+
+/// `A(x, y) : condition -(probabilité)-> successeur` ; condition et
+/// probabilité sont facultatives.
+fn parse_rule(line: &str) -> Result<Rule, String> {
+    let arrow = line.find("->").ok_or("« -> » attendu")?;
+    let mut left = line[..arrow].trim();
+    let mut probability = 1.0;
+    if let Some(open) = left.strip_suffix(')').and_then(|s| s.rfind("-(")) {
+        probability = constant(&left[open + 2..left.len() - 1])?;
+        if probability.is_nan() || probability < 0.0 {
+            return Err("probabilité négative".to_string());
+        }
+        left = left[..open].trim();
+    }
+    let (predecessor, condition) = match left.split_once(':') {
+        Some((predecessor, condition)) => (predecessor.trim(), Some(condition)),
+        None => (left, None),
+    };
+
+    let mut chars = predecessor.chars();
+    let symbol = chars.next().ok_or("prédécesseur manquant")?;
+    let rest = chars.as_str().trim();
+    let params: Vec<String> = if rest.is_empty() {
+        Vec::new()
+    } else {
+        let names = rest
+            .strip_prefix('(')
+            .and_then(|r| r.strip_suffix(')'))
+            .ok_or_else(|| format!("prédécesseur invalide « {} »", predecessor))?;
+        names.split(',').map(|name| name.trim().to_string()).collect()
+    };
+    if let Some(name) = params.iter().find(|n| n.is_empty() || !n.chars().all(|c| c.is_alphanumeric() || c == '_')) {
+        return Err(format!("nom de paramètre invalide « {} »", name));
+    }
+
+    let condition = match condition {
+        Some(text) => {
+            let chars: Vec<char> = text.chars().collect();
+            let mut parser = ExprParser { chars: &chars, pos: 0, params: &params };
+            let condition = parser.condition()?;
+            if let Some(c) = parser.peek() {
+                return Err(format!("caractère inattendu « {} » dans la condition", c));
+            }
+            Some(condition)
+        }
+        None => None,
+    };
+    Ok(Rule {
+        symbol,
+        arity: params.len(),
+        condition,
+        successor: parse_word(&line[arrow + 2..], &params)?,
+        probability,
+    })
+}
+
+/// Parmi les règles applicables au module, en tire une au hasard selon
+/// leurs probabilités ; `None` si aucune ne s'applique.
+fn choose<'a>(rules: &'a [Rule], module: &Module, rng: &mut Rng) -> Option<&'a Rule> {
+    let applicable = |rule: &&Rule| {
+        rule.symbol == module.symbol
+            && rule.arity == module.params.len()
+            && rule.condition.as_ref().is_none_or(|c| c.holds(&module.params))
+    };
+    let mut candidates = rules.iter().filter(applicable);
+    let first = candidates.next()?;
+    let total: f64 = rules.iter().filter(applicable).map(|r| r.probability).sum();
+    if total <= 0.0 || candidates.next().is_none() {
+        return Some(first);
+    }
+    let mut r = rng.next_f64() * total;
+    for rule in rules.iter().filter(applicable) {
+        r -= rule.probability;
+        if r < 0.0 {
+            return Some(rule);
+        }
+    }
+    Some(first)
+}
+
+/// Trait de tortue, en unités de tortue ; `width` est en pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Segment {
+    pub from: [f64; 2],
+    pub to: [f64; 2],
+    pub width: f64,
+}
+
+#[derive(Clone, Copy)]
+struct Turtle {
+    position: [f64; 2],
+    heading: f64,
+    width: f64,
+}
+
+/// Système de Lindenmayer et son interprétation par la tortue.
+///
+/// Symboles de la tortue : `F`, `G` avancent en traçant, `f`, `g` sans
+/// tracer (d'un pas, ou de leur paramètre) ; `+` et `-` tournent à gauche et à
+/// droite (de l'angle, ou de leur paramètre en degrés), `|` fait demi-tour ;
+/// `[` et `]` empilent et dépilent l'état ; `!(w)` fixe l'épaisseur du trait,
+/// `!` la multiplie par `width_factor`. Les autres symboles sont ignorés.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LSystem {
+    pub axiom: String,
+    /// Une règle par ligne ; les lignes vides ou commençant par `#` sont ignorées.
+    pub rules: String,
+    pub iterations: u32,
+    /// Angle des virages, en degrés.
+    pub angle: f64,
+    /// Direction initiale de la tortue, en degrés (90 : vers le haut).
+    pub heading: f64,
+    pub step: f64,
+    pub line_width: f64,
+    pub width_factor: f64,
+    /// Graine des règles stochastiques.
+    pub seed: u64,
+}
+
+impl Default for LSystem {
+    fn default() -> Self {
+        LSystemPreset::KochSnowflake.lsystem()
+    }
+}
+
+impl LSystem {
+    /// Chaîne obtenue après `iterations` réécritures de l'axiome.
+    pub fn derive(&self) -> Result<Vec<Module>, LSystemError> {
+        let mut word: Vec<Module> = parse_word(&self.axiom, &[])
+            .map_err(LSystemError::Axiom)?
+            .into_iter()
+            .map(|p| Module { symbol: p.symbol, params: p.args.iter().map(|e| e.eval(&[])).collect() })
+            .collect();
+        let rules = self
+            .rules
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+            .map(|(i, line)| parse_rule(line).map_err(|message| LSystemError::Rule { line: i + 1, message }))
+            .collect::<Result<Vec<Rule>, _>>()?;
+
+        let mut rng = Rng::new(self.seed);
+        for _ in 0..self.iterations {
+            let mut next = Vec::with_capacity(word.len() * 2);
+            for module in &word {
+                match choose(&rules, module, &mut rng) {
+                    Some(rule) => next.extend(rule.successor.iter().map(|p| Module {
+                        symbol: p.symbol,
+                        params: p.args.iter().map(|e| e.eval(&module.params)).collect(),
+                    })),
+                    None => next.push(module.clone()),
+                }
+                if next.len() > MAX_MODULES {
+                    return Err(LSystemError::TooLong(MAX_MODULES));
+                }
+            }
+            word = next;
+        }
+        Ok(word)
+    }
+
+    /// Traits de la tortue qui lit la chaîne.
+    pub fn draw(&self, word: &[Module]) -> Vec<Segment> {
+        let mut turtle = Turtle { position: [0.0, 0.0], heading: self.heading.to_radians(), width: self.line_width };
+        let mut stack = Vec::new();
+        let mut segments = Vec::new();
+        for module in word {
+            let arg = module.params.first().copied();
+            match module.symbol {
+                'F' | 'G' | 'f' | 'g' => {
+                    let length = arg.unwrap_or(self.step);
+                    let [x, y] = turtle.position;
+                    let to = [x + length * turtle.heading.cos(), y + length * turtle.heading.sin()];
+                    if module.symbol.is_uppercase() {
+                        segments.push(Segment { from: turtle.position, to, width: turtle.width });
+                    }
+                    turtle.position = to;
+                }
+                '+' => turtle.heading += arg.unwrap_or(self.angle).to_radians(),
+                '-' => turtle.heading -= arg.unwrap_or(self.angle).to_radians(),
+                '|' => turtle.heading += PI,
+                '[' => stack.push(turtle),
+                ']' => turtle = stack.pop().unwrap_or(turtle),
+                '!' => turtle.width = arg.unwrap_or(turtle.width * self.width_factor),
+                _ => {}
+            }
+        }
+        segments
+    }
+
+    pub fn segments(&self) -> Result<Vec<Segment>, LSystemError> {
+        Ok(self.draw(&self.derive()?))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LSystemPreset {
+    KochSnowflake,
+    HilbertCurve,
+    GosperCurve,
+    Plant,
+    StochasticPlant,
+    ParametricTree,
+}
+
+impl LSystemPreset {
+    pub const ALL: [LSystemPreset; 6] = [
+        LSystemPreset::KochSnowflake,
+        LSystemPreset::HilbertCurve,
+        LSystemPreset::GosperCurve,
+        LSystemPreset::Plant,
+        LSystemPreset::StochasticPlant,
+        LSystemPreset::ParametricTree,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LSystemPreset::KochSnowflake => "Flocon de Koch",
+            LSystemPreset::HilbertCurve => "Courbe de Hilbert",
+            LSystemPreset::GosperCurve => "Courbe de Gosper",
+            LSystemPreset::Plant => "Plante",
+            LSystemPreset::StochasticPlant => "Plante stochastique",
+            LSystemPreset::ParametricTree => "Arbre paramétrique",
+        }
+    }
+
+    pub fn lsystem(&self) -> LSystem {
+        let base = LSystem {
+            axiom: String::new(),
+            rules: String::new(),
+            iterations: 4,
+            angle: 90.0,
+            heading: 0.0,
+            step: 1.0,
+            line_width: 1.5,
+            width_factor: 0.7,
+            seed: 1,
+        };
+        let (axiom, rules, iterations, angle, heading) = match self {
+            LSystemPreset::KochSnowflake => ("F--F--F", "F -> F+F--F+F", 4, 60.0, 0.0),
+            LSystemPreset::HilbertCurve => ("A", "A -> +BF-AFA-FB+\nB -> -AF+BFB+FA-", 5, 90.0, 0.0),
+            LSystemPreset::GosperCurve => ("F", "F -> F-G--G+F++FF+G-\nG -> +F-GG--G-F++F+G", 4, 60.0, 0.0),
+            LSystemPreset::Plant => ("X", "X -> F+[[X]-X]-F[-FX]+X\nF -> FF", 6, 25.0, 80.0),
+            LSystemPreset::StochasticPlant => (
+                "F",
+                "F -(0.33)-> F[+F]F[-F]F\nF -(0.33)-> F[+F]F\nF -(0.34)-> F[-F]F",
+                5,
+                25.7,
+                90.0,
+            ),
+            LSystemPreset::ParametricTree => (
+                "A(100, 12)",
+                "# Branches raccourcies et amincies jusqu'à 5 unités.\n\
+                 A(l, w) : l >= 5 -> !(w)F(l)[+(35)A(l*0.75, w*0.7)][-(20)A(l*0.8, w*0.7)]",
+                12,
+                30.0,
+                90.0,
+            ),
+        };
+        LSystem { axiom: axiom.to_string(), rules: rules.to_string(), iterations, angle, heading, ..base }
+    }
+}
+
+// Passage des unités de tortue aux pixels, y vers le bas, le dessin centré
+// avec une marge de 5 %.
+struct Frame {
+    scale: f64,
+    center: [f64; 2],
+    size: (usize, usize),
+}
+
+impl Frame {
+    fn fit(segments: &[Segment], (width, height): (usize, usize)) -> Frame {
+        let mut min = [f64::INFINITY; 2];
+        let mut max = [f64::NEG_INFINITY; 2];
+        for p in segments.iter().flat_map(|s| [s.from, s.to]) {
+            min = [min[0].min(p[0]), min[1].min(p[1])];
+            max = [max[0].max(p[0]), max[1].max(p[1])];
+        }
+        if segments.is_empty() {
+            (min, max) = ([-1.0; 2], [1.0; 2]);
+        }
+        let span = [(max[0] - min[0]).max(1e-9), (max[1] - min[1]).max(1e-9)];
+        Frame {
+            scale: (0.9 * width as f64 / span[0]).min(0.9 * height as f64 / span[1]),
+            center: [(min[0] + max[0]) / 2.0, (min[1] + max[1]) / 2.0],
+            size: (width, height),
+        }
+    }
+
+    fn apply(&self, [x, y]: [f64; 2]) -> [f64; 2] {
+        [
+            self.size.0 as f64 / 2.0 + (x - self.center[0]) * self.scale,
+            self.size.1 as f64 / 2.0 - (y - self.center[1]) * self.scale,
+        ]
+    }
+}
+
+// Couleur du i-ème trait, le long du tracé ; le début sombre de beaucoup de
+// palettes disparaîtrait sur le fond noir.
+fn segment_color(scheme: &(dyn ColorScheme + Sync), index: usize, count: usize) -> (u8, u8, u8) {
+    scheme.get_color(0.25 + 0.75 * index as f64 / count.saturating_sub(1).max(1) as f64)
+}
+
+/// Image RGBA des traits, sur fond noir, avec anticrénelage.
+pub fn rasterize(segments: &[Segment], scheme: &(dyn ColorScheme + Sync), (width, height): (usize, usize)) -> Vec<u8> {
+    let mut canvas = vec![[0f32; 3]; width * height];
+    let frame = Frame::fit(segments, (width, height));
+    for (i, segment) in segments.iter().enumerate() {
+        let (r, g, b) = segment_color(scheme, i, segments.len());
+        let color = [r as f32, g as f32, b as f32];
+        let (a, b) = (frame.apply(segment.from), frame.apply(segment.to));
+        // Les traits plus fins qu'un pixel sont rendus par leur opacité.
+        let radius = (segment.width / 2.0).max(0.5);
+        let opacity = segment.width.min(1.0) as f32;
+        let reach = radius + 1.0;
+        let x0 = (a[0].min(b[0]) - reach).floor().max(0.0) as usize;
+        let y0 = (a[1].min(b[1]) - reach).floor().max(0.0) as usize;
+        let x1 = ((a[0].max(b[0]) + reach).ceil().max(0.0) as usize).min(width);
+        let y1 = ((a[1].max(b[1]) + reach).ceil().max(0.0) as usize).min(height);
+        let d = [b[0] - a[0], b[1] - a[1]];
+        let length_sqr = (d[0] * d[0] + d[1] * d[1]).max(1e-12);
+        for y in y0..y1 {
+            for x in x0..x1 {
+                // Distance du centre du pixel au segment.
+                let p = [x as f64 + 0.5 - a[0], y as f64 + 0.5 - a[1]];
+                let t = ((p[0] * d[0] + p[1] * d[1]) / length_sqr).clamp(0.0, 1.0);
+                let distance = (p[0] - t * d[0]).hypot(p[1] - t * d[1]);
+                let coverage = (radius + 0.5 - distance).clamp(0.0, 1.0) as f32 * opacity;
+                if coverage > 0.0 {
+                    let pixel = &mut canvas[y * width + x];
+                    for (channel, value) in pixel.iter_mut().zip(color) {
+                        *channel += (value - *channel) * coverage;
+                    }
+                }
+            }
+        }
+    }
+    canvas.iter().flat_map(|&[r, g, b]| [r as u8, g as u8, b as u8, 255]).collect()
+}
+
+/// Document SVG des traits, cadré comme `rasterize`. Les traits qui se
+/// suivent forment un seul chemin tant que l'épaisseur et la couleur
+/// (quantifiée) ne changent pas.
+pub fn to_svg(segments: &[Segment], scheme: &(dyn ColorScheme + Sync), (width, height): (usize, usize)) -> String {
+    const LEVELS: usize = 64;
+    let frame = Frame::fit(segments, (width, height));
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+        w = width,
+        h = height
+    );
+    svg.push_str("<rect width=\"100%\" height=\"100%\" fill=\"black\"/>\n");
+    svg.push_str("<g fill=\"none\" stroke-linecap=\"round\" stroke-linejoin=\"round\">\n");
+
+    let mut current: Option<(usize, f64, [f64; 2])> = None;
+    for (i, segment) in segments.iter().enumerate() {
+        let level = i * LEVELS / segments.len().max(1);
+        let (a, b) = (frame.apply(segment.from), frame.apply(segment.to));
+        let continues = current.is_some_and(|(l, w, end)| {
+            l == level && w == segment.width && (end[0] - a[0]).abs() < 1e-6 && (end[1] - a[1]).abs() < 1e-6
+        });
+        if !continues {
+            if current.is_some() {
+                svg.push_str("\"/>\n");
+            }
+            let index = (level * segments.len() / LEVELS).min(segments.len() - 1);
+            let (r, g, bl) = segment_color(scheme, index, segments.len());
+            let _ = write!(
+                svg,
+                "<path stroke=\"#{:02x}{:02x}{:02x}\" stroke-width=\"{}\" d=\"M{:.2} {:.2}",
+                r, g, bl, segment.width, a[0], a[1]
+            );
+        }
+        let _ = write!(svg, " L{:.2} {:.2}", b[0], b[1]);
+        current = Some((level, segment.width, b));
+    }
+    if current.is_some() {
+        svg.push_str("\"/>\n");
+    }
+    svg.push_str("</g>\n</svg>\n");
+    svg
+}
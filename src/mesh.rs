@@ -0,0 +1,337 @@
+use crate::color_schemes::ColorScheme;
+use crate::fractal_calculator::IterationData;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MeshFormat {
+    StlAscii,
+    StlBinary,
+    Obj,
+    Ply,
+}
+
+impl MeshFormat {
+    pub const ALL: [MeshFormat; 4] = [MeshFormat::StlBinary, MeshFormat::StlAscii, MeshFormat::Obj, MeshFormat::Ply];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            MeshFormat::StlAscii => "STL (texte)",
+            MeshFormat::StlBinary => "STL (binaire)",
+            MeshFormat::Obj => "OBJ",
+            MeshFormat::Ply => "PLY",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            MeshFormat::StlAscii | MeshFormat::StlBinary => "stl",
+            MeshFormat::Obj => "obj",
+            MeshFormat::Ply => "ply",
+        }
+    }
+}
+
+/// Réglages du relief ; les longueurs sont en millimètres.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MeshSettings {
+    pub format: MeshFormat,
+    /// Longueur du plus grand côté.
+    pub size: f64,
+    /// Hauteur du relief au-dessus du socle.
+    pub height: f64,
+    /// Épaisseur du socle, sous le point le plus bas.
+    pub base: f64,
+    /// Un sommet tous les `step` pixels.
+    pub step: usize,
+    /// Écart de hauteur toléré lors de la fusion des zones presque planes ;
+    /// 0 ne fusionne que les zones exactement planes.
+    pub tolerance: f64,
+    /// Parois et fond fermés, pour l'impression.
+    pub solid: bool,
+    /// Creuse l'ensemble au lieu d'en faire un plateau.
+    pub invert: bool,
+}
+
+impl Default for MeshSettings {
+    fn default() -> Self {
+        Self {
+            format: MeshFormat::StlBinary,
+            size: 100.0,
+            height: 10.0,
+            base: 2.0,
+            step: 2,
+            tolerance: 0.05,
+            solid: true,
+            invert: false,
+        }
+    }
+}
+
+/// Maillage triangulé, orienté vers l'extérieur.
+#[derive(Clone, Debug, Default)]
+pub struct Mesh {
+    pub vertices: Vec<[f32; 3]>,
+    pub colors: Vec<[u8; 3]>,
+    pub triangles: Vec<[u32; 3]>,
+}
+
+impl Mesh {
+    fn add_vertex(&mut self, position: [f64; 3], color: [u8; 3]) -> u32 {
+        self.vertices.push(position.map(|v| v as f32));
+        self.colors.push(color);
+        (self.vertices.len() - 1) as u32
+    }
+
+    fn normal(&self, [a, b, c]: [u32; 3]) -> [f32; 3] {
+        let [a, b, c] = [a, b, c].map(|i| self.vertices[i as usize]);
+        let (u, v) = ([0, 1, 2].map(|k| b[k] - a[k]), [0, 1, 2].map(|k| c[k] - a[k]));
+        let n = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+        let length = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+        if length > 0.0 {
+            n.map(|v| v / length)
+        } else {
+            n
+        }
+    }
+
+    pub fn save(&self, path: &Path, format: MeshFormat) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        match format {
+            MeshFormat::StlAscii => self.write_stl_ascii(&mut out)?,
+            MeshFormat::StlBinary => self.write_stl_binary(&mut out)?,
+            MeshFormat::Obj => self.write_obj(&mut out)?,
+            MeshFormat::Ply => self.write_ply(&mut out)?,
+        }
+        out.flush()
+    }
+
+    // Le STL n'a pas de couleurs.
+    fn write_stl_ascii(&self, out: &mut impl Write) -> io::Result<()> {
+        writeln!(out, "solid fractale")?;
+        for &triangle in &self.triangles {
+            let n = self.normal(triangle);
+            writeln!(out, "  facet normal {:e} {:e} {:e}", n[0], n[1], n[2])?;
+            writeln!(out, "    outer loop")?;
+            for index in triangle {
+                let v = self.vertices[index as usize];
+                writeln!(out, "      vertex {:e} {:e} {:e}", v[0], v[1], v[2])?;
+            }
+            writeln!(out, "    endloop")?;
+            writeln!(out, "  endfacet")?;
+        }
+        writeln!(out, "endsolid fractale")
+    }
+
+    fn write_stl_binary(&self, out: &mut impl Write) -> io::Result<()> {
+        let mut header = [0u8; 80];
+        let title = b"Relief fractal";
+        header[..title.len()].copy_from_slice(title);
+        out.write_all(&header)?;
+        out.write_all(&(self.triangles.len() as u32).to_le_bytes())?;
+        for &triangle in &self.triangles {
+            let normal = self.normal(triangle);
+            let corners = triangle.map(|i| self.vertices[i as usize]);
+            for v in std::iter::once(normal).chain(corners) {
+                for coordinate in v {
+                    out.write_all(&coordinate.to_le_bytes())?;
+                }
+            }
+            out.write_all(&0u16.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    // Couleurs par sommet en extension « v x y z r g b », lue par la
+    // plupart des logiciels.
+    fn write_obj(&self, out: &mut impl Write) -> io::Result<()> {
+        writeln!(out, "# Relief fractal : {} sommets, {} triangles", self.vertices.len(), self.triangles.len())?;
+        for (v, c) in self.vertices.iter().zip(&self.colors) {
+            let [r, g, b] = c.map(|c| c as f32 / 255.0);
+            writeln!(out, "v {} {} {} {:.4} {:.4} {:.4}", v[0], v[1], v[2], r, g, b)?;
+        }
+        for t in &self.triangles {
+            writeln!(out, "f {} {} {}", t[0] + 1, t[1] + 1, t[2] + 1)?;
+        }
+        Ok(())
+    }
+
+    fn write_ply(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(
+            out,
+            "ply\nformat binary_little_endian 1.0\ncomment Relief fractal\n\
+             element vertex {}\nproperty float x\nproperty float y\nproperty float z\n\
+             property uchar red\nproperty uchar green\nproperty uchar blue\n\
+             element face {}\nproperty list uchar int vertex_indices\nend_header\n",
+            self.vertices.len(),
+            self.triangles.len()
+        )?;
+        for (v, c) in self.vertices.iter().zip(&self.colors) {
+            for coordinate in v {
+                out.write_all(&coordinate.to_le_bytes())?;
+            }
+            out.write_all(c)?;
+        }
+        for t in &self.triangles {
+            out.write_all(&[3])?;
+            for index in t {
+                out.write_all(&(*index as i32).to_le_bytes())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Grille des hauteurs échantillonnées, en millimètres.
+struct HeightGrid {
+    width: usize,
+    height: usize,
+    z: Vec<f64>,
+    colors: Vec<[u8; 3]>,
+}
+
+impl HeightGrid {
+    #[inline]
+    fn z(&self, x: usize, y: usize) -> f64 {
+        self.z[y * self.width + x]
+    }
+
+    /// Écart maximal entre les hauteurs de la cellule et le carreau
+    /// bilinéaire de ses coins.
+    fn deviation(&self, x: usize, y: usize, size: usize) -> f64 {
+        let [z00, z10, z01, z11] = [self.z(x, y), self.z(x + size, y), self.z(x, y + size), self.z(x + size, y + size)];
+        let mut deviation: f64 = 0.0;
+        for j in 0..=size {
+            let v = j as f64 / size as f64;
+            for i in 0..=size {
+                let u = i as f64 / size as f64;
+                let bilinear = (z00 * (1.0 - u) + z10 * u) * (1.0 - v) + (z01 * (1.0 - u) + z11 * u) * v;
+                deviation = deviation.max((self.z(x + i, y + j) - bilinear).abs());
+            }
+        }
+        deviation
+    }
+
+    /// Cellules carrées (x, y, côté) qui pavent la grille : un quadtree
+    /// subdivise celles qui s'écartent trop d'un carreau plan.
+    fn leaves(&self, tolerance: f64) -> Vec<(usize, usize, usize)> {
+        let (last_x, last_y) = (self.width - 1, self.height - 1);
+        let mut leaves = Vec::new();
+        let mut stack = vec![(0, 0, last_x.max(last_y).next_power_of_two())];
+        while let Some((x, y, size)) = stack.pop() {
+            if x >= last_x || y >= last_y {
+                continue;
+            }
+            let inside = x + size <= last_x && y + size <= last_y;
+            if size == 1 || (inside && self.deviation(x, y, size) <= tolerance + 1e-9) {
+                leaves.push((x, y, size));
+            } else {
+                let half = size / 2;
+                stack.extend([(x, y, half), (x + half, y, half), (x, y + half, half), (x + half, y + half, half)]);
+            }
+        }
+        leaves
+    }
+}
+
+/// Points utilisés du bord de la cellule, dans le sens trigonométrique
+/// vu du dessus (les lignes de l'image descendent, l'axe y du relief monte).
+fn perimeter(used: &[bool], width: usize, x: usize, y: usize, size_x: usize, size_y: usize) -> Vec<(usize, usize)> {
+    let mut points = Vec::new();
+    let mut visit = |px: usize, py: usize| {
+        if used[py * width + px] {
+            points.push((px, py));
+        }
+    };
+    (y..y + size_y).for_each(|py| visit(x, py));
+    (x..x + size_x).for_each(|px| visit(px, y + size_y));
+    (y + 1..=y + size_y).rev().for_each(|py| visit(x + size_x, py));
+    (x + 1..=x + size_x).rev().for_each(|px| visit(px, y));
+    points
+}
+
+/// Relief des valeurs lissées de `data`, coloré par `scheme` comme l'image
+/// affichée. Les points de l'ensemble forment un plateau au sommet (ou un
+/// creux si `invert`).
+pub fn heightmap_mesh(data: &IterationData, scheme: &(dyn ColorScheme + Sync), offset: f64, settings: &MeshSettings) -> Mesh {
+    let (width, height) = (data.size.0.max(2), data.size.1.max(2));
+    let step = settings.step.max(1);
+    let pixels = data.colorize(scheme, offset);
+    let max_iterations = data.max_iterations.max(1) as f64;
+    let (grid_width, grid_height) = ((width - 1) / step + 1, (height - 1) / step + 1);
+    let mut grid = HeightGrid { width: grid_width, height: grid_height, z: Vec::new(), colors: Vec::new() };
+    for gy in 0..grid_height {
+        for gx in 0..grid_width {
+            let index = (gy * step).min(data.size.1.saturating_sub(1)) * data.size.0 + (gx * step).min(data.size.0.saturating_sub(1));
+            let value = data.smooth.get(index).copied().unwrap_or(f64::NAN);
+            let t = if value.is_nan() { 1.0 } else { (value / max_iterations).clamp(0.0, 1.0) };
+            let t = if settings.invert { 1.0 - t } else { t };
+            grid.z.push(settings.base + t * settings.height);
+            let color = pixels.get(index * 4..index * 4 + 3).map_or([0; 3], |c| [c[0], c[1], c[2]]);
+            grid.colors.push(color);
+        }
+    }
+    if grid_width < 2 || grid_height < 2 {
+        return Mesh::default();
+    }
+
+    let spacing = settings.size / (width.max(height) - 1) as f64 * step as f64;
+    let position = |x: usize, y: usize, z: f64| [x as f64 * spacing, (grid_height - 1 - y) as f64 * spacing, z];
+    let leaves = grid.leaves(settings.tolerance.max(0.0));
+    let mut used = vec![false; grid_width * grid_height];
+    for &(x, y, size) in &leaves {
+        for (px, py) in [(x, y), (x + size, y), (x, y + size), (x + size, y + size)] {
+            used[py * grid_width + px] = true;
+        }
+    }
+
+    let mut mesh = Mesh::default();
+    let mut indices = vec![u32::MAX; grid_width * grid_height];
+    let mut top = |mesh: &mut Mesh, (x, y): (usize, usize)| {
+        let i = y * grid_width + x;
+        if indices[i] == u32::MAX {
+            indices[i] = mesh.add_vertex(position(x, y, grid.z[i]), grid.colors[i]);
+        }
+        indices[i]
+    };
+    for &(x, y, size) in &leaves {
+        let points = perimeter(&used, grid_width, x, y, size, size);
+        let corners: Vec<u32> = points.iter().map(|&p| top(&mut mesh, p)).collect();
+        if corners.len() == 4 {
+            mesh.triangles.push([corners[0], corners[1], corners[2]]);
+            mesh.triangles.push([corners[0], corners[2], corners[3]]);
+        } else {
+            // Des voisins plus fins partagent le bord : éventail depuis le
+            // centre, sans sommet en T.
+            let center = top(&mut mesh, (x + size / 2, y + size / 2));
+            for k in 0..corners.len() {
+                mesh.triangles.push([center, corners[k], corners[(k + 1) % corners.len()]]);
+            }
+        }
+    }
+
+    if settings.solid {
+        // Parois verticales le long du bord, puis fond en éventail.
+        let rim = perimeter(&used, grid_width, 0, 0, grid_width - 1, grid_height - 1);
+        let tops: Vec<u32> = rim.iter().map(|&p| top(&mut mesh, p)).collect();
+        let bottoms: Vec<u32> = rim
+            .iter()
+            .map(|&(x, y)| mesh.add_vertex(position(x, y, 0.0), grid.colors[y * grid_width + x]))
+            .collect();
+        let n = rim.len();
+        for k in 0..n {
+            let (p, q) = (k, (k + 1) % n);
+            mesh.triangles.push([tops[p], bottoms[p], bottoms[q]]);
+            mesh.triangles.push([tops[p], bottoms[q], tops[q]]);
+        }
+        let center = mesh.add_vertex(
+            [(grid_width - 1) as f64 * spacing / 2.0, (grid_height - 1) as f64 * spacing / 2.0, 0.0],
+            [0; 3],
+        );
+        for k in 0..n {
+            mesh.triangles.push([center, bottoms[(k + 1) % n], bottoms[k]]);
+        }
+    }
+    mesh
+}
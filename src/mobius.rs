@@ -0,0 +1,108 @@
+use num::Complex;
+use serde::{Deserialize, Serialize};
+use std::ops::Mul;
+
+/// Transformation de Möbius z ↦ (a·z + b) / (c·z + d), représentée par sa
+/// matrice. Le point à l'infini est un complexe de partie réelle infinie.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Mobius {
+    pub a: Complex<f64>,
+    pub b: Complex<f64>,
+    pub c: Complex<f64>,
+    pub d: Complex<f64>,
+}
+
+pub const INFINITY: Complex<f64> = Complex::new(f64::INFINITY, 0.0);
+
+#[inline]
+pub fn is_infinite(z: Complex<f64>) -> bool {
+    z.re.is_infinite() || z.im.is_infinite()
+}
+
+impl Mobius {
+    pub fn new(a: Complex<f64>, b: Complex<f64>, c: Complex<f64>, d: Complex<f64>) -> Self {
+        Self { a, b, c, d }
+    }
+
+    pub fn determinant(&self) -> Complex<f64> {
+        self.a * self.d - self.b * self.c
+    }
+
+    pub fn trace(&self) -> Complex<f64> {
+        self.a + self.d
+    }
+
+    /// Même transformation, de déterminant 1 ; la trace devient alors un
+    /// invariant de conjugaison.
+    pub fn normalized(&self) -> Self {
+        let s = self.determinant().sqrt();
+        if s == Complex::new(0.0, 0.0) {
+            return *self;
+        }
+        Self::new(self.a / s, self.b / s, self.c / s, self.d / s)
+    }
+
+    /// Inverse, au facteur déterminant près (sans effet sur la transformation).
+    pub fn inverse(&self) -> Self {
+        Self::new(self.d, -self.b, -self.c, self.a)
+    }
+
+    #[inline]
+    pub fn apply(&self, z: Complex<f64>) -> Complex<f64> {
+        if is_infinite(z) {
+            return if self.c == Complex::new(0.0, 0.0) { INFINITY } else { self.a / self.c };
+        }
+        let denominator = self.c * z + self.d;
+        if denominator == Complex::new(0.0, 0.0) {
+            INFINITY
+        } else {
+            (self.a * z + self.b) / denominator
+        }
+    }
+
+    /// Les deux points fixes, éventuellement confondus (transformation
+    /// parabolique) ou à l'infini.
+    pub fn fixed_points(&self) -> [Complex<f64>; 2] {
+        let m = self.normalized();
+        let zero = Complex::new(0.0, 0.0);
+        if m.c == zero {
+            // z ↦ (a·z + b)/d : l'infini, et un point fini sauf pour une translation.
+            return if m.a == m.d { [INFINITY; 2] } else { [m.b / (m.d - m.a), INFINITY] };
+        }
+        let root = ((m.a - m.d) * (m.a - m.d) + 4.0 * m.b * m.c).sqrt();
+        [(m.a - m.d + root) / (2.0 * m.c), (m.a - m.d - root) / (2.0 * m.c)]
+    }
+
+    /// Point fixe attractif : celui où |T'(z)| = 1/|c·z + d|² est le plus petit.
+    pub fn attracting_fixed_point(&self) -> Complex<f64> {
+        let m = self.normalized();
+        let [p, q] = m.fixed_points();
+        let derivative = |z: Complex<f64>| {
+            if is_infinite(z) {
+                // En l'infini, T se comporte comme z ↦ (a/d)·z.
+                (m.d / m.a).norm()
+            } else {
+                1.0 / (m.c * z + m.d).norm_sqr()
+            }
+        };
+        if derivative(p) <= derivative(q) {
+            p
+        } else {
+            q
+        }
+    }
+}
+
+/// Composition : (s * t)(z) = s(t(z)).
+impl Mul for Mobius {
+    type Output = Mobius;
+
+    fn mul(self, t: Mobius) -> Mobius {
+        Mobius::new(
+            self.a * t.a + self.b * t.c,
+            self.a * t.b + self.b * t.d,
+            self.c * t.a + self.d * t.c,
+            self.c * t.b + self.d * t.d,
+        )
+    }
+}
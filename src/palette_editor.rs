@@ -0,0 +1,501 @@
+use crate::gradient::{ColorStop, GradientScheme, Interpolation};
+use crate::palette_extract::{extract_palette_from_file, ClusteringMethod, ExtractionOptions, PathOrdering};
+use crate::palette_io::{load_palettes, save_palette, PaletteFormat, PaletteLibrary};
+use eframe::egui;
+use std::path::PathBuf;
+
+const UNDO_LIMIT: usize = 100;
+const HANDLE_GRAB_DISTANCE: f32 = 8.0;
+
+/// Interaction en cours : ses modifications successives ne forment qu'une
+/// étape d'annulation, jusqu'à ce qu'elle se termine.
+#[derive(Clone, Copy, PartialEq)]
+enum EditKind {
+    Color,
+    Position,
+    Name,
+}
+
+pub struct PaletteEditor {
+    pub open: bool,
+    pub gradient: GradientScheme,
+    selected: Option<usize>,
+    dragging: Option<usize>,
+    undo_stack: Vec<GradientScheme>,
+    redo_stack: Vec<GradientScheme>,
+    last_edit: Option<EditKind>,
+    library: PaletteLibrary,
+    library_entries: Vec<PathBuf>,
+    extraction: ExtractionOptions,
+    status: Option<String>,
+}
+
+impl Default for PaletteEditor {
+    fn default() -> Self {
+        let library = PaletteLibrary::default();
+        let library_entries = library.list();
+        Self {
+            open: false,
+            gradient: GradientScheme::new(
+                "Nouvelle palette",
+                vec![
+                    ColorStop { position: 0.0, color: (0, 7, 100) },
+                    ColorStop { position: 0.5, color: (255, 255, 255) },
+                    ColorStop { position: 1.0, color: (255, 170, 0) },
+                ],
+                Interpolation::Linear,
+            ),
+            selected: None,
+            dragging: None,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            last_edit: None,
+            library,
+            library_entries,
+            extraction: ExtractionOptions::default(),
+            status: None,
+        }
+    }
+}
+
+impl PaletteEditor {
+    pub fn open_with(&mut self, gradient: GradientScheme) {
+        if gradient != self.gradient {
+            self.checkpoint(None);
+            self.gradient = gradient;
+        }
+        self.selected = None;
+        self.open = true;
+    }
+
+    /// Affiche le panneau ; renvoie `true` si la palette a été modifiée.
+    pub fn show(&mut self, ctx: &egui::Context) -> bool {
+        let mut changed = false;
+        if !self.open {
+            return changed;
+        }
+
+        egui::SidePanel::right("palette_editor")
+            .resizable(true)
+            .default_width(280.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.heading("Éditeur de palette");
+                    if ui.small_button("✕").clicked() {
+                        self.open = false;
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Nom :");
+                    let mut name = self.gradient.name.clone();
+                    let response = ui.text_edit_singleline(&mut name);
+                    if response.changed() {
+                        self.checkpoint(Some(EditKind::Name));
+                        self.gradient.name = name;
+                    }
+                    if response.lost_focus() {
+                        self.end_edit(EditKind::Name);
+                    }
+                });
+
+                changed |= self.gradient_bar(ui);
+                ui.label("Double-clic sur le dégradé : ajouter un arrêt. Clic droit sur un arrêt : le supprimer.");
+                ui.separator();
+
+                changed |= self.selected_stop_controls(ui);
+
+                ui.horizontal(|ui| {
+                    ui.label("Interpolation :");
+                    let mut interpolation = self.gradient.interpolation;
+                    egui::ComboBox::from_id_source("palette_interpolation")
+                        .selected_text(interpolation_label(interpolation))
+                        .show_ui(ui, |ui| {
+                            for mode in [Interpolation::Linear, Interpolation::Smooth, Interpolation::OkLab, Interpolation::Step] {
+                                ui.selectable_value(&mut interpolation, mode, interpolation_label(mode));
+                            }
+                        });
+                    if interpolation != self.gradient.interpolation {
+                        self.checkpoint(None);
+                        self.gradient.interpolation = interpolation;
+                        changed = true;
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    let (undo, redo) = ui.input(|i| {
+                        let z = i.modifiers.command && i.key_pressed(egui::Key::Z);
+                        (z && !i.modifiers.shift, z && i.modifiers.shift)
+                    });
+                    if ui.add_enabled(!self.undo_stack.is_empty(), egui::Button::new("Annuler")).clicked() || undo {
+                        changed |= self.undo();
+                    }
+                    if ui.add_enabled(!self.redo_stack.is_empty(), egui::Button::new("Rétablir")).clicked() || redo {
+                        changed |= self.redo();
+                    }
+                });
+
+                ui.separator();
+                changed |= self.library_controls(ui);
+
+                if let Some(status) = &self.status {
+                    ui.separator();
+                    ui.label(status);
+                }
+            });
+
+        changed
+    }
+
+    fn gradient_bar(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+        let width = ui.available_width().max(64.0);
+        let (bar_rect, bar_response) = ui.allocate_exact_size(egui::vec2(width, 36.0), egui::Sense::click());
+        let (handle_rect, handle_response) =
+            ui.allocate_exact_size(egui::vec2(width, 16.0), egui::Sense::click_and_drag());
+
+        let mut mesh = egui::Mesh::default();
+        let columns = bar_rect.width().ceil() as usize;
+        for x in 0..columns {
+            let t = x as f64 / (columns.max(2) - 1) as f64;
+            let (r, g, b) = self.gradient.sample(t);
+            let column = egui::Rect::from_min_size(
+                egui::pos2(bar_rect.left() + x as f32, bar_rect.top()),
+                egui::vec2(1.0, bar_rect.height()),
+            );
+            mesh.add_colored_rect(column, egui::Color32::from_rgb(r, g, b));
+        }
+        ui.painter().add(egui::Shape::mesh(mesh));
+        ui.painter().rect_stroke(bar_rect, 0.0, ui.visuals().widgets.noninteractive.bg_stroke);
+
+        let to_position = |x: f32| ((x - bar_rect.left()) / bar_rect.width()).clamp(0.0, 1.0) as f64;
+        let to_x = |position: f64| bar_rect.left() + position as f32 * bar_rect.width();
+
+        if bar_response.double_clicked() {
+            if let Some(pointer) = bar_response.interact_pointer_pos() {
+                let position = to_position(pointer.x);
+                self.checkpoint(None);
+                let stop = ColorStop { position, color: self.gradient.sample(position) };
+                let index = self.gradient.stops.partition_point(|s| s.position <= position);
+                self.gradient.stops.insert(index, stop);
+                self.selected = Some(index);
+                changed = true;
+            }
+        }
+
+        let handles: Vec<f32> = self.gradient.stops.iter().map(|stop| to_x(stop.position)).collect();
+        let nearest_stop = |pointer: egui::Pos2| {
+            handles
+                .iter()
+                .enumerate()
+                .map(|(i, x)| (i, (x - pointer.x).abs()))
+                .filter(|(_, distance)| *distance <= HANDLE_GRAB_DISTANCE)
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(i, _)| i)
+        };
+
+        if handle_response.drag_started() || handle_response.clicked() {
+            if let Some(pointer) = handle_response.interact_pointer_pos() {
+                self.selected = nearest_stop(pointer);
+                if handle_response.drag_started() && self.selected.is_some() {
+                    self.checkpoint(None);
+                    self.dragging = self.selected;
+                }
+            }
+        }
+        if handle_response.dragged() {
+            if let (Some(index), Some(pointer)) = (self.dragging, handle_response.interact_pointer_pos()) {
+                let index = self.move_stop(index, to_position(pointer.x));
+                self.dragging = Some(index);
+                self.selected = Some(index);
+                changed = true;
+            }
+        }
+        if handle_response.drag_released() {
+            self.dragging = None;
+        }
+        if handle_response.secondary_clicked() {
+            if let Some(index) = handle_response.interact_pointer_pos().and_then(nearest_stop) {
+                changed |= self.remove_stop(index);
+            }
+        }
+
+        let painter = ui.painter();
+        for (i, stop) in self.gradient.stops.iter().enumerate() {
+            let x = to_x(stop.position);
+            let (r, g, b) = stop.color;
+            let outline = if Some(i) == self.selected {
+                ui.visuals().selection.stroke
+            } else {
+                egui::Stroke::new(1.0, egui::Color32::GRAY)
+            };
+            let top = handle_rect.top();
+            painter.add(egui::Shape::convex_polygon(
+                vec![
+                    egui::pos2(x, top),
+                    egui::pos2(x + 6.0, top + 10.0),
+                    egui::pos2(x + 6.0, top + 15.0),
+                    egui::pos2(x - 6.0, top + 15.0),
+                    egui::pos2(x - 6.0, top + 10.0),
+                ],
+                egui::Color32::from_rgb(r, g, b),
+                outline,
+            ));
+        }
+
+        if self.selected.is_some() && ui.input(|i| i.key_pressed(egui::Key::Delete)) && !ui.ctx().wants_keyboard_input() {
+            if let Some(index) = self.selected {
+                changed |= self.remove_stop(index);
+            }
+        }
+
+        changed
+    }
+
+    fn selected_stop_controls(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+        let index = match self.selected {
+            Some(index) if index < self.gradient.stops.len() => index,
+            _ => {
+                ui.label("Aucun arrêt sélectionné.");
+                return changed;
+            }
+        };
+
+        ui.horizontal(|ui| {
+            ui.label("Position :");
+            let mut position = self.gradient.stops[index].position;
+            let response = ui.add(egui::DragValue::new(&mut position).clamp_range(0.0..=1.0).speed(0.005));
+            if response.changed() {
+                self.checkpoint(Some(EditKind::Position));
+                let index = self.move_stop(index, position);
+                self.selected = Some(index);
+                changed = true;
+            }
+            if response.drag_released() || response.lost_focus() {
+                self.end_edit(EditKind::Position);
+            }
+        });
+
+        let index = self.selected.unwrap_or(index);
+        ui.horizontal(|ui| {
+            ui.label("Couleur :");
+            let (r, g, b) = self.gradient.stops[index].color;
+            let mut rgb = [r, g, b];
+            if egui::color_picker::color_edit_button_srgb(ui, &mut rgb).changed() {
+                self.checkpoint(Some(EditKind::Color));
+                self.gradient.stops[index].color = (rgb[0], rgb[1], rgb[2]);
+                changed = true;
+            }
+            // La session du sélecteur dure tant que sa fenêtre est ouverte.
+            if !ui.memory(|mem| mem.any_popup_open()) {
+                self.end_edit(EditKind::Color);
+            }
+            if ui.button("Supprimer l'arrêt").clicked() {
+                changed |= self.remove_stop(index);
+            }
+        });
+
+        changed
+    }
+
+    fn library_controls(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+        ui.label("Bibliothèque :");
+
+        let mut to_load = None;
+        let mut to_remove = None;
+        egui::ScrollArea::vertical().max_height(160.0).show(ui, |ui| {
+            if self.library_entries.is_empty() {
+                ui.label("(vide)");
+            }
+            for path in &self.library_entries {
+                let name = path.file_stem().and_then(|s| s.to_str()).unwrap_or("?");
+                ui.horizontal(|ui| {
+                    if ui.button(name).clicked() {
+                        to_load = Some(path.clone());
+                    }
+                    if ui.small_button("🗑").on_hover_text("Supprimer de la bibliothèque").clicked() {
+                        to_remove = Some(path.clone());
+                    }
+                });
+            }
+        });
+
+        if let Some(path) = to_load {
+            changed |= self.load_from(&path);
+        }
+        if let Some(path) = to_remove {
+            self.status = Some(match self.library.remove(&path) {
+                Ok(()) => format!("Supprimée : {}", path.display()),
+                Err(err) => format!("Échec de la suppression : {}", err),
+            });
+            self.library_entries = self.library.list();
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button("Enregistrer dans la bibliothèque").clicked() {
+                self.status = Some(match self.library.save(&self.gradient) {
+                    Ok(path) => format!("Enregistrée : {}", path.display()),
+                    Err(err) => format!("Échec de l'enregistrement : {}", err),
+                });
+                self.library_entries = self.library.list();
+            }
+            if ui.button("↻").on_hover_text("Relire le dossier").clicked() {
+                self.library_entries = self.library.list();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            if ui.button("Importer…").clicked() {
+                let mut dialog = rfd::FileDialog::new();
+                for format in PaletteFormat::ALL {
+                    dialog = dialog.add_filter(format.label(), &[format.extension()]);
+                }
+                if let Some(path) = dialog.pick_file() {
+                    changed |= self.load_from(&path);
+                }
+            }
+            if ui.button("Exporter…").clicked() {
+                let mut dialog = rfd::FileDialog::new().set_file_name(format!("{}.json", self.gradient.name));
+                for format in PaletteFormat::ALL {
+                    dialog = dialog.add_filter(format.label(), &[format.extension()]);
+                }
+                if let Some(path) = dialog.save_file() {
+                    self.status = Some(match save_palette(&path, &self.gradient) {
+                        Ok(()) => format!("Exportée : {}", path.display()),
+                        Err(err) => format!("Échec de l'export : {}", err),
+                    });
+                }
+            }
+        });
+
+        ui.horizontal(|ui| {
+            if ui.button("Extraire d'une image…").clicked() {
+                let dialog = rfd::FileDialog::new().add_filter("Image", &["png", "jpg", "jpeg"]);
+                if let Some(path) = dialog.pick_file() {
+                    match extract_palette_from_file(&path, &self.extraction) {
+                        Ok(gradient) => {
+                            self.checkpoint(None);
+                            self.gradient = gradient;
+                            self.selected = None;
+                            self.status = Some(format!("Palette extraite de {}", path.display()));
+                            changed = true;
+                        }
+                        Err(err) => self.status = Some(format!("{} : {}", path.display(), err)),
+                    }
+                }
+            }
+            ui.add(egui::DragValue::new(&mut self.extraction.colors).clamp_range(2..=32).suffix(" couleurs"));
+        });
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source("extraction_method")
+                .selected_text(self.extraction.method.label())
+                .show_ui(ui, |ui| {
+                    for method in ClusteringMethod::ALL {
+                        ui.selectable_value(&mut self.extraction.method, method, method.label());
+                    }
+                });
+            egui::ComboBox::from_id_source("extraction_ordering")
+                .selected_text(self.extraction.ordering.label())
+                .show_ui(ui, |ui| {
+                    for ordering in PathOrdering::ALL {
+                        ui.selectable_value(&mut self.extraction.ordering, ordering, ordering.label());
+                    }
+                });
+        });
+
+        changed
+    }
+
+    fn load_from(&mut self, path: &std::path::Path) -> bool {
+        match load_palettes(path) {
+            Ok(mut gradients) if !gradients.is_empty() => {
+                self.checkpoint(None);
+                self.gradient = gradients.remove(0);
+                self.selected = None;
+                self.status = Some(format!("Chargée : {}", path.display()));
+                true
+            }
+            Ok(_) => false,
+            Err(err) => {
+                self.status = Some(format!("{} : {}", path.display(), err));
+                false
+            }
+        }
+    }
+
+    /// Déplace un arrêt en gardant la liste triée ; renvoie son nouvel index.
+    fn move_stop(&mut self, index: usize, position: f64) -> usize {
+        let mut stop = self.gradient.stops.remove(index);
+        stop.position = position;
+        let new_index = self.gradient.stops.partition_point(|s| s.position <= position);
+        self.gradient.stops.insert(new_index, stop);
+        new_index
+    }
+
+    fn remove_stop(&mut self, index: usize) -> bool {
+        // Un dégradé garde toujours au moins deux arrêts.
+        if self.gradient.stops.len() <= 2 || index >= self.gradient.stops.len() {
+            return false;
+        }
+        self.checkpoint(None);
+        self.gradient.stops.remove(index);
+        self.selected = None;
+        self.dragging = None;
+        true
+    }
+
+    /// Sauvegarde l'état courant pour l'annulation. Les modifications
+    /// d'une même interaction (glisser une couleur) n'en créent qu'une.
+    fn checkpoint(&mut self, kind: Option<EditKind>) {
+        if kind.is_some() && kind == self.last_edit {
+            return;
+        }
+        self.last_edit = kind;
+        self.undo_stack.push(self.gradient.clone());
+        if self.undo_stack.len() > UNDO_LIMIT {
+            self.undo_stack.remove(0);
+        }
+        self.redo_stack.clear();
+    }
+
+    fn end_edit(&mut self, kind: EditKind) {
+        if self.last_edit == Some(kind) {
+            self.last_edit = None;
+        }
+    }
+
+    fn undo(&mut self) -> bool {
+        match self.undo_stack.pop() {
+            Some(previous) => {
+                self.redo_stack.push(std::mem::replace(&mut self.gradient, previous));
+                self.selected = None;
+                self.last_edit = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn redo(&mut self) -> bool {
+        match self.redo_stack.pop() {
+            Some(next) => {
+                self.undo_stack.push(std::mem::replace(&mut self.gradient, next));
+                self.selected = None;
+                self.last_edit = None;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+fn interpolation_label(interpolation: Interpolation) -> &'static str {
+    match interpolation {
+        Interpolation::Linear => "Linéaire",
+        Interpolation::Smooth => "Douce",
+        Interpolation::OkLab => "OKLab",
+        Interpolation::Step => "Paliers",
+    }
+}
@@ -0,0 +1,293 @@
+use crate::gradient::{oklab_to_rgb, rgb_to_oklab, ColorStop, GradientScheme, Interpolation};
+use image::RgbaImage;
+use std::path::Path;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClusteringMethod {
+    MedianCut,
+    KMeans,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PathOrdering {
+    Lightness,
+    Hue,
+    NearestNeighbor,
+    /// Plus proche voisin amélioré par 2-opt (heuristique du voyageur de commerce).
+    TwoOpt,
+}
+
+impl ClusteringMethod {
+    pub const ALL: [ClusteringMethod; 2] = [ClusteringMethod::MedianCut, ClusteringMethod::KMeans];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ClusteringMethod::MedianCut => "Coupe médiane",
+            ClusteringMethod::KMeans => "k-moyennes",
+        }
+    }
+}
+
+impl PathOrdering {
+    pub const ALL: [PathOrdering; 4] =
+        [PathOrdering::Lightness, PathOrdering::Hue, PathOrdering::NearestNeighbor, PathOrdering::TwoOpt];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PathOrdering::Lightness => "Luminosité",
+            PathOrdering::Hue => "Teinte",
+            PathOrdering::NearestNeighbor => "Plus proche voisin",
+            PathOrdering::TwoOpt => "Plus proche voisin + 2-opt",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ExtractionOptions {
+    pub colors: usize,
+    pub method: ClusteringMethod,
+    pub ordering: PathOrdering,
+    pub max_samples: usize,
+    pub kmeans_iterations: usize,
+}
+
+impl Default for ExtractionOptions {
+    fn default() -> Self {
+        Self {
+            colors: 8,
+            method: ClusteringMethod::KMeans,
+            ordering: PathOrdering::TwoOpt,
+            max_samples: 20_000,
+            kmeans_iterations: 20,
+        }
+    }
+}
+
+type Lab = [f64; 3];
+
+pub fn extract_palette_from_file(path: &Path, options: &ExtractionOptions) -> Result<GradientScheme, image::ImageError> {
+    let image = image::open(path)?.to_rgba8();
+    let name = path.file_stem().and_then(|s| s.to_str()).unwrap_or("image");
+    Ok(extract_palette(name, &image, options))
+}
+
+/// Construit un dégradé à partir des couleurs dominantes d'une image,
+/// regroupées dans l'espace OKLab puis ordonnées en un chemin continu.
+pub fn extract_palette(name: &str, image: &RgbaImage, options: &ExtractionOptions) -> GradientScheme {
+    let samples = sample_pixels(image, options.max_samples.max(1));
+    if samples.is_empty() {
+        return GradientScheme::new(
+            name,
+            vec![
+                ColorStop { position: 0.0, color: (0, 0, 0) },
+                ColorStop { position: 1.0, color: (255, 255, 255) },
+            ],
+            Interpolation::OkLab,
+        );
+    }
+
+    let k = options.colors.clamp(2, 256);
+    let mut centers = median_cut(&samples, k);
+    if options.method == ClusteringMethod::KMeans {
+        centers = kmeans(&samples, centers, options.kmeans_iterations);
+    }
+    let ordered = order_colors(centers, options.ordering);
+
+    let last = (ordered.len().max(2) - 1) as f64;
+    let mut stops: Vec<ColorStop> = ordered
+        .iter()
+        .enumerate()
+        .map(|(i, lab)| ColorStop { position: i as f64 / last, color: oklab_to_rgb(*lab) })
+        .collect();
+    if stops.len() == 1 {
+        stops.push(ColorStop { position: 1.0, color: stops[0].color });
+    }
+    GradientScheme::new(name, stops, Interpolation::OkLab)
+}
+
+// Sous-échantillonnage régulier ; les pixels transparents sont ignorés.
+fn sample_pixels(image: &RgbaImage, max_samples: usize) -> Vec<Lab> {
+    let total = image.width() as usize * image.height() as usize;
+    let stride = total.div_ceil(max_samples).max(1);
+    image
+        .pixels()
+        .step_by(stride)
+        .filter(|pixel| pixel.0[3] >= 128)
+        .map(|pixel| rgb_to_oklab((pixel.0[0], pixel.0[1], pixel.0[2])))
+        .collect()
+}
+
+fn mean(points: &[Lab]) -> Lab {
+    let mut sum = [0.0; 3];
+    for p in points {
+        for axis in 0..3 {
+            sum[axis] += p[axis];
+        }
+    }
+    let n = points.len().max(1) as f64;
+    [sum[0] / n, sum[1] / n, sum[2] / n]
+}
+
+fn distance_sqr(a: &Lab, b: &Lab) -> f64 {
+    (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)
+}
+
+fn median_cut(samples: &[Lab], k: usize) -> Vec<Lab> {
+    let mut boxes: Vec<Vec<Lab>> = vec![samples.to_vec()];
+
+    while boxes.len() < k {
+        // Boîte dont l'étendue sur un axe est la plus grande.
+        let widest = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, points)| points.len() >= 2)
+            .map(|(i, points)| {
+                let (axis, range) = (0..3)
+                    .map(|axis| {
+                        let (min, max) = points.iter().fold((f64::MAX, f64::MIN), |(min, max), p| {
+                            (min.min(p[axis]), max.max(p[axis]))
+                        });
+                        (axis, max - min)
+                    })
+                    .max_by(|a, b| a.1.total_cmp(&b.1))
+                    .unwrap_or((0, 0.0));
+                (i, axis, range)
+            })
+            .max_by(|a, b| a.2.total_cmp(&b.2));
+
+        let (index, axis) = match widest {
+            Some((index, axis, range)) if range > 0.0 => (index, axis),
+            _ => break,
+        };
+        let mut points = boxes.swap_remove(index);
+        points.sort_by(|a, b| a[axis].total_cmp(&b[axis]));
+        // Coupe à la valeur médiane sans séparer des couleurs identiques.
+        let median = points[points.len() / 2][axis];
+        let mut split = points.partition_point(|p| p[axis] < median);
+        if split == 0 {
+            split = points.partition_point(|p| p[axis] <= median);
+        }
+        let upper = points.split_off(split);
+        boxes.push(points);
+        boxes.push(upper);
+    }
+
+    boxes.iter().map(|points| mean(points)).collect()
+}
+
+fn kmeans(samples: &[Lab], mut centers: Vec<Lab>, iterations: usize) -> Vec<Lab> {
+    let mut assignment = vec![usize::MAX; samples.len()];
+
+    for _ in 0..iterations {
+        let mut moved = false;
+        for (sample, assigned) in samples.iter().zip(assignment.iter_mut()) {
+            let nearest = nearest_center(&centers, sample);
+            if nearest != *assigned {
+                *assigned = nearest;
+                moved = true;
+            }
+        }
+        if !moved {
+            break;
+        }
+
+        let mut sums = vec![[0.0; 3]; centers.len()];
+        let mut counts = vec![0usize; centers.len()];
+        for (sample, &cluster) in samples.iter().zip(&assignment) {
+            for axis in 0..3 {
+                sums[cluster][axis] += sample[axis];
+            }
+            counts[cluster] += 1;
+        }
+        for (center, (sum, count)) in centers.iter_mut().zip(sums.iter().zip(&counts)) {
+            // Un centre vide garde sa position précédente.
+            if *count > 0 {
+                let n = *count as f64;
+                *center = [sum[0] / n, sum[1] / n, sum[2] / n];
+            }
+        }
+    }
+
+    centers
+}
+
+fn nearest_center(centers: &[Lab], sample: &Lab) -> usize {
+    centers
+        .iter()
+        .enumerate()
+        .map(|(i, center)| (i, distance_sqr(center, sample)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+fn order_colors(mut colors: Vec<Lab>, ordering: PathOrdering) -> Vec<Lab> {
+    let hue = |lab: &Lab| lab[2].atan2(lab[1]);
+    match ordering {
+        PathOrdering::Lightness => {
+            colors.sort_by(|a, b| a[0].total_cmp(&b[0]));
+            colors
+        }
+        PathOrdering::Hue => {
+            colors.sort_by(|a, b| hue(a).total_cmp(&hue(b)).then(a[0].total_cmp(&b[0])));
+            colors
+        }
+        PathOrdering::NearestNeighbor => nearest_neighbor_path(colors),
+        PathOrdering::TwoOpt => two_opt(nearest_neighbor_path(colors)),
+    }
+}
+
+// Chemin glouton partant de la couleur la plus sombre.
+fn nearest_neighbor_path(mut remaining: Vec<Lab>) -> Vec<Lab> {
+    let mut path = Vec::with_capacity(remaining.len());
+    let start = remaining
+        .iter()
+        .enumerate()
+        .min_by(|a, b| a.1[0].total_cmp(&b.1[0]))
+        .map(|(i, _)| i);
+    if let Some(start) = start {
+        path.push(remaining.swap_remove(start));
+    }
+    while let Some(current) = path.last() {
+        let next = match remaining
+            .iter()
+            .enumerate()
+            .min_by(|a, b| distance_sqr(current, a.1).total_cmp(&distance_sqr(current, b.1)))
+        {
+            Some((i, _)) => i,
+            None => break,
+        };
+        path.push(remaining.swap_remove(next));
+    }
+    path
+}
+
+// 2-opt sur un chemin ouvert : inverse un segment tant que cela raccourcit le trajet.
+fn two_opt(mut path: Vec<Lab>) -> Vec<Lab> {
+    let dist = |a: &Lab, b: &Lab| distance_sqr(a, b).sqrt();
+    let n = path.len();
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for i in 0..n.saturating_sub(1) {
+            for j in i + 1..n {
+                // Arêtes (i-1, i) et (j, j+1) remplacées par (i-1, j) et (i, j+1).
+                let before = i.checked_sub(1).map(|p| dist(&path[p], &path[i])).unwrap_or(0.0)
+                    + path.get(j + 1).map(|q| dist(&path[j], q)).unwrap_or(0.0);
+                let after = i.checked_sub(1).map(|p| dist(&path[p], &path[j])).unwrap_or(0.0)
+                    + path.get(j + 1).map(|q| dist(&path[i], q)).unwrap_or(0.0);
+                if after + 1e-12 < before {
+                    path[i..=j].reverse();
+                    improved = true;
+                }
+            }
+        }
+    }
+    // Le dégradé va du plus sombre au plus clair.
+    if let (Some(first), Some(last)) = (path.first(), path.last()) {
+        if first[0] > last[0] {
+            path.reverse();
+        }
+    }
+    path
+}
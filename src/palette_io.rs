@@ -0,0 +1,611 @@
+use crate::gradient::{ColorStop, GradientScheme, Interpolation};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub enum PaletteError {
+    Io(std::io::Error),
+    Parse { line: usize, message: String },
+    UnknownFormat(String),
+}
+
+impl fmt::Display for PaletteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaletteError::Io(err) => write!(f, "erreur d'entrée/sortie : {}", err),
+            PaletteError::Parse { line, message } => write!(f, "ligne {} : {}", line, message),
+            PaletteError::UnknownFormat(ext) => write!(f, "format de palette inconnu : {}", ext),
+        }
+    }
+}
+
+impl std::error::Error for PaletteError {}
+
+impl From<std::io::Error> for PaletteError {
+    fn from(err: std::io::Error) -> Self {
+        PaletteError::Io(err)
+    }
+}
+
+fn parse_error(line: usize, message: impl Into<String>) -> PaletteError {
+    PaletteError::Parse { line, message: message.into() }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaletteFormat {
+    FractintMap,
+    GimpGgr,
+    UltraFractalUgr,
+    Json,
+}
+
+impl PaletteFormat {
+    pub const ALL: [PaletteFormat; 4] = [
+        PaletteFormat::FractintMap,
+        PaletteFormat::GimpGgr,
+        PaletteFormat::UltraFractalUgr,
+        PaletteFormat::Json,
+    ];
+
+    pub fn from_path(path: &Path) -> Result<Self, PaletteError> {
+        let ext = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        match ext.as_str() {
+            "map" => Ok(PaletteFormat::FractintMap),
+            "ggr" => Ok(PaletteFormat::GimpGgr),
+            "ugr" => Ok(PaletteFormat::UltraFractalUgr),
+            "json" => Ok(PaletteFormat::Json),
+            _ => Err(PaletteError::UnknownFormat(ext)),
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            PaletteFormat::FractintMap => "map",
+            PaletteFormat::GimpGgr => "ggr",
+            PaletteFormat::UltraFractalUgr => "ugr",
+            PaletteFormat::Json => "json",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PaletteFormat::FractintMap => "Fractint",
+            PaletteFormat::GimpGgr => "GIMP",
+            PaletteFormat::UltraFractalUgr => "Ultra Fractal",
+            PaletteFormat::Json => "JSON",
+        }
+    }
+}
+
+/// Charge toutes les palettes d'un fichier (un `.ugr` peut en contenir plusieurs).
+pub fn load_palettes(path: &Path) -> Result<Vec<GradientScheme>, PaletteError> {
+    let format = PaletteFormat::from_path(path)?;
+    let source = fs::read_to_string(path)?;
+    let name = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("palette");
+
+    match format {
+        PaletteFormat::FractintMap => parse_map(name, &source).map(|g| vec![g]),
+        PaletteFormat::GimpGgr => parse_ggr(&source).map(|g| vec![g]),
+        PaletteFormat::UltraFractalUgr => parse_ugr(&source),
+        PaletteFormat::Json => parse_json(&source).map(|g| vec![g]),
+    }
+}
+
+pub fn save_palette(path: &Path, gradient: &GradientScheme) -> Result<(), PaletteError> {
+    let contents = match PaletteFormat::from_path(path)? {
+        PaletteFormat::FractintMap => write_map(gradient),
+        PaletteFormat::GimpGgr => write_ggr(gradient),
+        PaletteFormat::UltraFractalUgr => write_ugr(std::slice::from_ref(gradient)),
+        PaletteFormat::Json => write_json(gradient),
+    };
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+// --- Fractint .map ---------------------------------------------------------
+
+pub fn parse_map(name: &str, source: &str) -> Result<GradientScheme, PaletteError> {
+    let mut colors = Vec::new();
+
+    for (index, line) in source.lines().enumerate() {
+        let line_number = index + 1;
+        let mut fields = line.split_whitespace();
+        let first = match fields.next() {
+            Some(first) => first,
+            None => continue,
+        };
+        if first.starts_with(';') || first.starts_with('#') {
+            continue;
+        }
+
+        let channel = |value: Option<&str>| -> Result<u8, PaletteError> {
+            let value = value.ok_or_else(|| parse_error(line_number, "composante de couleur manquante"))?;
+            value.parse::<u8>().map_err(|_| {
+                parse_error(line_number, format!("composante invalide « {} » (0 à 255 attendu)", value))
+            })
+        };
+        let r = channel(Some(first))?;
+        let g = channel(fields.next())?;
+        let b = channel(fields.next())?;
+        // Le reste de la ligne est un commentaire libre.
+        colors.push((r, g, b));
+    }
+
+    if colors.len() < 2 {
+        return Err(parse_error(source.lines().count().max(1), "une palette .map doit contenir au moins deux couleurs"));
+    }
+
+    let last = (colors.len() - 1) as f64;
+    let stops = colors
+        .into_iter()
+        .enumerate()
+        .map(|(i, color)| ColorStop { position: i as f64 / last, color })
+        .collect();
+    Ok(GradientScheme::new(name, stops, Interpolation::Linear))
+}
+
+/// Écrit les 256 entrées attendues par Fractint.
+pub fn write_map(gradient: &GradientScheme) -> String {
+    let mut out = String::new();
+    for i in 0..256 {
+        let (r, g, b) = gradient.sample(i as f64 / 255.0);
+        out.push_str(&format!("{} {} {}\n", r, g, b));
+    }
+    out
+}
+
+// --- GIMP .ggr -------------------------------------------------------------
+
+const GGR_SAMPLES_PER_SEGMENT: usize = 16;
+
+pub fn parse_ggr(source: &str) -> Result<GradientScheme, PaletteError> {
+    let mut lines = source.lines().enumerate().map(|(i, line)| (i + 1, line.trim()));
+
+    match lines.next() {
+        Some((_, "GIMP Gradient")) => {}
+        Some((n, _)) => return Err(parse_error(n, "en-tête « GIMP Gradient » attendu")),
+        None => return Err(parse_error(1, "fichier vide")),
+    }
+
+    let (mut n, mut line) = lines.next().ok_or_else(|| parse_error(2, "nombre de segments manquant"))?;
+    let mut name = String::from("GIMP");
+    if let Some(rest) = line.strip_prefix("Name:") {
+        name = rest.trim().to_string();
+        (n, line) = lines.next().ok_or_else(|| parse_error(n + 1, "nombre de segments manquant"))?;
+    }
+    let count: usize = line
+        .parse()
+        .map_err(|_| parse_error(n, format!("nombre de segments invalide « {} »", line)))?;
+
+    let mut stops: Vec<ColorStop> = Vec::new();
+    for _ in 0..count {
+        (n, line) = lines
+            .next()
+            .ok_or_else(|| parse_error(n + 1, format!("{} segments attendus", count)))?;
+        let segment = GgrSegment::parse(n, line)?;
+        segment.push_stops(&mut stops);
+    }
+
+    stops.dedup_by(|b, a| a.position == b.position && a.color == b.color);
+    Ok(GradientScheme::new(&name, stops, Interpolation::Linear))
+}
+
+struct GgrSegment {
+    left: f64,
+    middle: f64,
+    right: f64,
+    left_color: [f64; 3],
+    right_color: [f64; 3],
+    blending: u32,
+    coloring: u32,
+}
+
+impl GgrSegment {
+    fn parse(line_number: usize, line: &str) -> Result<Self, PaletteError> {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 13 {
+            return Err(parse_error(
+                line_number,
+                format!("segment incomplet : 13 champs attendus, {} trouvés", fields.len()),
+            ));
+        }
+        let number = |i: usize| -> Result<f64, PaletteError> {
+            fields[i]
+                .parse::<f64>()
+                .map_err(|_| parse_error(line_number, format!("nombre invalide « {} » (champ {})", fields[i], i + 1)))
+        };
+        let integer = |i: usize| -> Result<u32, PaletteError> {
+            fields[i]
+                .parse::<u32>()
+                .map_err(|_| parse_error(line_number, format!("entier invalide « {} » (champ {})", fields[i], i + 1)))
+        };
+
+        let segment = Self {
+            left: number(0)?,
+            middle: number(1)?,
+            right: number(2)?,
+            left_color: [number(3)?, number(4)?, number(5)?],
+            right_color: [number(7)?, number(8)?, number(9)?],
+            blending: integer(11)?,
+            coloring: integer(12)?,
+        };
+        if !(segment.left <= segment.middle && segment.middle <= segment.right) {
+            return Err(parse_error(line_number, "positions du segment non ordonnées"));
+        }
+        if segment.blending > 5 || segment.coloring > 2 {
+            return Err(parse_error(line_number, "type de mélange ou de couleur inconnu"));
+        }
+        Ok(segment)
+    }
+
+    fn is_linear_rgb(&self) -> bool {
+        let width = self.right - self.left;
+        self.blending == 0 && self.coloring == 0 && (width <= 0.0 || ((self.middle - self.left) / width - 0.5).abs() < 1e-6)
+    }
+
+    // Reprend les fonctions de mélange de GIMP (gimpgradient.c).
+    fn blend(&self, pos: f64) -> f64 {
+        let width = self.right - self.left;
+        if width <= 0.0 {
+            return 0.0;
+        }
+        let middle = ((self.middle - self.left) / width).clamp(1e-6, 1.0 - 1e-6);
+        let linear = if pos <= middle {
+            0.5 * pos / middle
+        } else {
+            0.5 + 0.5 * (pos - middle) / (1.0 - middle)
+        };
+        match self.blending {
+            1 => pos.powf(0.5f64.ln() / middle.ln()),
+            2 => ((-std::f64::consts::FRAC_PI_2 + std::f64::consts::PI * linear).sin() + 1.0) / 2.0,
+            3 => (1.0 - (linear - 1.0) * (linear - 1.0)).sqrt(),
+            4 => 1.0 - (1.0 - linear * linear).sqrt(),
+            5 => if pos >= middle { 1.0 } else { 0.0 },
+            _ => linear,
+        }
+    }
+
+    fn color_at(&self, pos: f64) -> (u8, u8, u8) {
+        let f = self.blend(pos);
+        let [r, g, b] = match self.coloring {
+            0 => [0, 1, 2].map(|i| self.left_color[i] + (self.right_color[i] - self.left_color[i]) * f),
+            direction => {
+                let a = rgb_to_hsv(self.left_color);
+                let b = rgb_to_hsv(self.right_color);
+                let mut dh = b[0] - a[0];
+                // 1 : sens antihoraire (teinte croissante), 2 : sens horaire.
+                if direction == 1 && dh < 0.0 {
+                    dh += 1.0;
+                } else if direction == 2 && dh > 0.0 {
+                    dh -= 1.0;
+                }
+                hsv_to_rgb([
+                    (a[0] + dh * f).rem_euclid(1.0),
+                    a[1] + (b[1] - a[1]) * f,
+                    a[2] + (b[2] - a[2]) * f,
+                ])
+            }
+        };
+        (to_u8(r), to_u8(g), to_u8(b))
+    }
+
+    fn push_stops(&self, stops: &mut Vec<ColorStop>) {
+        let samples = if self.is_linear_rgb() { 1 } else { GGR_SAMPLES_PER_SEGMENT };
+        for i in 0..=samples {
+            let pos = i as f64 / samples as f64;
+            stops.push(ColorStop {
+                position: self.left + (self.right - self.left) * pos,
+                color: self.color_at(pos),
+            });
+        }
+    }
+}
+
+/// Chaque paire d'arrêts consécutifs devient un segment linéaire RGB.
+pub fn write_ggr(gradient: &GradientScheme) -> String {
+    let mut segments = Vec::new();
+    let stops = &gradient.stops;
+
+    if let (Some(first), Some(last)) = (stops.first(), stops.last()) {
+        if first.position > 0.0 {
+            segments.push((0.0, first.position, first.color, first.color));
+        }
+        for pair in stops.windows(2) {
+            if pair[1].position > pair[0].position {
+                segments.push((pair[0].position, pair[1].position, pair[0].color, pair[1].color));
+            }
+        }
+        if last.position < 1.0 {
+            segments.push((last.position, 1.0, last.color, last.color));
+        }
+    }
+    if segments.is_empty() {
+        let color = stops.first().map(|stop| stop.color).unwrap_or((0, 0, 0));
+        segments.push((0.0, 1.0, color, color));
+    }
+
+    let channel = |c: u8| c as f64 / 255.0;
+    let mut out = format!("GIMP Gradient\nName: {}\n{}\n", gradient.name, segments.len());
+    for (left, right, a, b) in segments {
+        out.push_str(&format!(
+            "{:.6} {:.6} {:.6} {:.6} {:.6} {:.6} 1.000000 {:.6} {:.6} {:.6} 1.000000 0 0\n",
+            left,
+            (left + right) / 2.0,
+            right,
+            channel(a.0),
+            channel(a.1),
+            channel(a.2),
+            channel(b.0),
+            channel(b.1),
+            channel(b.2),
+        ));
+    }
+    out
+}
+
+fn to_u8(c: f64) -> u8 {
+    (c.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn rgb_to_hsv([r, g, b]: [f64; 3]) -> [f64; 3] {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    let h = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        ((g - b) / delta).rem_euclid(6.0) / 6.0
+    } else if max == g {
+        ((b - r) / delta + 2.0) / 6.0
+    } else {
+        ((r - g) / delta + 4.0) / 6.0
+    };
+    let s = if max == 0.0 { 0.0 } else { delta / max };
+    [h, s, max]
+}
+
+fn hsv_to_rgb([h, s, v]: [f64; 3]) -> [f64; 3] {
+    let c = v * s;
+    let x = c * (1.0 - ((h * 6.0) % 2.0 - 1.0).abs());
+    let m = v - c;
+    let (r, g, b) = match (h * 6.0).floor() as i32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    [r + m, g + m, b + m]
+}
+
+// --- Ultra Fractal .ugr ----------------------------------------------------
+
+// Ultra Fractal répartit les couleurs sur 400 index ; les index hors de
+// 0..=400 (négatifs notamment) désignent le même cycle de couleurs. L'index
+// 400 ferme le cycle : write_ugr l'emploie pour la position 1.
+const UGR_INDEX_COUNT: i64 = 400;
+
+pub fn parse_ugr(source: &str) -> Result<Vec<GradientScheme>, PaletteError> {
+    let mut gradients = Vec::new();
+    let mut current: Option<(String, Vec<ColorStop>, Interpolation)> = None;
+    let mut in_gradient = false;
+    let mut last_line = 0;
+
+    for (index, raw) in source.lines().enumerate() {
+        let line_number = index + 1;
+        last_line = line_number;
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+
+        if current.is_none() {
+            let name = line
+                .strip_suffix('{')
+                .ok_or_else(|| parse_error(line_number, "début de bloc « nom { » attendu"))?;
+            current = Some((name.trim().to_string(), Vec::new(), Interpolation::Linear));
+            in_gradient = false;
+            continue;
+        }
+
+        if line == "}" {
+            let (name, stops, interpolation) = current.take().expect("bloc courant");
+            if stops.is_empty() {
+                return Err(parse_error(line_number, format!("le dégradé « {} » ne contient aucune couleur", name)));
+            }
+            gradients.push(GradientScheme::new(&name, stops, interpolation));
+            continue;
+        }
+        if line == "gradient:" {
+            in_gradient = true;
+            continue;
+        }
+        if line.ends_with(':') {
+            // Sections « opacity: » etc., ignorées.
+            in_gradient = false;
+            continue;
+        }
+        if !in_gradient {
+            continue;
+        }
+
+        let (name, stops, interpolation) = current.as_mut().expect("bloc courant");
+        let mut index = None;
+        let mut color = None;
+        for (key, value) in ugr_fields(line_number, line)? {
+            match key {
+                "title" => *name = value.to_string(),
+                "smooth" => {
+                    *interpolation = if value == "yes" { Interpolation::Smooth } else { Interpolation::Linear };
+                }
+                "index" => {
+                    index = Some(value.parse::<i64>().map_err(|_| {
+                        parse_error(line_number, format!("index invalide « {} »", value))
+                    })?);
+                }
+                "color" => {
+                    color = Some(value.parse::<u32>().map_err(|_| {
+                        parse_error(line_number, format!("couleur invalide « {} »", value))
+                    })?);
+                }
+                _ => {}
+            }
+        }
+        match (index, color) {
+            (Some(index), Some(color)) => {
+                // Couleur codée en BGR : r + 256 * g + 65536 * b.
+                let rgb = ((color & 0xff) as u8, ((color >> 8) & 0xff) as u8, ((color >> 16) & 0xff) as u8);
+                let index = if index == UGR_INDEX_COUNT { index } else { index.rem_euclid(UGR_INDEX_COUNT) };
+                stops.push(ColorStop {
+                    position: index as f64 / UGR_INDEX_COUNT as f64,
+                    color: rgb,
+                });
+            }
+            (Some(_), None) => return Err(parse_error(line_number, "« color= » manquant après « index= »")),
+            (None, Some(_)) => return Err(parse_error(line_number, "« index= » manquant avant « color= »")),
+            (None, None) => {}
+        }
+    }
+
+    if let Some((name, _, _)) = current {
+        return Err(parse_error(last_line, format!("bloc « {} » non fermé", name)));
+    }
+    if gradients.is_empty() {
+        return Err(parse_error(last_line.max(1), "aucun dégradé trouvé"));
+    }
+    Ok(gradients)
+}
+
+fn ugr_fields(line_number: usize, line: &str) -> Result<Vec<(&str, &str)>, PaletteError> {
+    let mut fields = Vec::new();
+    let mut rest = line.trim_start();
+    while !rest.is_empty() {
+        let eq = rest
+            .find('=')
+            .ok_or_else(|| parse_error(line_number, format!("« clé=valeur » attendu : « {} »", rest)))?;
+        let key = rest[..eq].trim();
+        rest = &rest[eq + 1..];
+        let value;
+        if let Some(quoted) = rest.strip_prefix('"') {
+            let end = quoted
+                .find('"')
+                .ok_or_else(|| parse_error(line_number, "guillemet fermant manquant"))?;
+            value = &quoted[..end];
+            rest = &quoted[end + 1..];
+        } else {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            value = &rest[..end];
+            rest = &rest[end..];
+        }
+        fields.push((key, value));
+        rest = rest.trim_start();
+    }
+    Ok(fields)
+}
+
+pub fn write_ugr(gradients: &[GradientScheme]) -> String {
+    let mut out = String::new();
+    for gradient in gradients {
+        let block_name: String = gradient
+            .name
+            .chars()
+            .map(|c| if c.is_whitespace() || c == '{' || c == '}' { '-' } else { c })
+            .collect();
+        let smooth = if gradient.interpolation == Interpolation::Smooth { "yes" } else { "no" };
+        out.push_str(&format!("{} {{\ngradient:\n  title=\"{}\" smooth={}\n", block_name, gradient.name.replace('"', "'"), smooth));
+        let mut last_index = -1;
+        for stop in &gradient.stops {
+            let index = (stop.position * UGR_INDEX_COUNT as f64).round() as i64;
+            if index == last_index {
+                continue;
+            }
+            last_index = index;
+            let (r, g, b) = stop.color;
+            let color = r as u32 | (g as u32) << 8 | (b as u32) << 16;
+            out.push_str(&format!("  index={} color={}\n", index, color));
+        }
+        out.push_str("opacity:\n  smooth=no index=0 opacity=255\n}\n\n");
+    }
+    out
+}
+
+// --- JSON ------------------------------------------------------------------
+
+pub fn parse_json(source: &str) -> Result<GradientScheme, PaletteError> {
+    let mut gradient: GradientScheme = serde_json::from_str(source)
+        .map_err(|err| parse_error(err.line(), err.to_string()))?;
+    if gradient.stops.is_empty() {
+        return Err(parse_error(1, "la palette ne contient aucun arrêt de couleur"));
+    }
+    gradient.sort_stops();
+    Ok(gradient)
+}
+
+pub fn write_json(gradient: &GradientScheme) -> String {
+    serde_json::to_string_pretty(gradient).expect("sérialisation d'une palette")
+}
+
+// --- Bibliothèque de palettes utilisateur ----------------------------------
+
+pub struct PaletteLibrary {
+    pub dir: PathBuf,
+}
+
+impl Default for PaletteLibrary {
+    fn default() -> Self {
+        Self { dir: Self::default_dir() }
+    }
+}
+
+impl PaletteLibrary {
+    pub fn default_dir() -> PathBuf {
+        let base = std::env::var_os("APPDATA")
+            .or_else(|| std::env::var_os("HOME"))
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("."));
+        base.join(".fractal_generator").join("palettes")
+    }
+
+    /// Fichiers de palettes reconnus, triés par nom. Un dossier absent est vide.
+    pub fn list(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.is_file() && PaletteFormat::from_path(path).is_ok())
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        paths.sort();
+        paths
+    }
+
+    /// Enregistre la palette en JSON sous un nom dérivé du sien.
+    pub fn save(&self, gradient: &GradientScheme) -> Result<PathBuf, PaletteError> {
+        fs::create_dir_all(&self.dir)?;
+        let mut stem: String = gradient
+            .name
+            .chars()
+            .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        if stem.is_empty() {
+            stem = String::from("palette");
+        }
+        let path = self.dir.join(format!("{}.{}", stem, PaletteFormat::Json.extension()));
+        save_palette(&path, gradient)?;
+        Ok(path)
+    }
+
+    pub fn remove(&self, path: &Path) -> Result<(), PaletteError> {
+        fs::remove_file(path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+use num::Complex;
+
+/// Polynôme complexe, coefficients par degré croissant : a0 + a1·z + a2·z² + …
+#[derive(Clone, Debug, PartialEq)]
+pub struct Polynomial {
+    pub coefficients: Vec<Complex<f64>>,
+}
+
+impl Polynomial {
+    pub fn new(mut coefficients: Vec<Complex<f64>>) -> Self {
+        while coefficients.len() > 1 && coefficients.last() == Some(&Complex::new(0.0, 0.0)) {
+            coefficients.pop();
+        }
+        if coefficients.is_empty() {
+            coefficients.push(Complex::new(0.0, 0.0));
+        }
+        Self { coefficients }
+    }
+
+    /// Polynôme unitaire ∏ (z − r).
+    pub fn from_roots(roots: &[Complex<f64>]) -> Self {
+        let mut coefficients = vec![Complex::new(1.0, 0.0)];
+        for root in roots {
+            let mut next = vec![Complex::new(0.0, 0.0); coefficients.len() + 1];
+            for (k, a) in coefficients.iter().enumerate() {
+                next[k + 1] += a;
+                next[k] -= a * root;
+            }
+            coefficients = next;
+        }
+        Self::new(coefficients)
+    }
+
+    pub fn degree(&self) -> usize {
+        self.coefficients.len() - 1
+    }
+
+    pub fn eval(&self, z: Complex<f64>) -> Complex<f64> {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(Complex::new(0.0, 0.0), |acc, a| acc * z + a)
+    }
+
+    /// p(z) et ses N − 1 premières dérivées exactes, par schéma de Horner.
+    pub fn eval_derivatives<const N: usize>(&self, z: Complex<f64>) -> [Complex<f64>; N] {
+        let mut d = [Complex::new(0.0, 0.0); N];
+        let mut coefficients = self.coefficients.iter().rev();
+        if let Some(leading) = coefficients.next() {
+            d[0] = *leading;
+        }
+        for a in coefficients {
+            for k in (1..N).rev() {
+                d[k] = d[k] * z + d[k - 1];
+            }
+            d[0] = d[0] * z + a;
+        }
+        let mut factorial = 1.0;
+        for (k, value) in d.iter_mut().enumerate().skip(1) {
+            factorial *= k as f64;
+            *value *= factorial;
+        }
+        d
+    }
+
+    /// Racines par la méthode de Durand–Kerner.
+    pub fn roots(&self) -> Vec<Complex<f64>> {
+        let n = self.degree();
+        if n == 0 {
+            return Vec::new();
+        }
+        let leading = self.coefficients[n];
+        let monic = Polynomial::new(self.coefficients.iter().map(|a| a / leading).collect());
+
+        let seed = Complex::new(0.4, 0.9);
+        let mut roots: Vec<Complex<f64>> = (0..n).map(|k| seed.powu(k as u32)).collect();
+        for _ in 0..500 {
+            let mut change: f64 = 0.0;
+            for i in 0..n {
+                let mut denominator = Complex::new(1.0, 0.0);
+                for j in 0..n {
+                    if i != j {
+                        denominator *= roots[i] - roots[j];
+                    }
+                }
+                if denominator == Complex::new(0.0, 0.0) {
+                    continue;
+                }
+                let delta = monic.eval(roots[i]) / denominator;
+                roots[i] -= delta;
+                change = change.max(delta.norm());
+            }
+            if change < 1e-14 {
+                break;
+            }
+        }
+        roots
+    }
+}
@@ -0,0 +1,561 @@
+use crate::color_schemes::ColorScheme;
+use crate::distance::Algebra;
+use rayon::prelude::*;
+use std::time::{Duration, Instant};
+
+/// Fractale 3D définie par un estimateur de distance.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Shape {
+    /// Mandelbulb de White et Nylander, puissance n en coordonnées sphériques.
+    Mandelbulb { power: f64 },
+    /// Mandelbox de Lowe : repli dans la boîte, inversion sphérique, homothétie.
+    Mandelbox { scale: f64, min_radius: f64, fixed_radius: f64 },
+    /// Julia de q² + c en dimension 4, vue par une coupe de dimension 3.
+    Julia4D { algebra: Algebra, c: [f64; 4], slice: Slice },
+}
+
+impl Shape {
+    pub const ALL: [Shape; 4] = [
+        Shape::Mandelbulb { power: 8.0 },
+        Shape::Mandelbox { scale: -1.5, min_radius: 0.5, fixed_radius: 1.0 },
+        Shape::Julia4D { algebra: Algebra::Quaternion, c: [-0.291, -0.399, 0.339, 0.437], slice: Slice::W0 },
+        Shape::Julia4D { algebra: Algebra::Bicomplex, c: [-0.12, 0.75, 0.0, 0.0], slice: Slice::W0 },
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Shape::Mandelbulb { .. } => "Mandelbulb",
+            Shape::Mandelbox { .. } => "Mandelbox",
+            Shape::Julia4D { algebra: Algebra::Quaternion, .. } => "Julia quaternionique",
+            Shape::Julia4D { algebra: Algebra::Bicomplex, .. } => "Julia bicomplexe",
+        }
+    }
+
+    /// Caméra qui cadre la forme entière.
+    pub fn default_camera(&self) -> Camera {
+        let position = match self {
+            Shape::Mandelbulb { .. } => [0.0, 1.2, -2.6],
+            Shape::Mandelbox { .. } => [0.0, 3.5, -7.0],
+            Shape::Julia4D { .. } => [0.0, 1.2, -2.8],
+        };
+        Camera { position, target: [0.0; 3], fov: 45.0 }
+    }
+
+    /// Rayon d'une sphère centrée à l'origine qui contient la forme.
+    fn bounding_radius(&self) -> f64 {
+        match *self {
+            Shape::Mandelbulb { .. } => 1.5,
+            Shape::Julia4D { .. } => 2.0,
+            // Le repli de la boîte ramène |z| sous 2(|s| + 1)/(|s| − 1) par axe.
+            Shape::Mandelbox { scale, .. } if scale.abs() > 1.05 => {
+                (2.0 * (scale.abs() + 1.0) / (scale.abs() - 1.0) * 3f64.sqrt()).min(40.0)
+            }
+            Shape::Mandelbox { .. } => 40.0,
+        }
+    }
+}
+
+/// Hyperplan de coupe d'une fractale de dimension 4 : l'espace (x, y, z)
+/// est placé en w = `offset`, puis tourné dans les plans xw, yw et zw.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Slice {
+    pub offset: f64,
+    /// Angles de rotation, en degrés ; les faire varier fait défiler la
+    /// quatrième dimension.
+    pub rotation: [f64; 3],
+}
+
+impl Slice {
+    pub const W0: Slice = Slice { offset: 0.0, rotation: [0.0; 3] };
+
+    /// Images dans R⁴ de l'origine et des trois axes de l'espace de la scène.
+    fn embedding(&self) -> [[f64; 4]; 4] {
+        let mut points = [[0.0, 0.0, 0.0, self.offset], [1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0]];
+        for (axis, angle) in self.rotation.iter().enumerate() {
+            let (sin, cos) = angle.to_radians().sin_cos();
+            for point in &mut points {
+                let (a, w) = (point[axis], point[3]);
+                point[axis] = a * cos - w * sin;
+                point[3] = a * sin + w * cos;
+            }
+        }
+        points
+    }
+}
+
+/// Borne inférieure de la distance de `p` au Mandelbulb de puissance `power`,
+/// et piège d'orbite ramené à [0, 1].
+pub fn mandelbulb(p: [f64; 3], power: f64, iterations: u32) -> (f64, f64) {
+    let mut z = p;
+    let mut dr = 1.0;
+    let mut r = norm(z);
+    let mut trap = r;
+    for _ in 0..iterations {
+        if r > 2.0 {
+            break;
+        }
+        // La dérivée suit |z|ⁿ⁻¹·n, le reste de la formule est une rotation.
+        dr = r.powf(power - 1.0) * power * dr + 1.0;
+        let theta = (z[2] / r.max(1e-300)).clamp(-1.0, 1.0).acos() * power;
+        let phi = z[1].atan2(z[0]) * power;
+        let zr = r.powf(power);
+        z = add(scale([theta.sin() * phi.cos(), theta.sin() * phi.sin(), theta.cos()], zr), p);
+        r = norm(z);
+        trap = trap.min(r);
+    }
+    (0.5 * r.max(1e-300).ln() * r / dr, trap.min(1.0))
+}
+
+fn mandelbox(p: [f64; 3], box_scale: f64, min_radius: f64, fixed_radius: f64, iterations: u32) -> (f64, f64) {
+    let (min2, fixed2) = (min_radius * min_radius, fixed_radius * fixed_radius);
+    let mut z = p;
+    let mut dr = 1.0;
+    let mut trap = f64::INFINITY;
+    for _ in 0..iterations {
+        z = z.map(|v| v.clamp(-1.0, 1.0) * 2.0 - v);
+        let r2 = dot(z, z);
+        let factor = if r2 < min2 {
+            fixed2 / min2
+        } else if r2 < fixed2 {
+            fixed2 / r2
+        } else {
+            1.0
+        };
+        z = add(scale(z, factor * box_scale), p);
+        dr = dr * factor * box_scale.abs() + 1.0;
+        trap = trap.min(r2.sqrt());
+        if dot(z, z) > 1e4 {
+            break;
+        }
+    }
+    // Les orbites repassent presque toutes près de l'origine : la racine
+    // étale le piège sur la palette.
+    (norm(z) / dr.abs(), (trap / fixed_radius.max(1e-9)).min(1.0).sqrt())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera {
+    pub position: [f64; 3],
+    pub target: [f64; 3],
+    /// Champ de vision vertical, en degrés.
+    pub fov: f64,
+}
+
+impl Camera {
+    /// Repère (droite, haut, avant) ; le haut du monde est +y.
+    fn basis(&self) -> [[f64; 3]; 3] {
+        let forward = normalize(sub(self.target, self.position));
+        let mut right = cross([0.0, 1.0, 0.0], forward);
+        if dot(right, right) < 1e-12 {
+            right = [1.0, 0.0, 0.0];
+        }
+        let right = normalize(right);
+        [right, cross(forward, right), forward]
+    }
+
+    /// Tourne la caméra autour de sa cible, en radians : `yaw` autour de la
+    /// verticale, `pitch` vers le haut (sans passer par les pôles).
+    pub fn orbit(&mut self, yaw: f64, pitch: f64) {
+        let offset = sub(self.position, self.target);
+        let r = norm(offset);
+        if r == 0.0 {
+            return;
+        }
+        let pitch = ((offset[1] / r).asin() + pitch).clamp(-1.55, 1.55);
+        let yaw = offset[0].atan2(offset[2]) + yaw;
+        self.position = add(self.target, scale([pitch.cos() * yaw.sin(), pitch.sin(), pitch.cos() * yaw.cos()], r));
+    }
+
+    /// Rapproche la caméra de sa cible d'un facteur `factor`.
+    pub fn dolly(&mut self, factor: f64) {
+        self.position = add(self.target, scale(sub(self.position, self.target), 1.0 / factor));
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RayMarchSettings {
+    pub shape: Shape,
+    pub camera: Camera,
+    /// Itérations de la formule pour chaque estimation de distance.
+    pub iterations: u32,
+    pub max_steps: u32,
+    /// Précision du contact, en pixels : plus petit, plus de détails.
+    pub detail: f64,
+    /// Direction de la lumière, en degrés.
+    pub light_azimuth: f64,
+    pub light_elevation: f64,
+    /// Dureté des ombres douces ; 0 les désactive.
+    pub shadow_hardness: f64,
+    /// Intensité de l'occlusion ambiante, entre 0 et 1.
+    pub ambient_occlusion: f64,
+    pub fog_density: f64,
+    pub fog_color: [u8; 3],
+}
+
+impl Default for RayMarchSettings {
+    fn default() -> Self {
+        let shape = Shape::ALL[0];
+        Self {
+            shape,
+            camera: shape.default_camera(),
+            iterations: 12,
+            max_steps: 200,
+            detail: 0.5,
+            light_azimuth: 40.0,
+            light_elevation: 50.0,
+            shadow_hardness: 16.0,
+            ambient_occlusion: 0.8,
+            fog_density: 0.05,
+            fog_color: [20, 22, 30],
+        }
+    }
+}
+
+// Côté des tuiles, et taille des blocs de chaque passe : la première passe
+// donne vite une image grossière, les suivantes l'affinent.
+const TILE: usize = 32;
+const BLOCKS: [usize; 4] = [8, 4, 2, 1];
+// Durée visée d'un lot, pour garder l'interface réactive.
+const BATCH_TIME: Duration = Duration::from_millis(50);
+
+/// Éclairage d'un pixel, indépendant de la palette : la couleur est
+/// recalculée sans relancer les rayons.
+#[derive(Clone, Copy, Debug, Default)]
+struct Sample {
+    hit: bool,
+    trap: f32,
+    light: f32,
+    specular: f32,
+    fog: f32,
+}
+
+/// Rendu progressif par tuiles : chaque lot traite des tuiles en parallèle
+/// jusqu'à épuiser son budget de temps, passe après passe.
+pub struct RayMarcher {
+    pub settings: RayMarchSettings,
+    width: usize,
+    height: usize,
+    samples: Vec<Sample>,
+    pass: usize,
+    next_tile: usize,
+}
+
+struct Scene {
+    shape: Shape,
+    /// Coupe des fractales de dimension 4 (voir `Slice::embedding`).
+    embedding: [[f64; 4]; 4],
+    iterations: u32,
+    max_steps: u32,
+    origin: [f64; 3],
+    basis: [[f64; 3]; 3],
+    /// Demi-largeur de l'image à distance 1 de la caméra.
+    half_height: f64,
+    /// Angle sous-tendu par un pixel, multiplié par la précision voulue.
+    cone: f64,
+    bound: f64,
+    light: [f64; 3],
+    shadow_hardness: f64,
+    ambient_occlusion: f64,
+    fog_density: f64,
+}
+
+impl RayMarcher {
+    pub fn new(settings: RayMarchSettings, size: (usize, usize)) -> Self {
+        let (width, height) = (size.0.max(1), size.1.max(1));
+        Self {
+            settings,
+            width,
+            height,
+            samples: vec![Sample { fog: 1.0, ..Sample::default() }; width * height],
+            pass: 0,
+            next_tile: 0,
+        }
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.pass >= BLOCKS.len()
+    }
+
+    /// Avancement entre 0 et 1, chaque passe comptant pour sa part de pixels.
+    pub fn progress(&self) -> f64 {
+        let weights = BLOCKS.map(|b| 1.0 / (b * b) as f64);
+        let total: f64 = weights.iter().sum();
+        let done: f64 = weights.iter().take(self.pass).sum();
+        let current = weights.get(self.pass).map_or(0.0, |w| w * self.next_tile as f64 / self.tile_count() as f64);
+        (done + current) / total
+    }
+
+    fn tile_count(&self) -> usize {
+        self.width.div_ceil(TILE) * self.height.div_ceil(TILE)
+    }
+
+    fn scene(&self) -> Scene {
+        let s = &self.settings;
+        let half_height = (s.camera.fov.clamp(1.0, 170.0).to_radians() / 2.0).tan();
+        let (sin_az, cos_az) = s.light_azimuth.to_radians().sin_cos();
+        let (sin_el, cos_el) = s.light_elevation.to_radians().sin_cos();
+        Scene {
+            shape: s.shape,
+            embedding: match s.shape {
+                Shape::Julia4D { slice, .. } => slice.embedding(),
+                _ => Slice::W0.embedding(),
+            },
+            iterations: s.iterations.max(1),
+            max_steps: s.max_steps.max(1),
+            origin: s.camera.position,
+            basis: s.camera.basis(),
+            half_height,
+            cone: 2.0 * half_height / self.height as f64 * s.detail.max(0.01),
+            bound: s.shape.bounding_radius(),
+            light: [cos_el * sin_az, sin_el, -cos_el * cos_az],
+            shadow_hardness: s.shadow_hardness,
+            ambient_occlusion: s.ambient_occlusion.clamp(0.0, 1.0),
+            fog_density: s.fog_density.max(0.0),
+        }
+    }
+
+    /// Calcule des tuiles de la passe courante pendant environ `BATCH_TIME`.
+    pub fn run_batch(&mut self) {
+        let start = Instant::now();
+        let scene = self.scene();
+        let chunk = rayon::current_num_threads().max(1);
+        while !self.is_done() && start.elapsed() < BATCH_TIME {
+            let block = BLOCKS[self.pass];
+            let coarser = self.pass.checked_sub(1).map(|p| BLOCKS[p]);
+            let tiles = self.next_tile..(self.next_tile + chunk).min(self.tile_count());
+            let columns = self.width.div_ceil(TILE);
+            let (width, height) = (self.width, self.height);
+            let results: Vec<Vec<(usize, usize, Sample)>> = tiles
+                .clone()
+                .into_par_iter()
+                .map(|tile| {
+                    let (x0, y0) = (tile % columns * TILE, tile / columns * TILE);
+                    let mut out = Vec::new();
+                    for y in (y0..(y0 + TILE).min(height)).filter(|y| y % block == 0) {
+                        for x in (x0..(x0 + TILE).min(width)).filter(|x| x % block == 0) {
+                            // Déjà calculé par la passe précédente.
+                            if coarser.is_some_and(|c| x % c == 0 && y % c == 0) {
+                                continue;
+                            }
+                            out.push((x, y, scene.shade(x as f64 + 0.5, y as f64 + 0.5, width, height)));
+                        }
+                    }
+                    out
+                })
+                .collect();
+            for (x, y, sample) in results.into_iter().flatten() {
+                for by in y..(y + block).min(height) {
+                    self.samples[by * width + x..by * width + (x + block).min(width)].fill(sample);
+                }
+            }
+            self.next_tile = tiles.end;
+            if self.next_tile >= self.tile_count() {
+                self.pass += 1;
+                self.next_tile = 0;
+            }
+        }
+    }
+
+    /// Image RGBA : la palette colore le piège d'orbite, puis l'éclairage
+    /// et le brouillard s'appliquent.
+    pub fn image(&self, scheme: &dyn ColorScheme) -> Vec<u8> {
+        let fog_color = self.settings.fog_color.map(|c| c as f32);
+        let mut pixels = vec![0; self.samples.len() * 4];
+        pixels.par_chunks_mut(4).zip(&self.samples).for_each(|(pixel, sample)| {
+            let color = if sample.hit {
+                let (r, g, b) = scheme.get_color(sample.trap as f64);
+                [r, g, b].map(|c| c as f32 * sample.light + 255.0 * sample.specular)
+            } else {
+                fog_color
+            };
+            for k in 0..3 {
+                pixel[k] = (color[k] * (1.0 - sample.fog) + fog_color[k] * sample.fog).clamp(0.0, 255.0) as u8;
+            }
+            pixel[3] = 255;
+        });
+        pixels
+    }
+}
+
+impl Scene {
+    /// Borne inférieure de la distance de `p` à la forme, et piège d'orbite
+    /// (plus petite distance de l'orbite à l'origine, ramenée à [0, 1]).
+    fn estimate(&self, p: [f64; 3]) -> (f64, f64) {
+        match self.shape {
+            Shape::Mandelbulb { power } => mandelbulb(p, power, self.iterations),
+            Shape::Mandelbox { scale, min_radius, fixed_radius } => mandelbox(p, scale, min_radius, fixed_radius, self.iterations),
+            Shape::Julia4D { algebra, c, .. } => {
+                let [origin, x, y, z] = self.embedding;
+                let q = [0, 1, 2, 3].map(|k| origin[k] + p[0] * x[k] + p[1] * y[k] + p[2] * z[k]);
+                let estimate = algebra.julia(q, c, self.iterations);
+                (estimate.distance, estimate.trap)
+            }
+        }
+    }
+
+    #[inline]
+    fn distance(&self, p: [f64; 3]) -> f64 {
+        self.estimate(p).0
+    }
+
+    fn shade(&self, px: f64, py: f64, width: usize, height: usize) -> Sample {
+        let aspect = width as f64 / height as f64;
+        let u = (2.0 * px / width as f64 - 1.0) * self.half_height * aspect;
+        let v = (1.0 - 2.0 * py / height as f64) * self.half_height;
+        let [right, up, forward] = self.basis;
+        let direction = normalize(add(forward, add(scale(right, u), scale(up, v))));
+
+        let miss = Sample { fog: 1.0, ..Sample::default() };
+        let Some((enter, exit)) = sphere_interval(self.origin, direction, self.bound) else {
+            return miss;
+        };
+        let Some(t) = self.march(self.origin, direction, enter, exit) else {
+            return miss;
+        };
+        let p = add(self.origin, scale(direction, t));
+        let epsilon = (self.cone * t).max(1e-7);
+        let normal = self.normal(p, epsilon);
+        let trap = self.estimate(p).1;
+
+        // Lambert et Blinn–Phong, atténués par l'ombre et l'occlusion.
+        let surface = add(p, scale(normal, 2.0 * epsilon));
+        let diffuse = dot(normal, self.light).max(0.0);
+        let shadow = if diffuse > 0.0 { self.soft_shadow(surface, epsilon) } else { 0.0 };
+        let occlusion = self.occlusion(p, normal, 0.01 * t);
+        let half = normalize(sub(self.light, direction));
+        let specular = 0.4 * dot(normal, half).max(0.0).powi(32) * shadow;
+        let light = 0.25 * occlusion + 0.9 * diffuse * shadow;
+        Sample {
+            hit: true,
+            trap: trap as f32,
+            light: light as f32,
+            specular: specular as f32,
+            fog: (1.0 - (-self.fog_density * t).exp()) as f32,
+        }
+    }
+
+    /// Avance le long du rayon jusqu'à passer sous la taille d'un pixel.
+    fn march(&self, origin: [f64; 3], direction: [f64; 3], start: f64, end: f64) -> Option<f64> {
+        let mut t = start;
+        for _ in 0..self.max_steps {
+            let d = self.distance(add(origin, scale(direction, t)));
+            if d < self.cone * t {
+                return Some(t);
+            }
+            t += d;
+            if t > end {
+                return None;
+            }
+        }
+        None
+    }
+
+    // Gradient par le tétraèdre : quatre évaluations au lieu de six.
+    fn normal(&self, p: [f64; 3], h: f64) -> [f64; 3] {
+        let corners = [[1.0, -1.0, -1.0], [-1.0, -1.0, 1.0], [-1.0, 1.0, -1.0], [1.0, 1.0, 1.0]];
+        let mut n = [0.0; 3];
+        for k in corners {
+            n = add(n, scale(k, self.distance(add(p, scale(k, h)))));
+        }
+        normalize(n)
+    }
+
+    /// Pénombre de Quilez : le rayon vers la lumière qui frôle la surface
+    /// assombrit d'autant plus que la distance restante est petite.
+    fn soft_shadow(&self, origin: [f64; 3], epsilon: f64) -> f64 {
+        if self.shadow_hardness <= 0.0 {
+            return 1.0;
+        }
+        let Some((_, end)) = sphere_interval(origin, self.light, self.bound) else {
+            return 1.0;
+        };
+        let mut result: f64 = 1.0;
+        let mut t = 4.0 * epsilon;
+        for _ in 0..self.max_steps.min(96) {
+            let d = self.distance(add(origin, scale(self.light, t)));
+            if d < epsilon {
+                return 0.0;
+            }
+            result = result.min(self.shadow_hardness * d / t);
+            t += d;
+            if t > end {
+                break;
+            }
+        }
+        result.clamp(0.0, 1.0)
+    }
+
+    /// Occlusion ambiante : écart entre la distance parcourue le long de la
+    /// normale et la distance à la surface, sur cinq points espacés de `step`.
+    fn occlusion(&self, p: [f64; 3], normal: [f64; 3], step: f64) -> f64 {
+        if self.ambient_occlusion <= 0.0 {
+            return 1.0;
+        }
+        let mut occlusion = 0.0;
+        let mut weight = 1.0;
+        for i in 1..=5 {
+            let h = step * i as f64;
+            occlusion += weight * (h - self.distance(add(p, scale(normal, h)))).max(0.0) / step;
+            weight *= 0.5;
+        }
+        (1.0 - self.ambient_occlusion * 0.2 * occlusion).clamp(0.0, 1.0)
+    }
+}
+
+/// Intervalle [entrée, sortie] du rayon dans la sphère englobante, à partir
+/// de t = 0.
+fn sphere_interval(origin: [f64; 3], direction: [f64; 3], radius: f64) -> Option<(f64, f64)> {
+    let b = dot(origin, direction);
+    let c = dot(origin, origin) - radius * radius;
+    let discriminant = b * b - c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let root = discriminant.sqrt();
+    let (enter, exit) = (-b - root, -b + root);
+    (exit > 0.0).then_some((enter.max(0.0), exit))
+}
+
+#[inline]
+fn add(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+#[inline]
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+#[inline]
+fn scale(a: [f64; 3], s: f64) -> [f64; 3] {
+    [a[0] * s, a[1] * s, a[2] * s]
+}
+
+#[inline]
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+#[inline]
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+#[inline]
+fn norm(a: [f64; 3]) -> f64 {
+    dot(a, a).sqrt()
+}
+
+#[inline]
+fn normalize(a: [f64; 3]) -> [f64; 3] {
+    let n = norm(a);
+    if n > 0.0 {
+        scale(a, 1.0 / n)
+    } else {
+        a
+    }
+}
@@ -1,12 +1,50 @@
-use crate::app::{FractalApp, ActiveFractal, ActiveColorScheme, ActiveColorSchemeType};
-use crate::fractal_calculator::FractalCalculator;
-use crate::fractal_types::{MandelbrotSet, JuliaSet, BurningShip, Tricorn, NewtonSet};
+use crate::app::{FractalApp, ActiveFractal, ActiveColorScheme, ActiveColorSchemeType, View};
+use crate::color_cycle::CycleDirection;
+use crate::color_schemes::basin_color;
+use crate::fractal_calculator::{FractalCalculator, QualityLevel};
+use crate::hybrid::{HybridFormula, HybridSet, HybridStep};
+use crate::buddhabrot::{BuddhaMode, BuddhabrotSettings, IterationRange};
+use crate::ifs::{AffineMap, IfsPreset};
+use crate::attractor::{Attractor, AttractorKind};
+use crate::rng::Rng;
+use crate::lsystem::LSystemPreset;
+use crate::kleinian::{KleinianGroup, KleinianPreset};
+use crate::mobius::Mobius;
+use crate::raymarch::Shape;
+use crate::distance::Algebra;
+use crate::mesh::MeshFormat;
+use crate::fractal_types::{default_newton_roots, Exponent, MandelbrotSet, JuliaSet, Multibrot, BurningShip, Tricorn, NewtonSet, NovaSet, PhoenixSet, MagnetSet, AbsFormula, AbsVariant, RootMethod, TranscendentalMap, TranscendentalSet, McMullenSet, LyapunovSet};
+use num::Complex;
 use eframe::egui;
 
 pub struct UserInterface;
 
 impl UserInterface {
     pub fn update(app: &mut FractalApp, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // Le panneau latéral doit être ajouté avant le panneau central.
+        if app.palette_editor.show(ctx) {
+            app.set_gradient(app.palette_editor.gradient.clone());
+        }
+        Self::buddhabrot_window(app, ctx);
+        Self::ifs_window(app, ctx);
+        Self::flame_window(app, ctx);
+        Self::lsystem_window(app, ctx);
+        Self::raymarch_window(app, ctx);
+        Self::mesh_export_window(app, ctx);
+
+        // Cycle des couleurs : seule la colorisation est refaite à chaque image.
+        let (dt, toggle_pause) = ctx.input(|i| (i.stable_dt as f64, i.key_pressed(egui::Key::Space)));
+        if toggle_pause && app.color_cycle.enabled && !ctx.wants_keyboard_input() {
+            app.color_cycle.toggle_pause();
+        }
+        if app.color_cycle.advance(dt) {
+            app.need_recolor = true;
+            ctx.request_repaint();
+        }
+        if app.advance_raymarch_spin(dt) {
+            ctx.request_repaint();
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.horizontal(|ui| {
                 ui.menu_button("Fichier", |ui| {
@@ -17,6 +55,32 @@ impl UserInterface {
                             .set_file_name("fractal.png"));
                         ui.close_menu();
                     }
+                    if ui.button("Exporter en relief 3D…").clicked() {
+                        app.show_mesh_export = true;
+                        ui.close_menu();
+                    }
+                    if ui.button("Enregistrer les paramètres…").clicked() {
+                        ui.close_menu();
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("Paramètres", &["json"])
+                            .set_file_name("fractale.json")
+                            .save_file()
+                        {
+                            app.params_error = app
+                                .save_params(&path)
+                                .err()
+                                .map(|error| format!("Échec de l'enregistrement des paramètres : {}", error));
+                        }
+                    }
+                    if ui.button("Charger des paramètres…").clicked() {
+                        ui.close_menu();
+                        if let Some(path) = rfd::FileDialog::new().add_filter("Paramètres", &["json"]).pick_file() {
+                            app.params_error = app
+                                .load_params(&path)
+                                .err()
+                                .map(|error| format!("Échec du chargement des paramètres : {}", error));
+                        }
+                    }
                 });
 
                 ui.menu_button("Type de fractale", |ui| {
@@ -32,6 +96,21 @@ impl UserInterface {
                             FractalCalculator::<JuliaSet>::new_julia(params, app.params.julia_c)
                         ));
                     }
+                    let multibrot = matches!(app.active_fractal, ActiveFractal::Multibrot(_));
+                    if ui.radio(multibrot && !app.params.conjugate, "Multibrot").clicked() {
+                        app.params.conjugate = false;
+                        let params = app.params.clone();
+                        app.set_fractal_type(ActiveFractal::Multibrot(
+                            FractalCalculator::<Multibrot>::new_multibrot(params)
+                        ));
+                    }
+                    if ui.radio(multibrot && app.params.conjugate, "Multicorn").clicked() {
+                        app.params.conjugate = true;
+                        let params = app.params.clone();
+                        app.set_fractal_type(ActiveFractal::Multibrot(
+                            FractalCalculator::<Multibrot>::new_multibrot(params)
+                        ));
+                    }
                     if ui.radio(matches!(app.active_fractal, ActiveFractal::BurningShip(_)), "Burning Ship").clicked() {
                         let params = app.params.clone();
                         app.set_fractal_type(ActiveFractal::BurningShip(
@@ -44,12 +123,131 @@ impl UserInterface {
                             FractalCalculator::<Tricorn>::new_tricorn(params)
                         ));
                     }
-                    if ui.radio(matches!(app.active_fractal, ActiveFractal::Newton(_)), "Newton").clicked() {
+                    // Méthodes de recherche de racines, qui partagent polynôme et racines.
+                    for method in RootMethod::ALL {
+                        let selected = matches!(app.active_fractal, ActiveFractal::Newton(_)) && app.params.root_method == method;
+                        if ui.radio(selected, method.label()).clicked() {
+                            app.params.root_method = method;
+                            let params = app.params.clone();
+                            app.set_fractal_type(ActiveFractal::Newton(
+                                FractalCalculator::<NewtonSet>::new_newton(params)
+                            ));
+                        }
+                    }
+                    if ui.radio(matches!(app.active_fractal, ActiveFractal::Nova(_)), "Nova").clicked() {
+                        let params = app.params.clone();
+                        app.set_fractal_type(ActiveFractal::Nova(
+                            FractalCalculator::<NovaSet>::new_nova(params)
+                        ));
+                    }
+                    if ui.radio(matches!(app.active_fractal, ActiveFractal::Phoenix(_)), "Phoenix").clicked() {
+                        let params = app.params.clone();
+                        app.set_fractal_type(ActiveFractal::Phoenix(
+                            FractalCalculator::<PhoenixSet>::new_phoenix(params)
+                        ));
+                    }
+                    ui.menu_button("Famille Burning Ship", |ui| {
+                        for variant in AbsVariant::ALL {
+                            let selected = matches!(app.active_fractal, ActiveFractal::AbsVariant(_)) && app.params.abs_variant == variant;
+                            if ui.radio(selected, variant.label()).clicked() {
+                                app.params.abs_variant = variant;
+                                let params = app.params.clone();
+                                app.set_fractal_type(ActiveFractal::AbsVariant(
+                                    FractalCalculator::<AbsFormula>::new_abs_variant(params)
+                                ));
+                            }
+                        }
+                    });
+                    ui.menu_button("Transcendantes", |ui| {
+                        for map in TranscendentalMap::ALL {
+                            let selected = matches!(app.active_fractal, ActiveFractal::Transcendental(_)) && app.params.transcendental == map;
+                            if ui.radio(selected, map.label()).clicked() {
+                                app.params.transcendental = map;
+                                let params = app.params.clone();
+                                app.set_fractal_type(ActiveFractal::Transcendental(
+                                    FractalCalculator::<TranscendentalSet>::new_transcendental(params)
+                                ));
+                            }
+                        }
+                    });
+                    if ui.radio(matches!(app.active_fractal, ActiveFractal::McMullen(_)), "McMullen zⁿ + λ/zᵐ").clicked() {
+                        let params = app.params.clone();
+                        app.set_fractal_type(ActiveFractal::McMullen(
+                            FractalCalculator::<McMullenSet>::new_mcmullen(params)
+                        ));
+                    }
+                    if ui.radio(matches!(app.active_fractal, ActiveFractal::Lyapunov(_)), "Lyapunov (Markus)").clicked() {
+                        // Le plan (a, b) intéressant est le carré [2, 4]².
+                        app.set_own_plane_view(Complex::new(3.0, 3.0), 0.5);
                         let params = app.params.clone();
-                        app.set_fractal_type(ActiveFractal::Newton(
-                            FractalCalculator::<NewtonSet>::new_newton(params)
+                        app.set_fractal_type(ActiveFractal::Lyapunov(
+                            FractalCalculator::<LyapunovSet>::new_lyapunov(params)
                         ));
                     }
+                    if ui.radio(matches!(app.active_fractal, ActiveFractal::Formula(_)), "Formule…").clicked() {
+                        app.apply_formula();
+                    }
+                    if ui.radio(matches!(app.active_fractal, ActiveFractal::Hybrid(_)), "Hybride…").clicked() {
+                        if let Some(fractal) = ActiveFractal::build("hybrid", app.params.clone()) {
+                            app.set_fractal_type(fractal);
+                        }
+                    }
+                    ui.menu_button("Attracteurs étranges", |ui| {
+                        for kind in AttractorKind::ALL {
+                            let selected = matches!(app.active_fractal, ActiveFractal::Attractor(_)) && app.params.attractor.kind == kind;
+                            if ui.radio(selected, kind.label()).clicked() {
+                                app.params.attractor = Attractor::new(kind);
+                                app.set_own_plane_view(Complex::new(0.0, 0.0), 1.0);
+                                if let Some(fractal) = ActiveFractal::build("attractor", app.params.clone()) {
+                                    app.set_fractal_type(fractal);
+                                }
+                            }
+                        }
+                    });
+                    ui.menu_button("Groupes de Klein", |ui| {
+                        for preset in KleinianPreset::ALL {
+                            let selected = matches!(app.active_fractal, ActiveFractal::Kleinian(_)) && app.params.kleinian == preset.group();
+                            if ui.radio(selected, preset.label()).clicked() {
+                                app.params.kleinian = preset.group();
+                                let (center, zoom) = preset.view();
+                                app.set_own_plane_view(center, zoom);
+                                if let Some(fractal) = ActiveFractal::build("kleinian", app.params.clone()) {
+                                    app.set_fractal_type(fractal);
+                                }
+                            }
+                        }
+                    });
+                    for magnet in MagnetSet::ALL {
+                        let selected = matches!(app.active_fractal, ActiveFractal::Magnet(_)) && app.params.magnet == magnet;
+                        if ui.radio(selected, magnet.label()).clicked() {
+                            app.params.magnet = magnet;
+                            let params = app.params.clone();
+                            app.set_fractal_type(ActiveFractal::Magnet(
+                                FractalCalculator::<MagnetSet>::new_magnet(params)
+                            ));
+                        }
+                    }
+                    ui.separator();
+                    if ui.button("Buddhabrot / Nebulabrot…").clicked() {
+                        app.open_view(View::Buddhabrot);
+                        ui.close_menu();
+                    }
+                    if ui.button("Système de fonctions itérées…").clicked() {
+                        app.open_view(View::Ifs);
+                        ui.close_menu();
+                    }
+                    if ui.button("Flamme fractale…").clicked() {
+                        app.open_view(View::Flame);
+                        ui.close_menu();
+                    }
+                    if ui.button("L-système…").clicked() {
+                        app.open_view(View::LSystem);
+                        ui.close_menu();
+                    }
+                    if ui.button("Fractale 3D…").clicked() {
+                        app.open_view(View::RayMarch);
+                        ui.close_menu();
+                    }
                 });
 
                 ui.menu_button("Palette de couleurs", |ui| {
@@ -68,7 +266,60 @@ impl UserInterface {
                     if ui.radio(matches!(app.active_color_scheme, ActiveColorScheme::Grayscale(_)), "Grayscale").clicked() {
                         app.set_color_scheme(ActiveColorSchemeType::Grayscale);
                     }
+                    if ui.radio(matches!(app.active_color_scheme, ActiveColorScheme::Gradient(_)), "Dégradé").clicked() {
+                        app.set_gradient(app.palette_editor.gradient.clone());
+                    }
+                    ui.separator();
+                    if ui.button("Éditeur de palette…").clicked() {
+                        app.open_palette_editor();
+                        app.set_gradient(app.palette_editor.gradient.clone());
+                        ui.close_menu();
+                    }
+                });
+
+                ui.menu_button("Animation", |ui| {
+                    if ui.checkbox(&mut app.color_cycle.enabled, "Cycle des couleurs").changed() {
+                        app.color_cycle.paused = false;
+                        app.need_recolor = true;
+                    }
+                    ui.horizontal(|ui| {
+                        ui.label("Vitesse :");
+                        ui.add(egui::Slider::new(&mut app.color_cycle.speed, 0.01..=2.0).logarithmic(true).suffix(" tour/s"));
+                    });
+                    ui.horizontal(|ui| {
+                        ui.radio_value(&mut app.color_cycle.direction, CycleDirection::Forward, "Avant");
+                        ui.radio_value(&mut app.color_cycle.direction, CycleDirection::Backward, "Arrière");
+                    });
+                    let pause_label = if app.color_cycle.paused { "Reprendre (Espace)" } else { "Pause (Espace)" };
+                    if ui.button(pause_label).clicked() {
+                        app.color_cycle.toggle_pause();
+                    }
+                    ui.separator();
+                    if ui.button("Exporter la boucle (GIF)…").clicked() {
+                        let dialog = rfd::FileDialog::new()
+                            .add_filter("GIF", &["gif"])
+                            .set_file_name("cycle.gif");
+                        if let Some(path) = dialog.save_file() {
+                            app.color_cycle_error = app
+                                .export_color_cycle(&path, 60, 40)
+                                .err()
+                                .map(|err| format!("Échec de l'export de l'animation : {}", err));
+                        }
+                        if app.color_cycle_error.is_none() {
+                            ui.close_menu();
+                        }
+                    }
+                    if let Some(error) = &app.color_cycle_error {
+                        ui.colored_label(egui::Color32::RED, error);
+                    }
                 });
+
+                if let Some(error) = &app.params_error {
+                    ui.colored_label(egui::Color32::RED, error);
+                    if ui.small_button("✕").clicked() {
+                        app.params_error = None;
+                    }
+                }
             });
 
             // Contrôles
@@ -76,40 +327,49 @@ impl UserInterface {
                 ui.label("Zoom:");
                 if ui.add(egui::Slider::new(&mut app.params.zoom, 0.1..=10.0)).changed() {
                     // Mettre à jour le calculateur avec les nouveaux paramètres
-                    let params = app.params.clone();
-                    app.active_fractal = match &app.active_fractal {
-                        ActiveFractal::Mandelbrot(_) => {
-                            ActiveFractal::Mandelbrot(FractalCalculator::<MandelbrotSet>::new_mandelbrot(params))
-                        },
-                        ActiveFractal::Julia(_) => {
-                            ActiveFractal::Julia(FractalCalculator::<JuliaSet>::new_julia(params, app.params.julia_c))
-                        },
-                        ActiveFractal::BurningShip(_) => {
-                            ActiveFractal::BurningShip(FractalCalculator::<BurningShip>::new_burning_ship(params))
-                        },
-                        ActiveFractal::Tricorn(_) => {
-                            ActiveFractal::Tricorn(FractalCalculator::<Tricorn>::new_tricorn(params))
-                        },
-                        ActiveFractal::Newton(_) => {
-                            ActiveFractal::Newton(FractalCalculator::<NewtonSet>::new_newton(params))
-                        },
-                    };
-                    app.need_update = true;
+                    app.rebuild_fractal();
                 }
                 
                 ui.label("Iterations:");
                 if ui.add(egui::Slider::new(&mut app.params.max_iterations, 10..=1000)).changed() {
-                    app.need_update = true;
+                    app.rebuild_fractal();
+                }
+                if matches!(app.active_fractal, ActiveFractal::Mandelbrot(_) | ActiveFractal::Julia(_))
+                    && ui.checkbox(&mut app.params.distance_estimation, "Estimation de distance").changed()
+                {
+                    app.rebuild_fractal();
                 }
             });
 
+            match app.active_fractal {
+                ActiveFractal::Multibrot(_) => Self::multibrot_controls(app, ui),
+                ActiveFractal::Nova(_) => Self::nova_controls(app, ui),
+                ActiveFractal::Phoenix(_) => Self::phoenix_controls(app, ui),
+                ActiveFractal::AbsVariant(_) => Self::abs_variant_controls(app, ui),
+                ActiveFractal::Transcendental(_) => Self::transcendental_controls(app, ui),
+                ActiveFractal::McMullen(_) => Self::mcmullen_controls(app, ui),
+                ActiveFractal::Lyapunov(_) => Self::lyapunov_controls(app, ui),
+                ActiveFractal::Formula(_) => Self::formula_controls(app, ui),
+                ActiveFractal::Hybrid(_) => Self::hybrid_controls(app, ui),
+                ActiveFractal::Attractor(_) => Self::attractor_controls(app, ui),
+                ActiveFractal::Kleinian(_) => Self::kleinian_controls(app, ui),
+                _ => {}
+            }
+
+            if matches!(app.active_fractal, ActiveFractal::Newton(_) | ActiveFractal::Nova(_)) {
+                Self::newton_controls(app, ui);
+            }
+
             // Gestion du zoom à la molette
             if ui.ui_contains_pointer() {
                 ui.input(|i| {
                     let scroll_delta = i.scroll_delta.y;
                     if scroll_delta != 0.0 {
                         let zoom_factor = if scroll_delta > 0.0 { 1.1 } else { 0.9 };
-                        if let Some(mouse_pos) = i.pointer.hover_pos() {
+                        if app.view == View::RayMarch {
+                            app.raymarch_settings.camera.dolly(zoom_factor);
+                            app.need_update = true;
+                        } else if let Some(mouse_pos) = i.pointer.hover_pos() {
                             app.handle_zoom(mouse_pos.x, mouse_pos.y, zoom_factor);
                         }
                     }
@@ -140,6 +400,17 @@ impl UserInterface {
             if app.need_update {
                 app.generate_fractal();
                 app.need_update = false;
+            } else if app.need_recolor {
+                app.recolor();
+            }
+            // Affichage progressif : un lot par image tant que le rendu tourne.
+            if app.buddhabrot.is_some() && app.buddhabrot_running {
+                app.step_buddhabrot();
+                ui.ctx().request_repaint();
+            }
+            if app.raymarcher.as_ref().is_some_and(|renderer| !renderer.is_done()) {
+                app.step_raymarch();
+                ui.ctx().request_repaint();
             }
 
             // Créer et afficher l'image
@@ -153,8 +424,1079 @@ impl UserInterface {
                     color_image,
                     Default::default()
                 );
-                ui.image(&texture);
+                let response = ui.image(&texture);
+                match app.view {
+                    View::Ifs => Self::ifs_handles(app, ui, response.rect),
+                    View::RayMarch => Self::raymarch_orbit(app, ui, response.rect),
+                    View::Fractal if matches!(app.active_fractal, ActiveFractal::Newton(_) | ActiveFractal::Nova(_)) => {
+                        Self::newton_root_handles(app, ui, response.rect);
+                    }
+                    _ => {}
+                }
             }
         });
     }
+
+    fn buddhabrot_window(app: &mut FractalApp, ctx: &egui::Context) {
+        if app.view != View::Buddhabrot {
+            return;
+        }
+        let mut open = true;
+        egui::Window::new("Buddhabrot / Nebulabrot")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let settings = &mut app.buddhabrot_settings;
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut settings.mode, BuddhaMode::Buddhabrot, "Buddhabrot");
+                    ui.radio_value(&mut settings.mode, BuddhaMode::AntiBuddhabrot, "Anti-Buddhabrot");
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Préréglages :");
+                    if ui.button("Buddhabrot").clicked() {
+                        settings.channels = [IterationRange { min: 20, max: 1000 }; 3];
+                    }
+                    if ui.button("Nebulabrot").clicked() {
+                        settings.channels = BuddhabrotSettings::default().channels;
+                    }
+                });
+                // En mode Anti-Buddhabrot seul le maximum compte : c'est la
+                // limite au-delà de laquelle une orbite est jugée bornée.
+                egui::Grid::new("plages_buddhabrot").show(ui, |ui| {
+                    for (name, range) in ["Rouge", "Vert", "Bleu"].iter().zip(&mut settings.channels) {
+                        ui.label(*name);
+                        ui.add(egui::DragValue::new(&mut range.min).clamp_range(0..=range.max).prefix("min "));
+                        ui.add(egui::DragValue::new(&mut range.max).clamp_range(1..=100_000).prefix("max "));
+                        ui.end_row();
+                    }
+                });
+                ui.checkbox(&mut settings.metropolis, "Échantillonnage de Metropolis–Hastings");
+                ui.horizontal(|ui| {
+                    ui.label("Graine :");
+                    ui.add(egui::DragValue::new(&mut settings.seed));
+                    ui.label("Lot :");
+                    ui.add(egui::DragValue::new(&mut settings.samples_per_batch).clamp_range(1_000..=10_000_000).speed(1000));
+                });
+                let gamma_changed = ui
+                    .add(egui::Slider::new(&mut settings.gamma, 0.5..=5.0).text("Gamma"))
+                    .changed();
+                if gamma_changed {
+                    // Le gamma n'agit que sur l'affichage : inutile de recommencer.
+                    let gamma = settings.gamma;
+                    if let Some(renderer) = &mut app.buddhabrot {
+                        renderer.settings.gamma = gamma;
+                        app.image_data = renderer.image();
+                    }
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Démarrer").clicked() {
+                        app.start_buddhabrot();
+                    }
+                    if app.buddhabrot.is_some() {
+                        let label = if app.buddhabrot_running { "Pause" } else { "Reprendre" };
+                        if ui.button(label).clicked() {
+                            app.buddhabrot_running = !app.buddhabrot_running;
+                        }
+                        if ui.button("Réinitialiser").clicked() {
+                            app.reset_buddhabrot();
+                        }
+                    }
+                });
+                if let Some(renderer) = &app.buddhabrot {
+                    ui.label(format!("{} échantillons", renderer.samples()));
+                }
+            });
+        // Fermer la fenêtre abandonne le rendu.
+        if !open {
+            app.close_view();
+        }
+    }
+
+    fn ifs_window(app: &mut FractalApp, ctx: &egui::Context) {
+        if app.view != View::Ifs {
+            return;
+        }
+        let mut open = true;
+        egui::Window::new("Système de fonctions itérées")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let mut changed = false;
+                ui.horizontal_wrapped(|ui| {
+                    for preset in IfsPreset::ALL {
+                        if ui.button(preset.label()).clicked() {
+                            app.set_ifs(preset.ifs());
+                        }
+                    }
+                });
+                ui.separator();
+
+                let map_count = app.ifs.maps.len();
+                let mut removed = None;
+                egui::Grid::new("transformations_ifs").show(ui, |ui| {
+                    ui.label("");
+                    ui.label("Probabilité");
+                    ui.label("Couleur");
+                    ui.end_row();
+                    for (i, map) in app.ifs.maps.iter_mut().enumerate() {
+                        ui.label(format!("f{}", i + 1));
+                        changed |= ui
+                            .add(egui::DragValue::new(&mut map.probability).clamp_range(0.0..=1.0).speed(0.005))
+                            .changed();
+                        changed |= ui.add(egui::Slider::new(&mut map.color, 0.0..=1.0)).changed();
+                        if map_count > 1 && ui.small_button("✕").clicked() {
+                            removed = Some(i);
+                        }
+                        ui.end_row();
+                    }
+                });
+                if let Some(i) = removed {
+                    app.ifs.maps.remove(i);
+                    changed = true;
+                }
+                ui.horizontal(|ui| {
+                    if ui.button("Ajouter").clicked() {
+                        let mut map = AffineMap::new([0.5, 0.0, 0.0, 0.5], [0.0, 0.0], 1.0 / (map_count + 1) as f64);
+                        map.color = 0.5;
+                        app.ifs.maps.push(map);
+                        changed = true;
+                    }
+                    if ui.button("Équilibrer les probabilités").clicked() {
+                        app.ifs.balance_probabilities();
+                        changed = true;
+                    }
+                    if ui.button("Recadrer").clicked() {
+                        app.ifs_view = None;
+                        changed = true;
+                    }
+                });
+                ui.label("Glisser les sommets des triangles pour modifier les transformations.");
+
+                ui.separator();
+                let settings = &mut app.ifs_settings;
+                ui.horizontal(|ui| {
+                    ui.label("Points :");
+                    changed |= ui
+                        .add(egui::DragValue::new(&mut settings.samples).clamp_range(10_000..=100_000_000).speed(10_000))
+                        .changed();
+                    ui.label("Graine :");
+                    changed |= ui.add(egui::DragValue::new(&mut settings.seed)).changed();
+                });
+                changed |= ui.add(egui::Slider::new(&mut settings.gamma, 0.5..=5.0).text("Gamma")).changed();
+                if changed {
+                    app.need_update = true;
+                }
+            });
+        if !open {
+            app.close_view();
+        }
+    }
+
+    fn mesh_export_window(app: &mut FractalApp, ctx: &egui::Context) {
+        if !app.show_mesh_export {
+            return;
+        }
+        let mut open = true;
+        egui::Window::new("Relief 3D")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let settings = &mut app.mesh_settings;
+                ui.horizontal(|ui| {
+                    for format in MeshFormat::ALL {
+                        ui.radio_value(&mut settings.format, format, format.label());
+                    }
+                });
+                egui::Grid::new("relief_3d").show(ui, |ui| {
+                    ui.label("Largeur :");
+                    ui.add(egui::DragValue::new(&mut settings.size).clamp_range(1.0..=2000.0).speed(0.5).suffix(" mm"));
+                    ui.label("Hauteur :");
+                    ui.add(egui::DragValue::new(&mut settings.height).clamp_range(0.0..=500.0).speed(0.1).suffix(" mm"));
+                    ui.end_row();
+                    ui.label("Socle :");
+                    ui.add(egui::DragValue::new(&mut settings.base).clamp_range(0.0..=100.0).speed(0.05).suffix(" mm"));
+                    ui.label("Pas :");
+                    ui.add(egui::DragValue::new(&mut settings.step).clamp_range(1..=64).suffix(" px"));
+                    ui.end_row();
+                    ui.label("Tolérance :");
+                    ui.add(egui::DragValue::new(&mut settings.tolerance).clamp_range(0.0..=10.0).speed(0.005).suffix(" mm"));
+                    ui.end_row();
+                });
+                ui.checkbox(&mut settings.solid, "Solide fermé (parois et fond)");
+                ui.checkbox(&mut settings.invert, "Inverser le relief");
+                if matches!(settings.format, MeshFormat::StlAscii | MeshFormat::StlBinary) {
+                    ui.label("Le STL ne conserve pas les couleurs.");
+                }
+
+                ui.separator();
+                let available = app.can_export_mesh();
+                if !available {
+                    ui.label("Disponible pour les fractales calculées par itérations.");
+                }
+                if ui.add_enabled(available, egui::Button::new("Exporter…")).clicked() {
+                    let extension = app.mesh_settings.format.extension();
+                    if let Some(path) = rfd::FileDialog::new()
+                        .add_filter(app.mesh_settings.format.label(), &[extension])
+                        .set_file_name(format!("relief.{}", extension))
+                        .save_file()
+                    {
+                        app.mesh_status = Some(match app.export_mesh(&path) {
+                            Ok(()) => format!("Relief enregistré : {}", path.display()),
+                            Err(error) => format!("Échec de l'export du relief : {}", error),
+                        });
+                    }
+                }
+                if let Some(status) = &app.mesh_status {
+                    ui.label(status);
+                }
+            });
+        if !open {
+            app.show_mesh_export = false;
+        }
+    }
+
+    fn raymarch_window(app: &mut FractalApp, ctx: &egui::Context) {
+        if app.view != View::RayMarch {
+            return;
+        }
+        let mut open = true;
+        egui::Window::new("Fractale 3D")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let mut changed = false;
+                let settings = &mut app.raymarch_settings;
+                ui.horizontal(|ui| {
+                    for shape in Shape::ALL {
+                        if ui.radio(settings.shape.label() == shape.label(), shape.label()).clicked() {
+                            settings.shape = shape;
+                            settings.camera = shape.default_camera();
+                            changed = true;
+                        }
+                    }
+                });
+                ui.horizontal(|ui| match &mut settings.shape {
+                    Shape::Mandelbulb { power } => {
+                        ui.label("Puissance :");
+                        changed |= ui.add(egui::DragValue::new(power).clamp_range(2.0..=16.0).speed(0.05)).changed();
+                    }
+                    Shape::Mandelbox { scale, min_radius, fixed_radius } => {
+                        ui.label("Échelle :");
+                        changed |= ui.add(egui::DragValue::new(scale).clamp_range(-4.0..=4.0).speed(0.01)).changed();
+                        ui.label("Rayon min. :");
+                        changed |= ui.add(egui::DragValue::new(min_radius).clamp_range(0.01..=2.0).speed(0.005)).changed();
+                        ui.label("Rayon fixe :");
+                        changed |= ui.add(egui::DragValue::new(fixed_radius).clamp_range(0.1..=4.0).speed(0.005)).changed();
+                    }
+                    Shape::Julia4D { algebra, c, .. } => {
+                        ui.label("c :");
+                        let units = match algebra {
+                            Algebra::Quaternion => ["", "i ", "j ", "k "],
+                            Algebra::Bicomplex => ["", "i ", "j ", "ij "],
+                        };
+                        for (value, unit) in c.iter_mut().zip(units) {
+                            changed |= ui.add(egui::DragValue::new(value).clamp_range(-2.0..=2.0).speed(0.002).prefix(unit)).changed();
+                        }
+                    }
+                });
+                let mut export_rotation = false;
+                if let Shape::Julia4D { slice, .. } = &mut settings.shape {
+                    egui::Grid::new("coupe_4d").show(ui, |ui| {
+                        ui.label("Coupe en w :");
+                        changed |= ui.add(egui::DragValue::new(&mut slice.offset).clamp_range(-2.0..=2.0).speed(0.002)).changed();
+                        ui.end_row();
+                        ui.label("Rotation :");
+                        for (angle, plane) in slice.rotation.iter_mut().zip(["xw ", "yw ", "zw "]) {
+                            changed |= ui.add(egui::DragValue::new(angle).speed(0.5).prefix(plane).suffix("°")).changed();
+                        }
+                        ui.end_row();
+                        ui.label("Animation :");
+                        ui.add(egui::DragValue::new(&mut app.raymarch_spin).clamp_range(-90.0..=90.0).speed(0.1).suffix(" °/s"));
+                        for (plane, name) in ["xw", "yw", "zw"].iter().enumerate() {
+                            ui.radio_value(&mut app.raymarch_spin_plane, plane, *name);
+                        }
+                        ui.end_row();
+                    });
+                    export_rotation = ui.button("Exporter un tour complet (PNG)…").clicked();
+                }
+                ui.separator();
+
+                egui::Grid::new("camera_3d").show(ui, |ui| {
+                    let camera = &mut settings.camera;
+                    for (name, point) in [("Position :", &mut camera.position), ("Cible :", &mut camera.target)] {
+                        ui.label(name);
+                        for (value, axis) in point.iter_mut().zip(["x ", "y ", "z "]) {
+                            changed |= ui.add(egui::DragValue::new(value).speed(0.01).prefix(axis)).changed();
+                        }
+                        ui.end_row();
+                    }
+                    ui.label("Champ de vision :");
+                    changed |= ui.add(egui::Slider::new(&mut camera.fov, 5.0..=120.0).suffix("°")).changed();
+                    ui.end_row();
+                });
+                ui.label("Glisser sur l'image pour tourner autour de la cible, molette pour avancer.");
+                ui.separator();
+
+                egui::Grid::new("rendu_3d").show(ui, |ui| {
+                    ui.label("Itérations :");
+                    changed |= ui.add(egui::DragValue::new(&mut settings.iterations).clamp_range(1..=100)).changed();
+                    ui.label("Pas max. :");
+                    changed |= ui.add(egui::DragValue::new(&mut settings.max_steps).clamp_range(10..=2000)).changed();
+                    ui.end_row();
+                    ui.label("Précision :");
+                    changed |= ui.add(egui::DragValue::new(&mut settings.detail).clamp_range(0.05..=4.0).speed(0.01).suffix(" px")).changed();
+                    ui.end_row();
+                    ui.label("Lumière :");
+                    changed |= ui.add(egui::DragValue::new(&mut settings.light_azimuth).speed(0.5).prefix("azimut ").suffix("°")).changed();
+                    changed |= ui.add(egui::DragValue::new(&mut settings.light_elevation).clamp_range(-90.0..=90.0).speed(0.5).prefix("hauteur ").suffix("°")).changed();
+                    ui.end_row();
+                    ui.label("Ombres douces :");
+                    changed |= ui.add(egui::DragValue::new(&mut settings.shadow_hardness).clamp_range(0.0..=128.0).speed(0.1)).changed();
+                    ui.label("Occlusion :");
+                    changed |= ui.add(egui::DragValue::new(&mut settings.ambient_occlusion).clamp_range(0.0..=1.0).speed(0.01)).changed();
+                    ui.end_row();
+                    ui.label("Brouillard :");
+                    changed |= ui.add(egui::DragValue::new(&mut settings.fog_density).clamp_range(0.0..=2.0).speed(0.002)).changed();
+                    // La couleur du brouillard ne demande pas de relancer les rayons.
+                    if ui.color_edit_button_srgb(&mut settings.fog_color).changed() {
+                        let fog_color = settings.fog_color;
+                        if let Some(renderer) = &mut app.raymarcher {
+                            renderer.settings.fog_color = fog_color;
+                        }
+                        app.need_recolor = true;
+                    }
+                    ui.end_row();
+                });
+
+                ui.separator();
+                if let Some(renderer) = &app.raymarcher {
+                    ui.add(egui::ProgressBar::new(renderer.progress() as f32).show_percentage());
+                }
+                if let Some(error) = &app.raymarch_error {
+                    ui.colored_label(egui::Color32::RED, error);
+                }
+                if export_rotation {
+                    if let Some(directory) = rfd::FileDialog::new().pick_folder() {
+                        app.raymarch_error = app
+                            .export_raymarch_rotation(&directory, 72)
+                            .err()
+                            .map(|error| format!("Échec de l'export de la rotation : {}", error));
+                    }
+                }
+                if changed {
+                    app.need_update = true;
+                }
+            });
+        if !open {
+            app.close_view();
+        }
+    }
+
+    /// Glisser sur l'image fait tourner la caméra autour de sa cible ; le
+    /// rendu repart à chaque déplacement, sa première passe est grossière.
+    fn raymarch_orbit(app: &mut FractalApp, ui: &mut egui::Ui, rect: egui::Rect) {
+        let response = ui.interact(rect, ui.id().with("raymarch_orbit"), egui::Sense::drag());
+        let delta = response.drag_delta();
+        if delta != egui::Vec2::ZERO {
+            app.raymarch_settings.camera.orbit(-delta.x as f64 * 0.01, delta.y as f64 * 0.01);
+            app.need_update = true;
+        }
+    }
+
+    fn flame_window(app: &mut FractalApp, ctx: &egui::Context) {
+        if app.view != View::Flame {
+            return;
+        }
+        let mut open = true;
+        egui::Window::new("Flamme fractale")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Importer un fichier .flame…").clicked() {
+                        if let Some(path) = rfd::FileDialog::new().add_filter("Flammes", &["flame", "xml"]).pick_file() {
+                            if let Err(error) = app.import_flames(&path) {
+                                app.flame_message = Some(format!("Échec de l'import : {}", error));
+                            }
+                        }
+                    }
+                    if ui.button("Rendre en taille réelle…").clicked() {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("PNG", &["png"])
+                            .set_file_name(format!("{}.png", app.flame.name))
+                            .save_file()
+                        {
+                            if let Err(error) = app.save_flame_render(&path) {
+                                app.flame_message = Some(format!("Échec de l'enregistrement : {}", error));
+                            }
+                        }
+                    }
+                });
+                if !app.flame_library.is_empty() {
+                    let mut selected = None;
+                    egui::ComboBox::from_label("Flamme")
+                        .selected_text(app.flame.name.clone())
+                        .show_ui(ui, |ui| {
+                            for (i, imported) in app.flame_library.iter().enumerate() {
+                                if ui.selectable_label(imported.flame.name == app.flame.name, &imported.flame.name).clicked() {
+                                    selected = Some(i);
+                                }
+                            }
+                        });
+                    if let Some(i) = selected {
+                        app.select_flame(i);
+                    }
+                }
+                if let Some(message) = &app.flame_message {
+                    ui.colored_label(egui::Color32::YELLOW, message);
+                }
+                ui.label(format!(
+                    "{} transformations{}, {} × {}",
+                    app.flame.xforms.len(),
+                    if app.flame.final_xform.is_some() { " + finale" } else { "" },
+                    app.flame.size.0,
+                    app.flame.size.1
+                ));
+
+                ui.separator();
+                let flame = &mut app.flame;
+                let mut changed = false;
+                // Un rendu peut prendre plusieurs secondes : on attend que le
+                // curseur soit relâché.
+                let committed = |response: egui::Response| response.drag_released() || (response.changed() && !response.dragged());
+                changed |= committed(ui.add(egui::Slider::new(&mut flame.quality, 1.0..=2000.0).logarithmic(true).text("Qualité (points/pixel)")));
+                changed |= committed(ui.add(egui::Slider::new(&mut flame.oversample, 1..=4).text("Suréchantillonnage")));
+                changed |= committed(ui.add(egui::Slider::new(&mut flame.estimator_radius, 0.0..=20.0).text("Rayon d'estimation")));
+                changed |= committed(ui.add(egui::Slider::new(&mut flame.brightness, 0.1..=20.0).logarithmic(true).text("Luminosité")));
+                changed |= committed(ui.add(egui::Slider::new(&mut flame.gamma, 1.0..=8.0).text("Gamma")));
+                changed |= committed(ui.add(egui::Slider::new(&mut flame.vibrancy, 0.0..=1.0).text("Vibrance")));
+                ui.horizontal(|ui| {
+                    ui.label("Graine :");
+                    changed |= ui.add(egui::DragValue::new(&mut flame.seed)).changed();
+                    if ui.button("Utiliser la palette active").clicked() {
+                        flame.set_palette(app.active_color_scheme.as_scheme());
+                        changed = true;
+                    }
+                });
+                if changed {
+                    app.need_update = true;
+                }
+            });
+        if !open {
+            app.close_view();
+        }
+    }
+
+    fn lsystem_window(app: &mut FractalApp, ctx: &egui::Context) {
+        if app.view != View::LSystem {
+            return;
+        }
+        let mut open = true;
+        egui::Window::new("L-système")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let mut changed = false;
+                ui.horizontal_wrapped(|ui| {
+                    for preset in LSystemPreset::ALL {
+                        if ui.button(preset.label()).clicked() {
+                            app.lsystem = preset.lsystem();
+                            changed = true;
+                        }
+                    }
+                });
+                ui.separator();
+
+                let lsystem = &mut app.lsystem;
+                ui.horizontal(|ui| {
+                    ui.label("Axiome :");
+                    ui.add(egui::TextEdit::singleline(&mut lsystem.axiom).code_editor().desired_width(300.0));
+                });
+                ui.label("Règles (F -> …, F -(0.5)-> … stochastique, A(l) : l > 1 -> … paramétrique) :");
+                let editor = ui.add(
+                    egui::TextEdit::multiline(&mut lsystem.rules)
+                        .code_editor()
+                        .desired_rows(4)
+                        .desired_width(380.0),
+                );
+                // Le texte n'est dérivé qu'à la demande, pas à chaque caractère.
+                let submit = editor.has_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter) && i.modifiers.ctrl);
+                if ui.button("Appliquer (Ctrl+Entrée)").clicked() || submit {
+                    changed = true;
+                }
+                if let Some(error) = &app.lsystem_error {
+                    ui.colored_label(egui::Color32::RED, error.to_string());
+                }
+
+                ui.separator();
+                let lsystem = &mut app.lsystem;
+                egui::Grid::new("tortue_lsysteme").show(ui, |ui| {
+                    ui.label("Profondeur :");
+                    changed |= ui.add(egui::DragValue::new(&mut lsystem.iterations).clamp_range(0..=16)).changed();
+                    ui.label("Angle :");
+                    changed |= ui.add(egui::DragValue::new(&mut lsystem.angle).speed(0.1).suffix("°")).changed();
+                    ui.end_row();
+                    ui.label("Direction initiale :");
+                    changed |= ui.add(egui::DragValue::new(&mut lsystem.heading).speed(0.5).suffix("°")).changed();
+                    ui.label("Pas :");
+                    changed |= ui.add(egui::DragValue::new(&mut lsystem.step).clamp_range(0.01..=100.0).speed(0.01)).changed();
+                    ui.end_row();
+                    ui.label("Épaisseur :");
+                    changed |= ui.add(egui::DragValue::new(&mut lsystem.line_width).clamp_range(0.1..=50.0).speed(0.05).suffix(" px")).changed();
+                    ui.label("Facteur « ! » :");
+                    changed |= ui.add(egui::DragValue::new(&mut lsystem.width_factor).clamp_range(0.05..=2.0).speed(0.005)).changed();
+                    ui.end_row();
+                    ui.label("Graine :");
+                    changed |= ui.add(egui::DragValue::new(&mut lsystem.seed)).changed();
+                    ui.end_row();
+                });
+
+                ui.separator();
+                if ui.button("Exporter en SVG…").clicked() {
+                    if let Some(path) = rfd::FileDialog::new()
+                        .add_filter("SVG", &["svg"])
+                        .set_file_name("lsysteme.svg")
+                        .save_file()
+                    {
+                        app.lsystem_error = app.save_lsystem_svg(&path).err();
+                    }
+                }
+                if changed {
+                    app.need_update = true;
+                }
+            });
+        if !open {
+            app.close_view();
+        }
+    }
+
+    /// Triangles images du triangle unité, un par transformation : le sommet
+    /// plein est l'image de l'origine, les deux autres celles des axes.
+    fn ifs_handles(app: &mut FractalApp, ui: &mut egui::Ui, rect: egui::Rect) {
+        let (width, height) = app.params.size;
+        let view = app.ifs_view();
+        let to_screen = |p: [f64; 2]| {
+            let (x, y) = view.to_pixel(p, width, height);
+            rect.left_top() + egui::vec2(x as f32, y as f32)
+        };
+        let to_plane = |p: egui::Pos2| view.to_plane(((p.x - rect.left()) as f64, (p.y - rect.top()) as f64), width, height);
+
+        let mut preview = None;
+        for i in 0..app.ifs.maps.len() {
+            let mut triangle = app.ifs.maps[i].triangle();
+            for vertex in 0..3 {
+                let handle = egui::Rect::from_center_size(to_screen(triangle[vertex]), egui::vec2(12.0, 12.0));
+                let response = ui.interact(handle, ui.id().with(("ifs_vertex", i, vertex)), egui::Sense::drag());
+                if response.dragged() {
+                    if let Some(pointer) = response.interact_pointer_pos() {
+                        triangle[vertex] = to_plane(pointer);
+                        app.ifs.maps[i].set_triangle(triangle);
+                        preview = Some(true);
+                    }
+                }
+                if response.drag_released() {
+                    preview = Some(false);
+                }
+            }
+
+            let (r, g, b) = app.active_color_scheme.as_scheme().get_color(app.ifs.maps[i].color);
+            let color = egui::Color32::from_rgb(r, g, b);
+            let points: Vec<egui::Pos2> = triangle.iter().map(|&p| to_screen(p)).collect();
+            ui.painter().add(egui::Shape::closed_line(points.clone(), egui::Stroke::new(1.5, color)));
+            ui.painter().circle(points[0], 5.0, color, egui::Stroke::new(1.5, egui::Color32::WHITE));
+            for point in &points[1..] {
+                ui.painter().circle_stroke(*point, 5.0, egui::Stroke::new(1.5, color));
+            }
+        }
+
+        // Aperçu allégé pendant le glissement, rendu complet au relâchement.
+        if let Some(preview) = preview {
+            app.render_ifs(preview);
+        }
+    }
+
+    fn kleinian_controls(app: &mut FractalApp, ui: &mut egui::Ui) {
+        let mut changed = false;
+        let complex = |ui: &mut egui::Ui, label: &str, z: &mut Complex<f64>| {
+            ui.label(label);
+            ui.add(egui::DragValue::new(&mut z.re).speed(0.001).prefix("re ")).changed()
+                | ui.add(egui::DragValue::new(&mut z.im).speed(0.001).prefix("im ")).changed()
+        };
+        ui.horizontal(|ui| {
+            let group = &mut app.params.kleinian;
+            let recipe = matches!(group, KleinianGroup::Recipe { .. });
+            // Le passage d'une forme à l'autre garde le même groupe.
+            if ui.radio(recipe, "Recette de Grand-mère").clicked() && !recipe {
+                let [a, b] = group.generators();
+                *group = KleinianGroup::Recipe { ta: a.trace(), tb: b.trace() };
+                changed = true;
+            }
+            if ui.radio(!recipe, "Générateurs").clicked() && recipe {
+                let [a, b] = group.generators();
+                *group = KleinianGroup::Generators { a, b };
+                changed = true;
+            }
+            ui.label("Profondeur max. :");
+            changed |= ui.add(egui::DragValue::new(&mut app.params.kleinian_depth).clamp_range(1..=500)).changed();
+        });
+        match &mut app.params.kleinian {
+            KleinianGroup::Recipe { ta, tb } => {
+                ui.horizontal(|ui| {
+                    changed |= complex(ui, "ta :", ta);
+                    changed |= complex(ui, "tb :", tb);
+                });
+            }
+            KleinianGroup::Generators { a, b } => {
+                for (name, m) in [("a", a), ("b", b)] {
+                    ui.horizontal(|ui| {
+                        let Mobius { a, b, c, d } = m;
+                        ui.label(format!("{} = (αz + β)/(γz + δ)", name));
+                        changed |= complex(ui, "α", a);
+                        changed |= complex(ui, "β", b);
+                        changed |= complex(ui, "γ", c);
+                        changed |= complex(ui, "δ", d);
+                    });
+                }
+            }
+        }
+        if changed {
+            app.rebuild_fractal();
+        }
+    }
+
+    fn attractor_controls(app: &mut FractalApp, ui: &mut egui::Ui) {
+        let mut changed = false;
+        let attractor = &mut app.params.attractor;
+        ui.horizontal(|ui| {
+            ui.label(format!("{} :", attractor.kind.label()));
+            let names = attractor.kind.parameter_names();
+            attractor.parameters.resize(names.len(), 0.0);
+            for (name, value) in names.iter().zip(attractor.parameters.iter_mut()) {
+                ui.label(*name);
+                changed |= ui.add(egui::DragValue::new(value).speed(0.005).max_decimals(4)).changed();
+            }
+            if ui.button("Attracteur aléatoire").on_hover_text("Paramètres tirés au hasard jusqu'à un exposant de Lyapunov positif").clicked() {
+                let seed = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map_or(0, |d| d.as_nanos() as u64);
+                if let Some(found) = Attractor::random_chaotic(attractor.kind, &mut Rng::new(seed)) {
+                    *attractor = found;
+                    changed = true;
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Points :");
+            changed |= ui
+                .add(egui::DragValue::new(&mut app.params.attractor_points).clamp_range(10_000..=100_000_000).speed(10_000))
+                .changed();
+            if attractor.kind.is_flow() {
+                ui.label("Pas :");
+                changed |= ui.add(egui::DragValue::new(&mut attractor.step).clamp_range(0.0001..=0.1).speed(0.0005)).changed();
+                changed |= ui.add(egui::Slider::new(&mut attractor.yaw, -180.0..=180.0).text("Lacet")).changed();
+                changed |= ui.add(egui::Slider::new(&mut attractor.pitch, -180.0..=180.0).text("Tangage")).changed();
+            }
+        });
+        if changed {
+            app.rebuild_fractal();
+        }
+    }
+
+    fn multibrot_controls(app: &mut FractalApp, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let mut changed = false;
+            let exponent = &mut app.params.exponent;
+            ui.label("Exposant d :");
+            egui::ComboBox::from_id_source("exposant")
+                .selected_text(match exponent {
+                    Exponent::Integer(_) => "Entier",
+                    Exponent::Real(_) => "Réel",
+                    Exponent::Complex(_) => "Complexe",
+                })
+                .show_ui(ui, |ui| {
+                    let d = match *exponent {
+                        Exponent::Integer(n) => Complex::new(n as f64, 0.0),
+                        Exponent::Real(d) => Complex::new(d, 0.0),
+                        Exponent::Complex(d) => d,
+                    };
+                    let integer = Exponent::Integer(d.re.round().max(2.0) as u32);
+                    changed |= ui.selectable_value(exponent, integer, "Entier").changed();
+                    changed |= ui.selectable_value(exponent, Exponent::Real(d.re), "Réel").changed();
+                    changed |= ui.selectable_value(exponent, Exponent::Complex(d), "Complexe").changed();
+                });
+            match exponent {
+                Exponent::Integer(n) => {
+                    changed |= ui.add(egui::DragValue::new(n).clamp_range(2..=16)).changed();
+                }
+                Exponent::Real(d) => {
+                    changed |= ui.add(egui::DragValue::new(d).speed(0.01)).changed();
+                }
+                Exponent::Complex(d) => {
+                    changed |= ui.add(egui::DragValue::new(&mut d.re).speed(0.01).prefix("re ")).changed();
+                    changed |= ui.add(egui::DragValue::new(&mut d.im).speed(0.01).prefix("im ")).changed();
+                }
+            }
+
+            changed |= Self::julia_controls(app, ui);
+
+            if changed {
+                app.rebuild_fractal();
+            }
+        });
+    }
+
+    fn nova_controls(app: &mut FractalApp, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let mut changed = false;
+            ui.label("Relaxation R :");
+            changed |= ui.add(egui::DragValue::new(&mut app.params.nova_relaxation.re).speed(0.01).prefix("re ")).changed();
+            changed |= ui.add(egui::DragValue::new(&mut app.params.nova_relaxation.im).speed(0.01).prefix("im ")).changed();
+            changed |= Self::julia_controls(app, ui);
+
+            if changed {
+                app.rebuild_fractal();
+            }
+        });
+    }
+
+    fn phoenix_controls(app: &mut FractalApp, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let mut changed = false;
+            ui.label("p :");
+            changed |= ui.add(egui::DragValue::new(&mut app.params.phoenix_p.re).speed(0.005).prefix("re ")).changed();
+            changed |= ui.add(egui::DragValue::new(&mut app.params.phoenix_p.im).speed(0.005).prefix("im ")).changed();
+            changed |= Self::julia_controls(app, ui);
+
+            if changed {
+                app.rebuild_fractal();
+            }
+        });
+    }
+
+    fn abs_variant_controls(app: &mut FractalApp, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let mut changed = false;
+            ui.label("Puissance :");
+            changed |= ui.radio_value(&mut app.params.abs_power, 2, "2").changed();
+            changed |= ui.radio_value(&mut app.params.abs_power, 3, "3").changed();
+            changed |= Self::julia_controls(app, ui);
+
+            if changed {
+                app.rebuild_fractal();
+            }
+        });
+    }
+
+    fn transcendental_controls(app: &mut FractalApp, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let mut changed = false;
+            if app.params.transcendental == TranscendentalMap::LambdaExp {
+                ui.label("λ :");
+                changed |= ui.add(egui::DragValue::new(&mut app.params.lambda.re).speed(0.005).prefix("re ")).changed();
+                changed |= ui.add(egui::DragValue::new(&mut app.params.lambda.im).speed(0.005).prefix("im ")).changed();
+            } else {
+                changed |= Self::julia_controls(app, ui);
+            }
+
+            if changed {
+                app.rebuild_fractal();
+            }
+        });
+    }
+
+    fn mcmullen_controls(app: &mut FractalApp, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let mut changed = false;
+            ui.label("n :");
+            changed |= ui.add(egui::DragValue::new(&mut app.params.mcmullen_n).clamp_range(1..=8)).changed();
+            ui.label("m :");
+            changed |= ui.add(egui::DragValue::new(&mut app.params.mcmullen_m).clamp_range(1..=8)).changed();
+            changed |= ui.checkbox(&mut app.params.julia_mode, "Plan dynamique").changed();
+            if app.params.julia_mode {
+                ui.label("λ :");
+                changed |= ui.add(egui::DragValue::new(&mut app.params.mcmullen_lambda.re).speed(0.0005).prefix("re ")).changed();
+                changed |= ui.add(egui::DragValue::new(&mut app.params.mcmullen_lambda.im).speed(0.0005).prefix("im ")).changed();
+            }
+
+            if changed {
+                app.rebuild_fractal();
+            }
+        });
+    }
+
+    fn lyapunov_controls(app: &mut FractalApp, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let mut changed = false;
+            ui.label("Séquence :");
+            let sequence = ui.add(
+                egui::TextEdit::singleline(&mut app.params.lyapunov_sequence)
+                    .hint_text("AB")
+                    .desired_width(120.0),
+            );
+            // Recalcul à la validation seulement, pas à chaque caractère.
+            if sequence.lost_focus() {
+                app.params.lyapunov_sequence.retain(|ch| matches!(ch, 'A' | 'B' | 'a' | 'b'));
+                app.params.lyapunov_sequence.make_ascii_uppercase();
+                changed = true;
+            }
+            ui.label("Préchauffage :");
+            changed |= ui.add(egui::DragValue::new(&mut app.params.lyapunov_warmup).clamp_range(1..=10_000)).changed();
+            ui.label("Échantillons :");
+            changed |= ui.add(egui::DragValue::new(&mut app.params.lyapunov_samples).clamp_range(1..=10_000)).changed();
+
+            if changed {
+                app.rebuild_fractal();
+            }
+        });
+    }
+
+    fn formula_controls(app: &mut FractalApp, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let editor = ui.add(
+                egui::TextEdit::multiline(&mut app.formula_source)
+                    .code_editor()
+                    .desired_rows(3)
+                    .desired_width(360.0),
+            );
+            ui.vertical(|ui| {
+                let submit = editor.has_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter) && i.modifiers.ctrl);
+                if ui.button("Compiler (Ctrl+Entrée)").clicked() || submit {
+                    app.apply_formula();
+                }
+                ui.horizontal(|ui| {
+                    let mut changed = false;
+                    ui.label("p :");
+                    changed |= ui.add(egui::DragValue::new(&mut app.params.formula_param.re).speed(0.005).prefix("re ")).changed();
+                    changed |= ui.add(egui::DragValue::new(&mut app.params.formula_param.im).speed(0.005).prefix("im ")).changed();
+                    if changed {
+                        app.rebuild_fractal();
+                    }
+                });
+                if let Some(error) = &app.formula_error {
+                    // Ligne fautive soulignée d'un curseur sous la colonne.
+                    let line = app.formula_source.lines().nth(error.line - 1).unwrap_or("");
+                    let caret = format!("{}^", " ".repeat(error.column.saturating_sub(1)));
+                    ui.colored_label(egui::Color32::RED, error.to_string());
+                    ui.monospace(format!("{}\n{}", line, caret));
+                }
+            });
+        });
+    }
+
+    // Liste ordonnée des étapes : formule, paramètres, répétitions.
+    fn hybrid_controls(app: &mut FractalApp, ui: &mut egui::Ui) {
+        let mut changed = false;
+        let mut remove = None;
+        let mut swap = None;
+        let step_count = app.params.hybrid.len();
+
+        for (index, step) in app.params.hybrid.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!("{}.", index + 1));
+                egui::ComboBox::from_id_source(("hybride", index))
+                    .selected_text(step.formula.label())
+                    .show_ui(ui, |ui| {
+                        for choice in HybridFormula::choices() {
+                            let selected = std::mem::discriminant(&choice) == std::mem::discriminant(&step.formula);
+                            if ui.selectable_label(selected, choice.label()).clicked() && !selected {
+                                step.formula = choice;
+                                changed = true;
+                            }
+                        }
+                    });
+                changed |= ui.add(egui::DragValue::new(&mut step.repeat).clamp_range(1..=16).suffix(" ×")).changed();
+
+                match &mut step.formula {
+                    HybridFormula::Mandelbrot => {}
+                    HybridFormula::Multibrot(exponent) => {
+                        let mut d = match *exponent {
+                            Exponent::Integer(n) => n as f64,
+                            Exponent::Real(d) => d,
+                            Exponent::Complex(d) => d.re,
+                        };
+                        if ui.add(egui::DragValue::new(&mut d).speed(0.01).prefix("d ")).changed() {
+                            *exponent = if d.fract() == 0.0 && d >= 0.0 { Exponent::Integer(d as u32) } else { Exponent::Real(d) };
+                            changed = true;
+                        }
+                    }
+                    HybridFormula::Abs { variant, power } => {
+                        egui::ComboBox::from_id_source(("hybride-abs", index))
+                            .selected_text(variant.label())
+                            .show_ui(ui, |ui| {
+                                for choice in AbsVariant::ALL {
+                                    changed |= ui.selectable_value(variant, choice, choice.label()).changed();
+                                }
+                            });
+                        changed |= ui.radio_value(power, 2, "2").changed();
+                        changed |= ui.radio_value(power, 3, "3").changed();
+                    }
+                    HybridFormula::Phoenix(p) => {
+                        changed |= ui.add(egui::DragValue::new(&mut p.re).speed(0.005).prefix("p re ")).changed();
+                        changed |= ui.add(egui::DragValue::new(&mut p.im).speed(0.005).prefix("im ")).changed();
+                    }
+                    HybridFormula::Formula(source) => {
+                        changed |= ui.text_edit_singleline(source).lost_focus();
+                    }
+                }
+
+                if ui.add_enabled(index > 0, egui::Button::new("↑")).clicked() {
+                    swap = Some((index - 1, index));
+                }
+                if ui.add_enabled(index + 1 < step_count, egui::Button::new("↓")).clicked() {
+                    swap = Some((index, index + 1));
+                }
+                if ui.add_enabled(step_count > 1, egui::Button::new("✖")).clicked() {
+                    remove = Some(index);
+                }
+            });
+        }
+
+        if let Some((a, b)) = swap {
+            app.params.hybrid.swap(a, b);
+            changed = true;
+        }
+        if let Some(index) = remove {
+            app.params.hybrid.remove(index);
+            changed = true;
+        }
+        ui.horizontal(|ui| {
+            if ui.button("Ajouter une étape").clicked() {
+                app.params.hybrid.push(HybridStep::new(HybridFormula::Mandelbrot, 1));
+                changed = true;
+            }
+            changed |= Self::julia_controls(app, ui);
+        });
+
+        if let Err(error) = HybridSet::new(&app.params.hybrid) {
+            ui.colored_label(egui::Color32::RED, format!("Formule invalide : {}", error));
+        } else if changed {
+            app.rebuild_fractal();
+        }
+    }
+
+    // Bascule plan des paramètres / ensemble de Julia de paramètre c.
+    fn julia_controls(app: &mut FractalApp, ui: &mut egui::Ui) -> bool {
+        let mut changed = ui.checkbox(&mut app.params.julia_mode, "Julia").changed();
+        if app.params.julia_mode {
+            ui.label("c :");
+            changed |= ui.add(egui::DragValue::new(&mut app.params.julia_c.re).speed(0.005).prefix("re ")).changed();
+            changed |= ui.add(egui::DragValue::new(&mut app.params.julia_c.im).speed(0.005).prefix("im ")).changed();
+        }
+        changed
+    }
+
+    fn newton_controls(app: &mut FractalApp, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let mut changed = false;
+            // Nova a sa propre relaxation R.
+            if matches!(app.active_fractal, ActiveFractal::Newton(_)) {
+                ui.label("Relaxation a :");
+                changed |= ui.add(egui::DragValue::new(&mut app.params.newton_relaxation.re).speed(0.01).prefix("re ")).changed();
+                changed |= ui.add(egui::DragValue::new(&mut app.params.newton_relaxation.im).speed(0.01).prefix("im ")).changed();
+            }
+            if ui.button("Ajouter une racine").clicked() {
+                app.params.newton_roots.push(app.params.center);
+                changed = true;
+            }
+            if ui.button("Réinitialiser").clicked() {
+                app.params.newton_roots = default_newton_roots();
+                app.params.newton_relaxation = Complex::new(1.0, 0.0);
+                changed = true;
+            }
+
+            ui.label("Coefficients a0, a1, … :");
+            ui.text_edit_singleline(&mut app.newton_coefficients);
+            if ui.button("Appliquer").clicked() {
+                let coefficients: Result<Vec<Complex<f64>>, String> = app
+                    .newton_coefficients
+                    .split(',')
+                    .map(|term| {
+                        term.trim()
+                            .replace(' ', "")
+                            .parse::<Complex<f64>>()
+                            .map_err(|_| format!("coefficient invalide « {} »", term.trim()))
+                    })
+                    .collect();
+                app.newton_error = match coefficients {
+                    Ok(coefficients) => {
+                        let newton = NewtonSet::from_coefficients(coefficients, app.params.newton_relaxation);
+                        if newton.roots.is_empty() {
+                            Some(String::from("le polynôme doit être de degré 1 au moins"))
+                        } else {
+                            app.params.newton_roots = newton.roots;
+                            changed = true;
+                            None
+                        }
+                    }
+                    Err(error) => Some(error),
+                };
+            }
+
+            if changed {
+                app.update_newton(QualityLevel::High);
+            }
+        });
+        if let Some(error) = &app.newton_error {
+            ui.colored_label(egui::Color32::RED, format!("Polynôme invalide : {}", error));
+        }
+    }
+
+    // Racines déplaçables à la souris, dessinées par-dessus l'image.
+    fn newton_root_handles(app: &mut FractalApp, ui: &mut egui::Ui, rect: egui::Rect) {
+        let (center, zoom) = (app.params.center, app.params.zoom);
+        let to_screen = |z: Complex<f64>| {
+            egui::pos2(
+                rect.left() + (((z.re - center.re) * zoom + 0.5) * rect.width() as f64) as f32,
+                rect.top() + (((z.im - center.im) * zoom + 0.5) * rect.height() as f64) as f32,
+            )
+        };
+        let to_complex = |p: egui::Pos2| {
+            Complex::new(
+                ((p.x - rect.left()) as f64 / rect.width() as f64 - 0.5) / zoom + center.re,
+                ((p.y - rect.top()) as f64 / rect.height() as f64 - 0.5) / zoom + center.im,
+            )
+        };
+
+        let root_count = app.params.newton_roots.len();
+        let mut quality = None;
+        let mut removed = None;
+        for i in 0..root_count {
+            let position = to_screen(app.params.newton_roots[i]);
+            let handle = egui::Rect::from_center_size(position, egui::vec2(16.0, 16.0));
+            let response = ui
+                .interact(handle, ui.id().with(("newton_root", i)), egui::Sense::click_and_drag())
+                .on_hover_text("Glisser pour déplacer, clic droit pour supprimer");
+            if response.dragged() {
+                if let Some(pointer) = response.interact_pointer_pos() {
+                    app.params.newton_roots[i] = to_complex(pointer);
+                    // Aperçu rapide pendant le glissement.
+                    quality = Some(QualityLevel::Low);
+                }
+            }
+            if response.drag_released() {
+                quality = Some(QualityLevel::High);
+            }
+            if response.secondary_clicked() && root_count > 2 {
+                removed = Some(i);
+            }
+
+            let (r, g, b) = basin_color(i, root_count, 0.0, app.params.max_iterations, 0.0);
+            ui.painter().circle(
+                to_screen(app.params.newton_roots[i]),
+                6.0,
+                egui::Color32::from_rgb(r, g, b),
+                egui::Stroke::new(2.0, egui::Color32::WHITE),
+            );
+        }
+
+        if let Some(i) = removed {
+            app.params.newton_roots.remove(i);
+            quality = Some(QualityLevel::High);
+        }
+        if let Some(quality) = quality {
+            app.update_newton(quality);
+        }
+    }
 } 
\ No newline at end of file
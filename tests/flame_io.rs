@@ -0,0 +1,86 @@
+use fractal_generator::flame::Variation;
+use fractal_generator::flame_io::{parse_flames, FlameError};
+
+fn parse_error_line<T>(result: Result<T, FlameError>) -> usize {
+    match result {
+        Err(FlameError::Parse { line, .. }) => line,
+        Err(other) => panic!("erreur inattendue : {}", other),
+        Ok(_) => panic!("une erreur d'analyse était attendue"),
+    }
+}
+
+#[test]
+fn coefficients_follow_flam3_order() {
+    let source = r#"<flame name="ordre"><xform weight="1" coefs="1 2 3 4 5 6" linear="1"/></flame>"#;
+    let flames = parse_flames(source).unwrap();
+    let affine = flames[0].flame.xforms[0].affine;
+    // x' = a·x + b·y + c et y' = d·x + e·y + f avec « a d b e c f » = 1 2 3 4 5 6.
+    assert_eq!(affine.apply([1.0, 0.0]), [1.0 + 5.0, 2.0 + 6.0]);
+    assert_eq!(affine.apply([0.0, 1.0]), [3.0 + 5.0, 4.0 + 6.0]);
+    assert_eq!(flames[0].flame.xforms[0].variations, vec![(Variation::Linear, 1.0)]);
+}
+
+#[test]
+fn reads_hex_and_indexed_palettes() {
+    let hex = r#"<flame>
+  <xform coefs="1 0 0 1 0 0" linear="1"/>
+  <palette count="2" format="RGB">
+    FF0000 00ff80
+  </palette>
+</flame>"#;
+    let palette = &parse_flames(hex).unwrap()[0].flame.palette;
+    assert_eq!(palette[0], [255, 0, 0]);
+    assert_eq!(palette[1], [0, 255, 128]);
+
+    let indexed = r#"<flame>
+  <xform coefs="1 0 0 1 0 0" linear="1"/>
+  <color index="0" rgb="10 20 30"/>
+  <color index="255" rgb="300 0 -4"/>
+</flame>"#;
+    let palette = &parse_flames(indexed).unwrap()[0].flame.palette;
+    assert_eq!(palette[0], [10, 20, 30]);
+    assert_eq!(palette[255], [255, 0, 0]);
+}
+
+#[test]
+fn reports_error_lines() {
+    let source = "<flames>\n<!-- deux\nlignes -->\n<flame>\n  <xform coefs=\"1 0 0\" linear=\"1\"/>\n</flame>\n</flames>\n";
+    assert_eq!(parse_error_line(parse_flames(source)), 5);
+    assert_eq!(parse_error_line(parse_flames("<flames>\n<flame>\n</flames>\n")), 3);
+    assert_eq!(parse_error_line(parse_flames("<flame>\n  <palette>\n00GG00\n</palette>\n</flame>\n")), 2);
+}
+
+#[test]
+fn rejects_deep_nesting() {
+    let source = format!("{}{}", "<a>".repeat(50_000), "</a>".repeat(50_000));
+    assert_eq!(parse_error_line(parse_flames(&source)), 1);
+}
+
+#[test]
+fn collects_unsupported_variations() {
+    let source = r#"<flames>
+  <flame name="a">
+    <xform coefs="1 0 0 1 0 0" linear="0.5" mobius="1" julia_power="2" blob_low="0.2"/>
+    <xform coefs="1 0 0 1 0 0" mobius="1" wedge="0.3"/>
+  </flame>
+  <flame name="b"><xform coefs="1 0 0 1 0 0" spherical="1"/></flame>
+</flames>"#;
+    let flames = parse_flames(source).unwrap();
+    assert_eq!(flames.len(), 2);
+    assert_eq!(flames[0].unsupported, vec!["blob_low".to_string(), "mobius".to_string(), "wedge".to_string()]);
+    assert_eq!(flames[0].flame.xforms[0].variations, vec![(Variation::Linear, 0.5)]);
+    assert!(flames[1].unsupported.is_empty());
+    assert_eq!(flames[1].flame.name, "b");
+}
+
+#[test]
+fn reports_variations_with_underscores() {
+    let source = r#"<flame supersample="3">
+  <xform coefs="1 0 0 1 0 0" linear="1" radial_blur="0.4" radial_blur_angle="0.5"/>
+  <xform coefs="1 0 0 1 0 0" pre_blur="1" gaussian_blur="0.2"/>
+</flame>"#;
+    let flames = parse_flames(source).unwrap();
+    assert_eq!(flames[0].unsupported, vec!["pre_blur".to_string(), "radial_blur".to_string()]);
+    assert_eq!(flames[0].flame.xforms[1].variations, vec![(Variation::GaussianBlur, 0.2)]);
+    assert_eq!(flames[0].flame.oversample, 3);
+}
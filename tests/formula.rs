@@ -0,0 +1,122 @@
+use fractal_generator::formula::{parse, BinaryOp, CompiledFormula, Condition, Expr, FormulaError, Function};
+use fractal_generator::fractal_types::{FractalFunction, OrbitState};
+use num::Complex;
+
+fn number(value: f64) -> Box<Expr> {
+    Box::new(Expr::Number(Complex::new(value, 0.0)))
+}
+
+fn compile_error(source: &str) -> FormulaError {
+    match CompiledFormula::compile(source) {
+        Err(error) => error,
+        Ok(_) => panic!("une erreur était attendue pour « {} »", source),
+    }
+}
+
+// Itère la formule à la main depuis initial_z, sans critère d'échappement.
+fn orbit(formula: &CompiledFormula, c: Complex<f64>, steps: u32) -> Complex<f64> {
+    let mut state = OrbitState::new(formula.initial_z(c));
+    for _ in 0..steps {
+        formula.step(c, &mut state);
+    }
+    state.z
+}
+
+#[test]
+fn negation_binds_looser_than_power() {
+    let program = parse("z = -z^2").unwrap();
+    let z = Box::new(Expr::Variable(0));
+    assert_eq!(program.statements[0].expr, Expr::Neg(Box::new(Expr::Binary(BinaryOp::Pow, z, number(2.0)))));
+}
+
+#[test]
+fn power_is_right_associative() {
+    let program = parse("z = 2^3^2").unwrap();
+    let right = Box::new(Expr::Binary(BinaryOp::Pow, number(3.0), number(2.0)));
+    assert_eq!(program.statements[0].expr, Expr::Binary(BinaryOp::Pow, number(2.0), right));
+
+    let formula = CompiledFormula::compile("z = 2^3^2").unwrap();
+    assert_eq!(formula.initial_z(Complex::new(0.0, 0.0)), Complex::new(0.0, 0.0));
+    let z = orbit(&formula, Complex::new(0.0, 0.0), 1);
+    assert!((z - Complex::new(512.0, 0.0)).norm() < 1e-9, "{}", z);
+}
+
+#[test]
+fn errors_carry_line_and_column() {
+    let error = compile_error("z = z^2 + c\nz = z * $");
+    assert_eq!((error.line, error.column), (2, 9));
+
+    let error = compile_error("z = z^2 + c\n\nz = (z + c");
+    assert_eq!(error.line, 3);
+
+    let error = compile_error("z = sinus(z)");
+    assert_eq!((error.line, error.column), (1, 5));
+    assert!(error.message.contains("sinus"), "{}", error.message);
+
+    let error = compile_error("z = z + w");
+    assert_eq!((error.line, error.column), (1, 9));
+}
+
+#[test]
+fn deep_nesting_is_rejected() {
+    let source = format!("z = {}z{}", "(".repeat(50_000), ")".repeat(50_000));
+    assert_eq!(compile_error(&source).message, "expression trop imbriquée");
+    assert_eq!(compile_error(&format!("z = {}z", "-".repeat(50_000))).message, "expression trop imbriquée");
+    assert!(CompiledFormula::compile(&format!("z = {}z{} + c", "(".repeat(100), ")".repeat(100))).is_ok());
+}
+
+#[test]
+fn norm_bailout_matches_general_condition() {
+    let fast = CompiledFormula::compile("z = z^2 + c\nbailout |z| > 3").unwrap();
+    let general = CompiledFormula::compile("z = z^2 + c\nbailout re(z)*re(z) + im(z)*im(z) > 9").unwrap();
+    for i in 0..40 {
+        let c = Complex::new(-2.0 + i as f64 * 0.065, 0.3 - i as f64 * 0.02);
+        let zero = Complex::new(0.0, 0.0);
+        assert_eq!(fast.iterate(c, zero, 200).iterations, general.iterate(c, zero, 200).iterations, "c = {}", c);
+    }
+    assert!(!fast.escaped(Complex::new(2.5, 0.0)));
+    assert!(fast.escaped(Complex::new(3.5, 0.0)));
+}
+
+#[test]
+fn combined_conditions() {
+    let program = parse("z = z^2 + c\nbailout re(z) > 2 or im(z) > 2 and re(z) < 0").unwrap();
+    assert!(matches!(program.bailout, Some(Condition::And(..))));
+
+    let formula = CompiledFormula::compile("z = z^2 + c\nbailout re(z) > 2 or im(z) > 2").unwrap();
+    assert!(formula.escaped(Complex::new(3.0, 0.0)));
+    assert!(formula.escaped(Complex::new(0.0, 3.0)));
+    assert!(!formula.escaped(Complex::new(-3.0, -3.0)));
+
+    let formula = CompiledFormula::compile("z = z^2 + c\nbailout re(z) > 2 and im(z) > 2").unwrap();
+    assert!(formula.escaped(Complex::new(3.0, 3.0)));
+    assert!(!formula.escaped(Complex::new(3.0, 0.0)));
+}
+
+#[test]
+fn init_statements_run_once() {
+    let formula = CompiledFormula::compile("init z = c\ninit w = 2\nz = z * w").unwrap();
+    let c = Complex::new(0.05, 0.1);
+    assert_eq!(formula.initial_z(c), c);
+    let result = formula.iterate(c, Complex::new(0.0, 0.0), 3);
+    assert_eq!(result.iterations, 3);
+    assert_eq!(result.z, c * 8.0);
+    assert!(parse("init z = c").is_err());
+}
+
+#[test]
+fn integer_powers_match_powc() {
+    let c = Complex::new(-0.4, 0.6);
+    for (lowered, general) in [
+        ("init z = c; z = z^2 + c", "init z = c; z = z^(2 + 0i) + c"),
+        ("init z = c; z = z^3 + c", "init z = c; z = z^(3 + 0i) + c"),
+        ("init z = c; z = z^(-3) + c", "init z = c; z = z^(-3 + 0i) + c"),
+    ] {
+        let lowered = CompiledFormula::compile(lowered).unwrap();
+        let general = CompiledFormula::compile(general).unwrap();
+        let (a, b) = (orbit(&lowered, c, 6), orbit(&general, c, 6));
+        assert!((a - b).norm() < 1e-9 * (1.0 + a.norm()), "{} ≠ {}", a, b);
+    }
+    let program = parse("z = sqrt(z)").unwrap();
+    assert!(matches!(program.statements[0].expr, Expr::Call(Function::Sqrt, _)));
+}
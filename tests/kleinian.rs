@@ -0,0 +1,47 @@
+use fractal_generator::fractal_params::FractalParams;
+use fractal_generator::kleinian::{grandma_recipe, KleinianPreset, KleinianRenderer};
+use num::Complex;
+
+const RECIPES: [((f64, f64), (f64, f64)); 4] = [
+    ((2.0, 0.0), (2.0, 0.0)),
+    ((1.91, 0.05), (2.0, 0.0)),
+    ((1.87, 0.1), (1.87, -0.1)),
+    ((2.2, 0.3), (1.9, 0.0)),
+];
+
+fn close(a: Complex<f64>, b: Complex<f64>) -> bool {
+    (a - b).norm() < 1e-9
+}
+
+#[test]
+fn recipe_generators_have_the_requested_traces() {
+    for (ta, tb) in RECIPES {
+        let (ta, tb) = (Complex::new(ta.0, ta.1), Complex::new(tb.0, tb.1));
+        let [a, b] = grandma_recipe(ta, tb).map(|g| g.normalized());
+        assert!(close(a.trace(), ta), "tr a = {} au lieu de {}", a.trace(), ta);
+        assert!(close(b.trace(), tb), "tr b = {} au lieu de {}", b.trace(), tb);
+    }
+}
+
+#[test]
+fn recipe_commutator_is_parabolic() {
+    for (ta, tb) in RECIPES {
+        let (ta, tb) = (Complex::new(ta.0, ta.1), Complex::new(tb.0, tb.1));
+        let [a, b] = grandma_recipe(ta, tb).map(|g| g.normalized());
+        let commutator = a * b * a.inverse() * b.inverse();
+        assert!(close(commutator.trace(), Complex::new(-2.0, 0.0)), "tr abAB = {}", commutator.trace());
+    }
+}
+
+#[test]
+fn apollonian_limit_set_fills_the_unit_disk() {
+    let preset = KleinianPreset::ApollonianGasket;
+    let (center, zoom) = preset.view();
+    let params = FractalParams { size: (200, 200), center, zoom, kleinian: preset.group(), ..FractalParams::default() };
+    let segments = KleinianRenderer::new(params).limit_set();
+    assert!(segments.len() > 1000);
+    let norms: Vec<f64> = segments.iter().flatten().map(|z| z.norm()).collect();
+    // La baderne est bordée par le cercle unité, qu'elle touche.
+    assert!(norms.iter().all(|&r| r <= 1.0 + 1e-6), "{}", norms.iter().cloned().fold(0.0, f64::max));
+    assert!(norms.iter().any(|&r| r > 0.999));
+}
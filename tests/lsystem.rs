@@ -0,0 +1,73 @@
+use fractal_generator::color_schemes::GrayscaleScheme;
+use fractal_generator::lsystem::{to_svg, LSystem, LSystemError, LSystemPreset, Module, Segment};
+
+fn lsystem(axiom: &str, rules: &str, iterations: u32) -> LSystem {
+    LSystem { axiom: axiom.to_string(), rules: rules.to_string(), iterations, ..LSystem::default() }
+}
+
+fn word(modules: &[Module]) -> String {
+    modules.iter().map(|m| m.symbol).collect()
+}
+
+#[test]
+fn koch_length_grows_fourfold() {
+    let mut koch = LSystemPreset::KochSnowflake.lsystem();
+    for iterations in 0..5 {
+        koch.iterations = iterations;
+        let modules = koch.derive().unwrap();
+        // Chaque F devient F+F--F+F : 7·4ⁿ symboles dont 3·4ⁿ traits.
+        assert_eq!(modules.len(), 7 * 4usize.pow(iterations));
+        assert_eq!(koch.segments().unwrap().len(), 3 * 4usize.pow(iterations));
+    }
+    koch.iterations = 1;
+    assert_eq!(word(&koch.derive().unwrap()), "F+F--F+F--F+F--F+F--F+F--F+F");
+}
+
+#[test]
+fn parametric_rules_follow_conditions() {
+    let system = lsystem("A(1)", "A(x) : x < 3 -> A(x + 1)F(x)\nA(x) : x >= 3 -> B(x * 2)", 4);
+    let modules = system.derive().unwrap();
+    assert_eq!(word(&modules), "BFF");
+    assert_eq!(modules[0].params, vec![6.0]);
+    assert_eq!(modules[1].params, vec![2.0]);
+    assert_eq!(modules[2].params, vec![1.0]);
+}
+
+#[test]
+fn stochastic_rules_depend_only_on_seed() {
+    let mut plant = LSystemPreset::StochasticPlant.lsystem();
+    let first = plant.derive().unwrap();
+    assert_eq!(plant.derive().unwrap(), first);
+    plant.seed += 1;
+    assert_ne!(plant.derive().unwrap(), first);
+}
+
+#[test]
+fn rule_errors_count_comment_lines() {
+    let system = lsystem("F", "# commentaire\n\nF -> F+F\nG => G", 2);
+    match system.derive() {
+        Err(LSystemError::Rule { line, .. }) => assert_eq!(line, 4),
+        other => panic!("erreur de règle attendue : {:?}", other),
+    }
+}
+
+#[test]
+fn svg_merges_connected_segments() {
+    let scheme = GrayscaleScheme;
+    let line: Vec<Segment> = (0..640)
+        .map(|i| Segment { from: [i as f64, 0.0], to: [i as f64 + 1.0, 0.0], width: 1.0 })
+        .collect();
+    // Un chemin par niveau de couleur : 64 niveaux de 10 traits.
+    let svg = to_svg(&line, &scheme, (200, 100));
+    assert_eq!(svg.matches("<path").count(), 64);
+    assert_eq!(svg.matches(" L").count(), 640);
+
+    let dashes: Vec<Segment> = (0..640)
+        .map(|i| Segment { from: [2.0 * i as f64, 0.0], to: [2.0 * i as f64 + 1.0, 0.0], width: 1.0 })
+        .collect();
+    assert_eq!(to_svg(&dashes, &scheme, (200, 100)).matches("<path").count(), 640);
+
+    let mut widths = line.clone();
+    widths[5].width = 2.0;
+    assert_eq!(to_svg(&widths, &scheme, (200, 100)).matches("<path").count(), 66);
+}
@@ -0,0 +1,104 @@
+use fractal_generator::color_schemes::GrayscaleScheme;
+use fractal_generator::fractal_calculator::IterationData;
+use fractal_generator::mesh::{heightmap_mesh, Mesh, MeshFormat, MeshSettings};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+fn iteration_data((width, height): (usize, usize), value: impl Fn(usize, usize) -> f64) -> IterationData {
+    let smooth = (0..width * height).map(|i| value(i % width, i / width)).collect();
+    IterationData {
+        size: (width, height),
+        max_iterations: 100,
+        smooth,
+        root_count: 0,
+        basins: Vec::new(),
+        converged: vec![false; width * height],
+    }
+}
+
+// Relief irrégulier : des anneaux d'itérations et un plateau central.
+fn bumpy_data() -> IterationData {
+    iteration_data((45, 30), |x, y| {
+        let (dx, dy) = (x as f64 - 22.0, y as f64 - 15.0);
+        let r = (dx * dx + dy * dy).sqrt();
+        if r < 5.0 {
+            f64::NAN
+        } else {
+            50.0 + 40.0 * (r * 0.7).sin()
+        }
+    })
+}
+
+fn mesh(data: &IterationData, solid: bool) -> Mesh {
+    let settings = MeshSettings { step: 1, solid, ..MeshSettings::default() };
+    heightmap_mesh(data, &GrayscaleScheme, 0.0, &settings)
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("relief_{}_{}", std::process::id(), name))
+}
+
+#[test]
+fn flat_grid_collapses_to_two_triangles() {
+    let flat = mesh(&iteration_data((33, 17), |_, _| 10.0), false);
+    // 33 × 17 n'est pas carré : deux cellules de 16, chacune à deux triangles.
+    assert_eq!(flat.triangles.len(), 4);
+    assert_eq!(flat.vertices.len(), 6);
+
+    let square = mesh(&iteration_data((17, 17), |_, _| 10.0), false);
+    assert_eq!(square.triangles.len(), 2);
+    assert_eq!(square.vertices.len(), 4);
+    assert_eq!(square.colors.len(), square.vertices.len());
+}
+
+// Arêtes orientées qui ne sont pas parcourues une fois dans chaque sens.
+fn unmatched_edges(mesh: &Mesh) -> usize {
+    let mut edges: HashMap<(u32, u32), i32> = HashMap::new();
+    for &[a, b, c] in &mesh.triangles {
+        for (p, q) in [(a, b), (b, c), (c, a)] {
+            *edges.entry((p, q)).or_default() += 1;
+        }
+    }
+    edges.iter().filter(|(&(p, q), &count)| count != 1 || edges.get(&(q, p)) != Some(&1)).count()
+}
+
+#[test]
+fn solid_mesh_is_closed() {
+    let solid = mesh(&bumpy_data(), true);
+    assert!(solid.triangles.len() > 100);
+    assert!(solid.triangles.iter().all(|&[a, b, c]| a != b && b != c && a != c));
+    assert_eq!(unmatched_edges(&solid), 0);
+    assert!(unmatched_edges(&mesh(&bumpy_data(), false)) > 0);
+}
+
+#[test]
+fn binary_stl_has_fixed_size_records() {
+    let mesh = mesh(&bumpy_data(), true);
+    let path = temp_path("binaire.stl");
+    mesh.save(&path, MeshFormat::StlBinary).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(bytes.len(), 84 + 50 * mesh.triangles.len());
+    assert_eq!(u32::from_le_bytes(bytes[80..84].try_into().unwrap()) as usize, mesh.triangles.len());
+}
+
+#[test]
+fn ply_header_counts_match() {
+    let mesh = mesh(&bumpy_data(), true);
+    let path = temp_path("couleurs.ply");
+    mesh.save(&path, MeshFormat::Ply).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let end = b"end_header\n";
+    let header_len = bytes.windows(end.len()).position(|w| w == end).unwrap() + end.len();
+    let header = std::str::from_utf8(&bytes[..header_len]).unwrap();
+    let count = |element: &str| -> usize {
+        let line = header.lines().find(|l| l.starts_with(&format!("element {} ", element))).unwrap();
+        line.rsplit(' ').next().unwrap().parse().unwrap()
+    };
+    assert_eq!(count("vertex"), mesh.vertices.len());
+    assert_eq!(count("face"), mesh.triangles.len());
+    // Sommet : 3 flottants et 3 octets ; face : un octet et 3 entiers.
+    assert_eq!(bytes.len(), header_len + 15 * mesh.vertices.len() + 13 * mesh.triangles.len());
+}
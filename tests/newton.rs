@@ -0,0 +1,71 @@
+use fractal_generator::fractal_calculator::{FractalCalculator, IterationData};
+use fractal_generator::fractal_params::FractalParams;
+use fractal_generator::fractal_types::{default_newton_roots, FractalFunction, NewtonSet, RootMethod};
+use num::Complex;
+
+// Image d'un seul pixel, centrée pour que ce pixel tombe exactement sur c.
+fn single_pixel(c: Complex<f64>, max_iterations: u32) -> IterationData {
+    let zoom = 1e6;
+    let params = FractalParams {
+        size: (1, 1),
+        zoom,
+        max_iterations,
+        center: c + 0.5 / zoom * Complex::new(1.0, 1.0),
+        newton_roots: default_newton_roots(),
+        ..FractalParams::default()
+    };
+    FractalCalculator::<NewtonSet>::new_newton(params).compute()
+}
+
+#[test]
+fn points_near_each_cube_root_share_its_basin() {
+    for (index, root) in default_newton_roots().into_iter().enumerate() {
+        for offset in [Complex::new(0.1, 0.0), Complex::new(0.0, -0.1), Complex::new(-0.07, 0.07)] {
+            let data = single_pixel(root + offset, 100);
+            assert_eq!(data.root_count, 3);
+            assert_eq!(data.basins, vec![Some(index as u16)], "{} + {}", root, offset);
+            assert_eq!(data.converged, vec![true]);
+        }
+    }
+}
+
+#[test]
+fn distant_point_does_not_converge_in_few_iterations() {
+    // Loin des racines, chaque pas ne réduit |z| que d'un tiers.
+    let far = Complex::new(1e6, 1e6);
+    let data = single_pixel(far, 10);
+    assert_eq!(data.basins, vec![None]);
+    assert_eq!(data.converged, vec![false]);
+    assert!(data.smooth[0].is_nan());
+    assert_eq!(single_pixel(far, 200).converged, vec![true]);
+}
+
+#[test]
+fn every_method_finds_the_nearby_cube_root() {
+    let roots = default_newton_roots();
+    for method in RootMethod::ALL {
+        let newton = NewtonSet::from_roots(roots.clone(), Complex::new(1.0, 0.0)).with_method(method);
+        for (index, root) in roots.iter().enumerate() {
+            let start = root + Complex::new(0.15, -0.1);
+            let result = newton.iterate(start, start, 100);
+            assert_eq!(result.root, Some(index), "{:?} depuis {}", method, start);
+            assert!((result.z - root).norm() < 1e-6);
+        }
+    }
+}
+
+#[test]
+fn schroder_is_fast_on_a_double_root() {
+    // (z − 1)²(z + 1) : Newton ne converge que linéairement vers la racine double.
+    let roots = vec![Complex::new(1.0, 0.0), Complex::new(1.0, 0.0), Complex::new(-1.0, 0.0)];
+    let start = Complex::new(1.3, 0.2);
+    let iterate = |method| {
+        NewtonSet::from_roots(roots.clone(), Complex::new(1.0, 0.0))
+            .with_method(method)
+            .iterate(start, start, 200)
+    };
+    let (newton, schroder) = (iterate(RootMethod::Newton), iterate(RootMethod::Schroder));
+    assert_eq!(newton.root, Some(0));
+    assert_eq!(schroder.root, Some(0));
+    assert!(schroder.iterations * 3 < newton.iterations, "{} contre {}", schroder.iterations, newton.iterations);
+}
@@ -0,0 +1,94 @@
+use fractal_generator::gradient::{rgb_to_oklab, GradientScheme};
+use fractal_generator::palette_extract::{extract_palette, ClusteringMethod, ExtractionOptions, PathOrdering};
+use image::{Rgba, RgbaImage};
+
+fn image_from(colors: &[(u8, u8, u8)], width: u32, height: u32) -> RgbaImage {
+    RgbaImage::from_fn(width, height, |x, y| {
+        let (r, g, b) = colors[((y * width + x) as usize) % colors.len()];
+        Rgba([r, g, b, 255])
+    })
+}
+
+// Image de couleurs pseudo-aléatoires mais reproductibles.
+fn noisy_image(seed: u32) -> RgbaImage {
+    let mut state = seed;
+    RgbaImage::from_fn(64, 64, |_, _| {
+        let mut next = || {
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (state >> 24) as u8
+        };
+        Rgba([next(), next(), next(), 255])
+    })
+}
+
+fn options(colors: usize, method: ClusteringMethod, ordering: PathOrdering) -> ExtractionOptions {
+    ExtractionOptions { colors, method, ordering, ..ExtractionOptions::default() }
+}
+
+// Longueur du chemin des arrêts dans l'espace OKLab.
+fn path_length(gradient: &GradientScheme) -> f64 {
+    gradient
+        .stops
+        .windows(2)
+        .map(|pair| {
+            let (a, b) = (rgb_to_oklab(pair[0].color), rgb_to_oklab(pair[1].color));
+            ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+        })
+        .sum()
+}
+
+#[test]
+fn two_colors_give_two_stops_dark_to_light() {
+    let image = image_from(&[(250, 240, 20), (10, 20, 90)], 16, 16);
+    for method in ClusteringMethod::ALL {
+        for ordering in PathOrdering::ALL {
+            let gradient = extract_palette("deux", &image, &options(2, method, ordering));
+            let stops: Vec<_> = gradient.stops.iter().map(|s| (s.position, s.color)).collect();
+            assert_eq!(stops, vec![(0.0, (10, 20, 90)), (1.0, (250, 240, 20))], "{:?} / {:?}", method, ordering);
+        }
+    }
+}
+
+#[test]
+fn extra_colors_do_not_duplicate_stops() {
+    let colors = [(255, 0, 0), (0, 255, 0), (0, 0, 255)];
+    let image = image_from(&colors, 9, 9);
+    for method in ClusteringMethod::ALL {
+        let gradient = extract_palette("trois", &image, &options(16, method, PathOrdering::TwoOpt));
+        let mut found: Vec<_> = gradient.stops.iter().map(|s| s.color).collect();
+        found.sort();
+        assert_eq!(found, vec![(0, 0, 255), (0, 255, 0), (255, 0, 0)], "{:?}", method);
+    }
+
+    let single = image_from(&[(40, 80, 120)], 4, 4);
+    let gradient = extract_palette("une", &single, &options(8, ClusteringMethod::KMeans, PathOrdering::Hue));
+    assert_eq!(gradient.stops.len(), 2);
+    assert!(gradient.stops.iter().all(|s| s.color == (40, 80, 120)));
+}
+
+#[test]
+fn two_opt_never_lengthens_the_greedy_path() {
+    for seed in 1..=6 {
+        let image = noisy_image(seed);
+        for method in ClusteringMethod::ALL {
+            let greedy = extract_palette("x", &image, &options(12, method, PathOrdering::NearestNeighbor));
+            let improved = extract_palette("x", &image, &options(12, method, PathOrdering::TwoOpt));
+            assert_eq!(improved.stops.len(), greedy.stops.len());
+            // Les couleurs sont arrondies en RGB : petite marge.
+            assert!(
+                path_length(&improved) <= path_length(&greedy) + 1e-3,
+                "graine {} : {} > {}",
+                seed,
+                path_length(&improved),
+                path_length(&greedy)
+            );
+        }
+    }
+}
+
+#[test]
+fn lightness_ordering_is_monotonic() {
+    let gradient = extract_palette("x", &noisy_image(7), &options(10, ClusteringMethod::MedianCut, PathOrdering::Lightness));
+    let lightness: Vec<f64> = gradient.stops.iter().map(|s| rgb_to_oklab(s.color)[0]).collect();
+    assert!(lightness.windows(2).all(|w| w[0] <= w[1] + 1e-3), "{:?}", lightness);
+}
@@ -0,0 +1,147 @@
+use fractal_generator::gradient::{ColorStop, GradientScheme, Interpolation};
+use fractal_generator::palette_io::{
+    parse_ggr, parse_json, parse_map, parse_ugr, write_ggr, write_json, write_map, write_ugr, PaletteError,
+};
+
+fn sample_gradient(interpolation: Interpolation) -> GradientScheme {
+    GradientScheme::new(
+        "Coucher de soleil",
+        vec![
+            ColorStop { position: 0.0, color: (0, 0, 32) },
+            ColorStop { position: 0.25, color: (128, 0, 64) },
+            ColorStop { position: 0.5, color: (255, 96, 0) },
+            ColorStop { position: 1.0, color: (255, 255, 200) },
+        ],
+        interpolation,
+    )
+}
+
+fn parse_error_line<T>(result: Result<T, PaletteError>) -> usize {
+    match result {
+        Err(PaletteError::Parse { line, .. }) => line,
+        Err(other) => panic!("erreur inattendue : {}", other),
+        Ok(_) => panic!("une erreur d'analyse était attendue"),
+    }
+}
+
+#[test]
+fn map_round_trip() {
+    let gradient = sample_gradient(Interpolation::Linear);
+    let written = write_map(&gradient);
+    assert_eq!(written.lines().count(), 256);
+
+    let parsed = parse_map("sunset", &written).unwrap();
+    assert_eq!(parsed.stops.len(), 256);
+    assert_eq!(write_map(&parsed), written);
+    for i in 0..=20 {
+        let t = i as f64 / 20.0;
+        let (a, b) = (gradient.sample(t), parsed.sample(t));
+        assert!((a.0 as i32 - b.0 as i32).abs() <= 1, "t = {}", t);
+        assert!((a.1 as i32 - b.1 as i32).abs() <= 1, "t = {}", t);
+        assert!((a.2 as i32 - b.2 as i32).abs() <= 1, "t = {}", t);
+    }
+}
+
+#[test]
+fn map_reports_bad_line() {
+    let source = "0 0 0\n; commentaire\n255 12\n";
+    assert_eq!(parse_error_line(parse_map("x", source)), 3);
+    assert_eq!(parse_error_line(parse_map("x", "0 0 0\n1 300 2\n")), 2);
+}
+
+#[test]
+fn ggr_round_trip() {
+    let gradient = sample_gradient(Interpolation::Linear);
+    let parsed = parse_ggr(&write_ggr(&gradient)).unwrap();
+    assert_eq!(parsed, gradient);
+}
+
+#[test]
+fn ggr_keeps_hard_edges() {
+    let gradient = GradientScheme::new(
+        "Bandes",
+        vec![
+            ColorStop { position: 0.0, color: (255, 0, 0) },
+            ColorStop { position: 0.5, color: (255, 0, 0) },
+            ColorStop { position: 0.5, color: (0, 0, 255) },
+            ColorStop { position: 1.0, color: (0, 0, 255) },
+        ],
+        Interpolation::Linear,
+    );
+    let parsed = parse_ggr(&write_ggr(&gradient)).unwrap();
+    assert_eq!(parsed, gradient);
+    assert_eq!(parsed.sample(0.49), (255, 0, 0));
+    assert_eq!(parsed.sample(0.51), (0, 0, 255));
+}
+
+#[test]
+fn ggr_samples_nonlinear_segments() {
+    let source = "GIMP Gradient\nName: Courbe\n1\n0.0 0.25 1.0 0 0 0 1 1 1 1 1 1 0\n";
+    let parsed = parse_ggr(source).unwrap();
+    assert_eq!(parsed.name, "Courbe");
+    assert!(parsed.stops.len() > 2);
+    // Le point milieu à 0.25 place le gris moyen au quart du segment.
+    let (r, _, _) = parsed.sample(0.25);
+    assert!((r as i32 - 128).abs() <= 8, "r = {}", r);
+}
+
+#[test]
+fn ggr_reports_bad_line() {
+    let source = "GIMP Gradient\nName: Cassé\n2\n0 0.5 1 0 0 0 1 1 1 1 1 0 0\n0 0.5 1 0 0 zéro 1 1 1 1 1 0 0\n";
+    assert_eq!(parse_error_line(parse_ggr(source)), 5);
+    assert_eq!(parse_error_line(parse_ggr("GIMP Gradient\nName: Court\n3\n")), 4);
+    assert_eq!(parse_error_line(parse_ggr("Pas un dégradé\n")), 1);
+}
+
+#[test]
+fn ugr_round_trip() {
+    let gradients = vec![sample_gradient(Interpolation::Smooth), sample_gradient(Interpolation::Linear)];
+    let parsed = parse_ugr(&write_ugr(&gradients)).unwrap();
+    assert_eq!(parsed, gradients);
+}
+
+#[test]
+fn ugr_reads_ultra_fractal_block() {
+    let source = "\
+default {
+gradient:
+  title=\"Default\" smooth=no
+  index=0 color=16711680
+  index=200 color=255
+opacity:
+  smooth=no index=0 opacity=255
+}
+";
+    let parsed = parse_ugr(source).unwrap();
+    assert_eq!(parsed.len(), 1);
+    assert_eq!(parsed[0].name, "Default");
+    assert_eq!(parsed[0].stops[0].color, (0, 0, 255));
+    assert_eq!(parsed[0].stops[1], ColorStop { position: 0.5, color: (255, 0, 0) });
+}
+
+#[test]
+fn ugr_wraps_indices_outside_cycle() {
+    let source = "a {\ngradient:\n  index=-100 color=255\n  index=500 color=65280\n}\n";
+    let parsed = parse_ugr(source).unwrap();
+    assert_eq!(parsed[0].stops[0], ColorStop { position: 0.25, color: (0, 255, 0) });
+    assert_eq!(parsed[0].stops[1], ColorStop { position: 0.75, color: (255, 0, 0) });
+}
+
+#[test]
+fn ugr_reports_bad_line() {
+    let source = "a {\ngradient:\n  index=0 color=12\n  index=4 color=bleu\n}\n";
+    assert_eq!(parse_error_line(parse_ugr(source)), 4);
+    assert_eq!(parse_error_line(parse_ugr("a {\ngradient:\n  index=0 color=12\n")), 3);
+}
+
+#[test]
+fn json_round_trip() {
+    let gradient = sample_gradient(Interpolation::OkLab);
+    assert_eq!(parse_json(&write_json(&gradient)).unwrap(), gradient);
+}
+
+#[test]
+fn json_reports_bad_line() {
+    let source = "{\n  \"name\": \"x\",\n  \"stops\": [,\n  \"interpolation\": \"Linear\"\n}\n";
+    assert_eq!(parse_error_line(parse_json(source)), 3);
+}
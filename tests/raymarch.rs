@@ -0,0 +1,34 @@
+use fractal_generator::color_schemes::GrayscaleScheme;
+use fractal_generator::raymarch::{mandelbulb, RayMarchSettings, RayMarcher, Shape};
+
+#[test]
+fn mandelbulb_distance_is_positive_outside_its_bounding_sphere() {
+    for radius in [1.6, 2.0, 3.0] {
+        for i in 0..24 {
+            let (theta, phi) = (i as f64 * 0.13 + 0.05, i as f64 * 0.71);
+            let p = [radius * theta.sin() * phi.cos(), radius * theta.sin() * phi.sin(), radius * theta.cos()];
+            let (distance, trap) = mandelbulb(p, 8.0, 12);
+            // L'origine appartient à la forme : la borne ne peut dépasser |p|.
+            assert!(distance > 0.0 && distance <= radius, "{:?} : {}", p, distance);
+            assert!((0.0..=1.0).contains(&trap));
+        }
+    }
+}
+
+#[test]
+fn batches_run_to_completion() {
+    let settings = RayMarchSettings { shape: Shape::ALL[0], iterations: 6, max_steps: 60, ..RayMarchSettings::default() };
+    let mut marcher = RayMarcher::new(settings, (48, 40));
+    assert_eq!(marcher.progress(), 0.0);
+    let mut progress = 0.0;
+    let mut batches = 0;
+    while !marcher.is_done() {
+        marcher.run_batch();
+        assert!(marcher.progress() >= progress, "{} < {}", marcher.progress(), progress);
+        progress = marcher.progress();
+        batches += 1;
+        assert!(batches < 10_000);
+    }
+    assert_eq!(marcher.progress(), 1.0);
+    assert_eq!(marcher.image(&GrayscaleScheme).len(), 48 * 40 * 4);
+}